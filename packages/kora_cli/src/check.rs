@@ -0,0 +1,109 @@
+use std::process::ExitCode;
+
+use kora_diagnostics::{to_json, to_sarif, Diagnostic, FileId, Renderer, SourceMap};
+use kora_parser::Parser;
+
+/// How `kora check` reports diagnostics, set with `--error-format=<format>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorFormat {
+    /// Rendered with source snippets on stderr, followed by a summary.
+    Human,
+    /// One JSON object per line on stdout, see [`to_json`].
+    Json,
+    /// A single SARIF log for all the files on stdout, see [`to_sarif`].
+    Sarif,
+}
+
+/// Runs `kora check [options] <files>`: parses every file, resolves its names, checks its types
+/// and reports its diagnostics. Fails if any file can't be read or has errors.
+pub fn run(args: &[String]) -> ExitCode {
+    let mut error_format = ErrorFormat::Human;
+    let mut paths = Vec::new();
+    for arg in args {
+        if let Some(format) = arg.strip_prefix("--error-format=") {
+            error_format = match format {
+                "human" => ErrorFormat::Human,
+                "json" => ErrorFormat::Json,
+                "sarif" => ErrorFormat::Sarif,
+                _ => {
+                    eprintln!(
+                        "error: unknown error format `{format}`, expected `human`, `json` or `sarif`"
+                    );
+                    return ExitCode::FAILURE;
+                }
+            };
+        } else if arg.starts_with("--") {
+            eprintln!("error: unknown option `{arg}`");
+            return ExitCode::FAILURE;
+        } else {
+            paths.push(arg);
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("error: `kora check` expects at least one file");
+        return ExitCode::FAILURE;
+    }
+
+    let renderer = Renderer::from_env();
+    let mut source_map = SourceMap::new();
+    let mut error_count = 0;
+    let mut all_diagnostics = Vec::new();
+    for path in paths {
+        let file = match source_map.load_file(path) {
+            Ok(file) => file,
+            Err(error) => {
+                eprintln!("error: can't read `{path}`: {error}");
+                error_count += 1;
+                continue;
+            }
+        };
+
+        for diagnostic in check_file(&source_map, file) {
+            error_count += usize::from(diagnostic.is_error());
+            match error_format {
+                ErrorFormat::Human => {
+                    eprintln!("{}", renderer.render(&diagnostic, &source_map, file))
+                }
+                ErrorFormat::Json => println!("{}", to_json(&diagnostic, &source_map, file)),
+                ErrorFormat::Sarif => all_diagnostics.push((file, diagnostic)),
+            }
+        }
+    }
+
+    if error_format == ErrorFormat::Sarif {
+        println!("{}", to_sarif(&source_map, &all_diagnostics));
+    }
+    if error_format != ErrorFormat::Human {
+        return if error_count > 0 {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        };
+    }
+    if error_count > 0 {
+        let plural = if error_count == 1 { "" } else { "s" };
+        eprintln!("error: check failed with {error_count} error{plural}");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Returns the diagnostics of a file of the source map, from parsing it, resolving its names
+/// and checking its types.
+pub fn check_file(source_map: &SourceMap, file: FileId) -> Vec<Diagnostic> {
+    let (mut module, errors) = Parser::parse_module(source_map.file(file).source_code());
+    let mut diagnostics = errors.iter().map(Diagnostic::from).collect::<Vec<_>>();
+    let resolution = kora_resolve::resolve(&mut module);
+    let typing = kora_typeck::check(&module, &resolution);
+    diagnostics.extend(resolution.diagnostics);
+    diagnostics.extend(typing.diagnostics);
+    diagnostics
+}
+
+/// Returns the syntax errors of a file of the source map, without resolving its names, for code
+/// that can use names defined elsewhere, such as a line of the REPL.
+pub fn check_syntax(source_map: &SourceMap, file: FileId) -> Vec<Diagnostic> {
+    let (_, errors) = Parser::parse_module(source_map.file(file).source_code());
+    errors.iter().map(Diagnostic::from).collect()
+}
@@ -1,14 +1,58 @@
+use std::process::ExitCode;
+
+use kora_diagnostics::{Renderer, SourceMap};
 use reedline::{DefaultPrompt, Reedline, Signal};
 
-fn main() {
+mod check;
+
+const USAGE: &str = "\
+usage: kora [command]
+
+commands:
+    check [options] <files>     report the errors of the files
+    (none)                      start an interactive session
+
+check options:
+    --error-format=<format>     `human` to render diagnostics, `json` to print one JSON
+                                object per line, or `sarif` to print a SARIF log";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        None => {
+            repl();
+            ExitCode::SUCCESS
+        }
+        Some("check") => check::run(&args[1..]),
+        Some("help" | "--help" | "-h") => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Some(command) => {
+            eprintln!("error: unknown command `{command}`\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn repl() {
     let mut line_editor = Reedline::create();
     let prompt = DefaultPrompt::default();
+    let renderer = Renderer::from_env();
+    let mut source_map = SourceMap::new();
 
     loop {
         let sig = line_editor.read_line(&prompt);
         match sig {
             Ok(Signal::Success(buffer)) => {
-                println!("We processed: {}", buffer);
+                let file = source_map.add_file("<repl>", buffer.as_str());
+                let diagnostics = check::check_syntax(&source_map, file);
+                if diagnostics.is_empty() {
+                    println!("We processed: {}", buffer);
+                }
+                for diagnostic in diagnostics {
+                    eprintln!("{}", renderer.render(&diagnostic, &source_map, file));
+                }
             }
             Ok(Signal::CtrlD) => {
                 break;
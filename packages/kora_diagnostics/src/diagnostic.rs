@@ -0,0 +1,168 @@
+use std::fmt;
+
+use kora_lexer::{Severity, Span};
+
+use crate::{code::ErrorCode, source_map::FileId, suggestion::Suggestion};
+
+/// An error or warning about the source code, with the spans it is about and extra explanations.
+///
+/// ```
+/// # use kora_diagnostics::{Diagnostic, ErrorCode};
+/// # use kora_lexer::Span;
+/// let diagnostic = Diagnostic::error("mismatched types", Span::new(8, 12))
+///     .with_code(ErrorCode(300))
+///     .with_primary_message("expected `Int`, found `Str`")
+///     .with_label(Span::new(0, 3), "expected because of this annotation")
+///     .with_note("strings can't be added to numbers");
+/// assert_eq!(diagnostic.to_string(), "error[E0300]: mismatched types");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<ErrorCode>,
+    /// Short description of the problem, without the location or a trailing period.
+    pub message: String,
+    /// The code that the diagnostic is about.
+    pub primary: Label,
+    /// Other code that explains the diagnostic, such as the previous definition of a duplicate
+    /// name.
+    pub secondary: Vec<Label>,
+    /// Extra explanations that don't point to any code.
+    pub notes: Vec<String>,
+    /// Advice on how to fix the problem, such as "rename the variable".
+    pub help: Vec<String>,
+    /// Changes to the source code that fix the problem, which tools can apply.
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A span of the source code, with an optional message that is shown next to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Label {
+    pub span: Span,
+    /// File of the span, or `None` for the file that the diagnostic is about, which is given
+    /// when the diagnostic is rendered or serialized.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub file: Option<FileId>,
+    pub message: Option<String>,
+}
+
+impl Label {
+    pub fn new(span: Span) -> Self {
+        Self {
+            span,
+            file: None,
+            message: None,
+        }
+    }
+
+    pub fn with_message(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            file: None,
+            message: Some(message.into()),
+        }
+    }
+
+    /// Sets the file of the span, when it isn't the file that the diagnostic is about.
+    pub fn in_file(mut self, file: FileId) -> Self {
+        self.file = Some(file);
+        self
+    }
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            primary: Label::new(span),
+            secondary: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, message, span)
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, message, span)
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Sets the message shown next to the primary span.
+    pub fn with_primary_message(mut self, message: impl Into<String>) -> Self {
+        self.primary.message = Some(message.into());
+        self
+    }
+
+    /// Adds a secondary label.
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.secondary.push(Label::with_message(span, message));
+        self
+    }
+
+    /// Adds a secondary label about a span of another file, such as the definition of a name
+    /// imported from another module.
+    pub fn with_label_in(mut self, file: FileId, span: Span, message: impl Into<String>) -> Self {
+        self.secondary
+            .push(Label::with_message(span, message).in_file(file));
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    /// Span of the code that the diagnostic is about.
+    pub fn span(&self) -> Span {
+        self.primary.span
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Returns all labels, starting with the primary one.
+    pub fn labels(&self) -> impl Iterator<Item = &Label> {
+        std::iter::once(&self.primary).chain(&self.secondary)
+    }
+}
+
+/// Diagnostics are displayed as their header, such as `error[E0001]: unterminated string
+/// literal`. Use a [`crate::Renderer`] to also show the labels, notes and help.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match self.code {
+            Some(code) => write!(f, "{severity}[{code}]: {}", self.message),
+            None => write!(f, "{severity}: {}", self.message),
+        }
+    }
+}
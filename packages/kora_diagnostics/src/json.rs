@@ -0,0 +1,125 @@
+//! Serialization of diagnostics to JSON.
+
+use kora_lexer::{Severity, Span};
+use serde::Serialize;
+
+use crate::{
+    diagnostic::Diagnostic,
+    source_map::{FileId, SourceFile, SourceMap},
+    suggestion::Applicability,
+};
+
+/// Serializes the diagnostic about the file with the given id to a single line of JSON, for
+/// editors and CI wrappers that can't parse rendered diagnostics.
+///
+/// The object has the `severity` (`"error"` or `"warning"`), the `code` (such as `"E0001"`, or
+/// `null`), the `message`, the path of the `file`, the `spans` with the primary span first, the
+/// `notes` and `help` messages, and the `suggestions`. Every span has its `start` and `end` byte
+/// offsets, its one-based `line_start`, `column_start`, `line_end` and `column_end`, whether it
+/// `is_primary`, and the `label` shown next to it, or `null`. Spans in another file than the
+/// diagnostic also have the path of their `file`. Every suggestion has a `message`, the same
+/// offsets, lines and columns as spans, the `replacement` text, and its `applicability`
+/// (`"machine-applicable"` or `"maybe-incorrect"`). Columns are counted in code points, like in
+/// SARIF, while offsets are counted in bytes. For example:
+///
+/// ```json
+/// {"severity":"error","code":"E0001","message":"unterminated string literal","file":"main.kora",
+/// "spans":[{"start":8,"end":10,"line_start":1,"column_start":9,"line_end":1,"column_end":11,
+/// "is_primary":true,"label":"missing closing quote"}],"notes":[],"help":[],"suggestions":[]}
+/// ```
+pub fn to_json(diagnostic: &Diagnostic, source_map: &SourceMap, file_id: FileId) -> String {
+    let file = source_map.file(file_id);
+    let json = JsonDiagnostic {
+        severity: match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        code: diagnostic.code.map(|code| code.to_string()),
+        message: &diagnostic.message,
+        file: file.path(),
+        spans: diagnostic
+            .labels()
+            .enumerate()
+            .map(|(index, label)| {
+                let label_file = (label.file)
+                    .filter(|label_file| *label_file != file_id)
+                    .map(|label_file| source_map.file(label_file));
+                JsonLabel {
+                    file: label_file.map(SourceFile::path),
+                    span: JsonSpan::new(label_file.unwrap_or(file), label.span),
+                    is_primary: index == 0,
+                    label: label.message.as_deref(),
+                }
+            })
+            .collect(),
+        notes: &diagnostic.notes,
+        help: &diagnostic.help,
+        suggestions: diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| JsonSuggestion {
+                message: &suggestion.message,
+                span: JsonSpan::new(file, suggestion.span),
+                replacement: &suggestion.replacement,
+                applicability: suggestion.applicability,
+            })
+            .collect(),
+    };
+    serde_json::to_string(&json).expect("diagnostics can always be serialized")
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: &'static str,
+    code: Option<String>,
+    message: &'a str,
+    file: &'a str,
+    spans: Vec<JsonLabel<'a>>,
+    notes: &'a [String],
+    help: &'a [String],
+    suggestions: Vec<JsonSuggestion<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonSpan {
+    start: u32,
+    end: u32,
+    line_start: u32,
+    column_start: u32,
+    line_end: u32,
+    column_end: u32,
+}
+
+impl JsonSpan {
+    fn new(file: &SourceFile, span: Span) -> Self {
+        let start = file.char_position(span.start);
+        let end = file.char_position(span.end);
+        Self {
+            start: span.start,
+            end: span.end,
+            line_start: start.line + 1,
+            column_start: start.column + 1,
+            line_end: end.line + 1,
+            column_end: end.column + 1,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<&'a str>,
+    #[serde(flatten)]
+    span: JsonSpan,
+    is_primary: bool,
+    label: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonSuggestion<'a> {
+    message: &'a str,
+    #[serde(flatten)]
+    span: JsonSpan,
+    replacement: &'a str,
+    applicability: Applicability,
+}
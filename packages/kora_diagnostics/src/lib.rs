@@ -0,0 +1,26 @@
+//! Diagnostics shared by the lexer, the parser and the later passes, so that every error and
+//! warning is reported, rendered and serialized the same way.
+
+mod code;
+mod diagnostic;
+#[cfg(feature = "serde")]
+mod json;
+mod render;
+#[cfg(feature = "serde")]
+mod sarif;
+mod similar;
+mod source_map;
+mod suggestion;
+mod syntax;
+
+pub use code::ErrorCode;
+pub use diagnostic::{Diagnostic, Label};
+#[cfg(feature = "serde")]
+pub use json::to_json;
+pub use kora_lexer::Severity;
+pub use render::Renderer;
+#[cfg(feature = "serde")]
+pub use sarif::to_sarif;
+pub use similar::similar_names;
+pub use source_map::{FileId, Location, SourceFile, SourceMap};
+pub use suggestion::{apply_suggestions, Applicability, Suggestion};
@@ -0,0 +1,351 @@
+use std::{fmt::Write, io::IsTerminal};
+
+use kora_lexer::{Severity, SourcePosition, Span};
+
+use crate::{
+    diagnostic::{Diagnostic, Label},
+    source_map::{FileId, SourceFile, SourceMap},
+    suggestion::Suggestion,
+};
+
+/// Number of columns that a tab character takes when showing source code.
+const TAB_WIDTH: usize = 4;
+
+/// Renders diagnostics for terminals, with the lines of source code that they are about and
+/// markers under their labels:
+///
+/// ```text
+/// error[E0100]: expected `)`, found the end of the file
+///  --> main.kora:1:4
+///   |
+/// 1 | f(a
+///   |    ^ expected `)`
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Renderer {
+    color: bool,
+}
+
+/// Kinds of text that are colored differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Error,
+    Warning,
+    /// Gutter, line numbers and secondary labels.
+    Accent,
+    /// Markers under the code changed by a suggestion.
+    Change,
+    Bold,
+}
+
+impl Renderer {
+    /// Renders diagnostics with ANSI colors if `color` is true.
+    pub fn new(color: bool) -> Self {
+        Self { color }
+    }
+
+    /// Renders diagnostics with colors only if stderr is a terminal and the `NO_COLOR`
+    /// environment variable is unset or empty, see <https://no-color.org>.
+    pub fn from_env() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self::new(std::io::stderr().is_terminal() && !no_color)
+    }
+
+    /// Renders the diagnostic about the file with the given id. The result ends with a newline.
+    ///
+    /// Labels in other files are shown after the code of the file, under the path of their file.
+    pub fn render(
+        &self,
+        diagnostic: &Diagnostic,
+        source_map: &SourceMap,
+        file_id: FileId,
+    ) -> String {
+        let file = source_map.file(file_id);
+        // The labels of each file, starting with the file of the primary label
+        let mut files: Vec<(FileId, Vec<LabelLines>)> = Vec::new();
+        for (index, label) in diagnostic.labels().enumerate() {
+            let label_file = label.file.unwrap_or(file_id);
+            let label = LabelLines::new(label, index == 0, source_map.file(label_file));
+            match files.iter_mut().find(|(id, _)| *id == label_file) {
+                Some((_, labels)) => labels.push(label),
+                None => files.push((label_file, vec![label])),
+            }
+        }
+        let last_line = (files.iter())
+            .flat_map(|(_, labels)| labels.iter().map(|label| label.end.line))
+            .chain(
+                (diagnostic.suggestions.iter())
+                    .map(|suggestion| file.position(suggestion.span.start).line),
+            )
+            .max()
+            .unwrap_or(0);
+        let gutter = " ".repeat((last_line + 1).to_string().len());
+        let severity_style = match diagnostic.severity {
+            Severity::Error => Style::Error,
+            Severity::Warning => Style::Warning,
+        };
+
+        let mut output = String::new();
+        let header = match (diagnostic.severity, diagnostic.code) {
+            (Severity::Error, Some(code)) => format!("error[{code}]"),
+            (Severity::Error, None) => "error".to_string(),
+            (Severity::Warning, Some(code)) => format!("warning[{code}]"),
+            (Severity::Warning, None) => "warning".to_string(),
+        };
+        let _ = writeln!(
+            output,
+            "{}{}",
+            self.paint(severity_style, &header),
+            self.paint(Style::Bold, &format!(": {}", diagnostic.message))
+        );
+        for (index, (label_file, labels)) in files.iter().enumerate() {
+            let (arrow, span) = if index == 0 {
+                ("-->", diagnostic.span())
+            } else {
+                (":::", labels[0].span)
+            };
+            let _ = writeln!(
+                output,
+                "{gutter}{} {}",
+                self.paint(Style::Accent, arrow),
+                source_map.location(*label_file, span)
+            );
+            let _ = writeln!(output, "{gutter} {}", self.paint(Style::Accent, "|"));
+            self.render_lines(
+                &mut output,
+                labels,
+                source_map.file(*label_file),
+                &gutter,
+                severity_style,
+            );
+        }
+
+        if !diagnostic.notes.is_empty() || !diagnostic.help.is_empty() {
+            let _ = writeln!(output, "{gutter} {}", self.paint(Style::Accent, "|"));
+        }
+        for (kind, messages) in [("note", &diagnostic.notes), ("help", &diagnostic.help)] {
+            for message in messages {
+                let _ = writeln!(
+                    output,
+                    "{gutter} {} {}: {message}",
+                    self.paint(Style::Accent, "="),
+                    self.paint(Style::Bold, kind)
+                );
+            }
+        }
+        for suggestion in &diagnostic.suggestions {
+            self.render_suggestion(&mut output, suggestion, file, &gutter);
+        }
+        output
+    }
+
+    /// Renders the lines of the file, with markers under the labels that cover them.
+    fn render_lines(
+        &self,
+        output: &mut String,
+        labels: &[LabelLines],
+        file: &SourceFile,
+        gutter: &str,
+        severity_style: Style,
+    ) {
+        let mut previous_line = None;
+        for line in shown_lines(labels) {
+            if previous_line.is_some_and(|previous| previous + 1 < line) {
+                let _ = writeln!(output, "{}", self.paint(Style::Accent, "..."));
+            }
+            previous_line = Some(line);
+
+            let text = file.line_text(line);
+            let line_number = format!("{:>width$} |", line + 1, width = gutter.len());
+            let source_line = format!(
+                "{} {}",
+                self.paint(Style::Accent, &line_number),
+                expand_tabs(text)
+            );
+            let _ = writeln!(output, "{}", source_line.trim_end());
+
+            for label in labels.iter().filter(|label| label.covers(line)) {
+                let (start, end) = label.marked_columns(line, text);
+                let style = if label.is_primary {
+                    severity_style
+                } else {
+                    Style::Accent
+                };
+                let mut marker = (if label.is_primary { "^" } else { "-" }).repeat(end - start);
+                if let Some(message) = label.message.filter(|_| line == label.end.line) {
+                    marker.push(' ');
+                    marker.push_str(message);
+                }
+                let _ = writeln!(
+                    output,
+                    "{gutter} {} {}{}",
+                    self.paint(Style::Accent, "|"),
+                    " ".repeat(start),
+                    self.paint(style, &marker)
+                );
+            }
+        }
+    }
+
+    /// Renders the message of the suggestion, followed by the line changed by it if it only
+    /// changes one line, with markers under the new code, or under the removed code if the
+    /// suggestion only removes code.
+    fn render_suggestion(
+        &self,
+        output: &mut String,
+        suggestion: &Suggestion,
+        file: &SourceFile,
+        gutter: &str,
+    ) {
+        let _ = writeln!(
+            output,
+            "{}{}",
+            self.paint(Style::Bold, "help"),
+            self.paint(Style::Bold, &format!(": {}", suggestion.message))
+        );
+        let start = file.position(suggestion.span.start);
+        let end = file.position(suggestion.span.end);
+        if start.line != end.line || suggestion.replacement.contains('\n') {
+            return;
+        }
+
+        let text = file.line_text(start.line);
+        let start_column = (start.column as usize).min(text.len());
+        let end_column = (end.column as usize).min(text.len());
+        let (shown_text, marker, marker_width) = if suggestion.replacement.is_empty() {
+            let removed_width = display_width(&text[start_column..], end_column - start_column);
+            (text.to_string(), "-", removed_width)
+        } else {
+            let changed_text = format!(
+                "{}{}{}",
+                &text[..start_column],
+                suggestion.replacement,
+                &text[end_column..]
+            );
+            let marker = if start_column == end_column { "+" } else { "~" };
+            let replacement = &suggestion.replacement;
+            (
+                changed_text,
+                marker,
+                display_width(replacement, replacement.len()),
+            )
+        };
+
+        let line_number = format!("{:>width$} |", start.line + 1, width = gutter.len());
+        let source_line = format!(
+            "{} {}",
+            self.paint(Style::Accent, &line_number),
+            expand_tabs(&shown_text)
+        );
+        let _ = writeln!(output, "{gutter} {}", self.paint(Style::Accent, "|"));
+        let _ = writeln!(output, "{}", source_line.trim_end());
+        let _ = writeln!(
+            output,
+            "{gutter} {} {}{}",
+            self.paint(Style::Accent, "|"),
+            " ".repeat(display_width(text, start_column)),
+            self.paint(Style::Change, &marker.repeat(marker_width.max(1)))
+        );
+    }
+
+    /// Wraps the text in the ANSI escape codes of the style, if colors are enabled.
+    fn paint(&self, style: Style, text: &str) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+        let code = match style {
+            Style::Error => "1;31",
+            Style::Warning => "1;33",
+            Style::Accent => "1;34",
+            Style::Change => "1;32",
+            Style::Bold => "1",
+        };
+        format!("\x1b[{code}m{text}\x1b[0m")
+    }
+}
+
+/// A label with the positions of its first and last byte.
+struct LabelLines<'a> {
+    span: Span,
+    message: Option<&'a str>,
+    is_primary: bool,
+    start: SourcePosition,
+    /// Position right after the last byte of the span, on the line of the last byte. Empty spans
+    /// end where they start. Columns are byte offsets, like in [`SourcePosition`].
+    end: SourcePosition,
+}
+
+impl<'a> LabelLines<'a> {
+    fn new(label: &'a Label, is_primary: bool, file: &SourceFile) -> Self {
+        let start = file.position(label.span.start);
+        let end = if label.span.is_empty() {
+            start
+        } else {
+            // The last byte decides the line, so that spans ending with a newline don't end on
+            // the next line.
+            let last = file.position(label.span.end - 1);
+            SourcePosition {
+                line: last.line,
+                column: last.column + 1,
+            }
+        };
+        Self {
+            span: label.span,
+            message: label.message.as_deref(),
+            is_primary,
+            start,
+            end,
+        }
+    }
+
+    fn covers(&self, line: u32) -> bool {
+        (self.start.line..=self.end.line).contains(&line)
+    }
+
+    /// Returns the display columns of the markers under the given line of the label. At least
+    /// one column is marked, so that empty spans are visible.
+    fn marked_columns(&self, line: u32, text: &str) -> (usize, usize) {
+        let start = if line == self.start.line {
+            display_width(text, self.start.column as usize)
+        } else {
+            0
+        };
+        let end = if line == self.end.line {
+            display_width(text, self.end.column as usize)
+        } else {
+            display_width(text, text.len())
+        };
+        (start, end.max(start + 1))
+    }
+}
+
+/// Returns the lines that contain the start or the end of a label, sorted. Lines between two
+/// shown lines are shown as well, as they take as much space as the `...` replacing them.
+fn shown_lines(labels: &[LabelLines]) -> Vec<u32> {
+    let mut lines = labels
+        .iter()
+        .flat_map(|label| [label.start.line, label.end.line])
+        .collect::<Vec<_>>();
+    lines.sort_unstable();
+    lines.dedup();
+    let gaps = lines
+        .windows(2)
+        .filter(|pair| pair[0] + 2 == pair[1])
+        .map(|pair| pair[0] + 1)
+        .collect::<Vec<_>>();
+    lines.extend(gaps);
+    lines.sort_unstable();
+    lines
+}
+
+/// Number of columns taken by the first `byte_len` bytes of the text.
+fn display_width(text: &str, byte_len: usize) -> usize {
+    text.char_indices()
+        .take_while(|(index, _)| *index < byte_len)
+        .map(|(_, char)| if char == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
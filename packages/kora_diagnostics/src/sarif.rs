@@ -0,0 +1,151 @@
+//! Serialization of diagnostics to SARIF 2.1, the format of code scanning dashboards such as
+//! GitHub code scanning.
+
+use std::collections::BTreeSet;
+
+use kora_lexer::{Severity, Span};
+use serde_json::{json, Value};
+
+use crate::{
+    diagnostic::Diagnostic,
+    source_map::{FileId, SourceFile, SourceMap},
+};
+
+/// Serializes the diagnostics, each with the id of the file it is about, to a SARIF 2.1 log with
+/// a single run.
+///
+/// Every diagnostic is a result whose rule is its code, and whose location is its primary span.
+/// Secondary labels are related locations, which can be in other files, notes and help are
+/// appended to the message, and suggestions are fixes.
+/// Columns count Unicode code points, as declared by the `columnKind` of the run.
+pub fn to_sarif(source_map: &SourceMap, diagnostics: &[(FileId, Diagnostic)]) -> String {
+    let codes = diagnostics
+        .iter()
+        .filter_map(|(_, diagnostic)| diagnostic.code)
+        .collect::<BTreeSet<_>>();
+    let rules = codes
+        .iter()
+        .map(|code| json!({ "id": code.to_string() }))
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::new();
+    for (file, diagnostic) in diagnostics {
+        let file = source_map.file(*file);
+        let mut text = diagnostic.message.clone();
+        for note in &diagnostic.notes {
+            text.push_str(&format!("\nnote: {note}"));
+        }
+        for help in &diagnostic.help {
+            text.push_str(&format!("\nhelp: {help}"));
+        }
+
+        let mut result = json!({
+            "level": match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            },
+            "message": { "text": text },
+            "locations": [location(file, diagnostic.primary.span)],
+        });
+        if let Some(code) = diagnostic.code {
+            result["ruleId"] = json!(code.to_string());
+            result["ruleIndex"] = json!(codes.range(..code).count());
+        }
+        if !diagnostic.secondary.is_empty() {
+            let related_locations = diagnostic
+                .secondary
+                .iter()
+                .enumerate()
+                .map(|(index, label)| {
+                    let label_file = label.file.map_or(file, |id| source_map.file(id));
+                    let mut location = location(label_file, label.span);
+                    location["id"] = json!(index);
+                    if let Some(message) = &label.message {
+                        location["message"] = json!({ "text": message });
+                    }
+                    location
+                })
+                .collect::<Vec<_>>();
+            result["relatedLocations"] = json!(related_locations);
+        }
+        if !diagnostic.suggestions.is_empty() {
+            let fixes = diagnostic
+                .suggestions
+                .iter()
+                .map(|suggestion| {
+                    json!({
+                        "description": { "text": suggestion.message },
+                        "artifactChanges": [{
+                            "artifactLocation": { "uri": path_uri(file.path()) },
+                            "replacements": [{
+                                "deletedRegion": region(file, suggestion.span),
+                                "insertedContent": { "text": suggestion.replacement },
+                            }],
+                        }],
+                    })
+                })
+                .collect::<Vec<_>>();
+            result["fixes"] = json!(fixes);
+        }
+        results.push(result);
+    }
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "kora",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&log).expect("SARIF logs can always be serialized")
+}
+
+/// Returns the physical location of the span in the file.
+fn location(file: &SourceFile, span: Span) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": path_uri(file.path()) },
+            "region": region(file, span),
+        },
+    })
+}
+
+/// Returns the region of the span in the file, with one-based lines and columns.
+fn region(file: &SourceFile, span: Span) -> Value {
+    let (start_line, start_column) = line_and_column(file, span.start);
+    let (end_line, end_column) = line_and_column(file, span.end);
+    json!({
+        "startLine": start_line,
+        "startColumn": start_column,
+        "endLine": end_line,
+        "endColumn": end_column,
+    })
+}
+
+/// Returns the URI of the path: relative paths are relative URI references, and absolute paths are
+/// `file` URIs.
+fn path_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        format!("file:///{path}")
+    } else {
+        path
+    }
+}
+
+/// Returns the one-based line and column of the byte offset, with the column counted in code
+/// points.
+fn line_and_column(file: &SourceFile, offset: u32) -> (u32, u32) {
+    let position = file.char_position(offset);
+    (position.line + 1, position.column + 1)
+}
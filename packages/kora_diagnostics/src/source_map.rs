@@ -0,0 +1,136 @@
+use std::{fmt, io, path::Path};
+
+use kora_lexer::{LineIndex, SourcePosition, Span};
+
+/// Identifies a file of a [`SourceMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileId(u32);
+
+/// Owns the source code of every file of a program, so that spans can be resolved to paths, lines
+/// and columns.
+///
+/// ```
+/// # use kora_diagnostics::SourceMap;
+/// # use kora_lexer::Span;
+/// let mut source_map = SourceMap::new();
+/// let file = source_map.add_file("main.kora", "let a = 1\nprint(a)\n");
+/// assert_eq!(source_map.location(file, Span::new(16, 17)).to_string(), "main.kora:2:7");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+/// A file of a [`SourceMap`].
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    path: String,
+    source_code: String,
+    line_index: LineIndex,
+}
+
+/// The path and the positions of a span in a file, displayed as `path:line:column` with the
+/// one-based position of the start of the span. Columns are counted in code points, see
+/// [`SourceFile::char_position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location<'a> {
+    pub path: &'a str,
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file with the given source code, which doesn't have to exist on disk, such as the
+    /// input of a REPL.
+    pub fn add_file(&mut self, path: impl Into<String>, source_code: impl Into<String>) -> FileId {
+        let source_code = source_code.into();
+        let id = FileId(self.files.len() as u32);
+        self.files.push(SourceFile {
+            path: path.into(),
+            line_index: LineIndex::new(&source_code),
+            source_code,
+        });
+        id
+    }
+
+    /// Reads the file at the given path and adds it.
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> io::Result<FileId> {
+        let path = path.as_ref();
+        let source_code = std::fs::read_to_string(path)?;
+        Ok(self.add_file(path.to_string_lossy(), source_code))
+    }
+
+    /// Returns the file with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id belongs to another source map.
+    pub fn file(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0 as usize]
+    }
+
+    /// Returns the files in the order they were added.
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        (self.files.iter().enumerate()).map(|(index, file)| (FileId(index as u32), file))
+    }
+
+    /// Returns the path and the positions of the span in the file with the given id.
+    pub fn location(&self, id: FileId, span: Span) -> Location<'_> {
+        let file = self.file(id);
+        Location {
+            path: &file.path,
+            start: file.char_position(span.start),
+            end: file.char_position(span.end),
+        }
+    }
+}
+
+impl SourceFile {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn source_code(&self) -> &str {
+        &self.source_code
+    }
+
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    /// Returns the line/column pair of the byte offset in the file.
+    pub fn position(&self, offset: u32) -> SourcePosition {
+        self.line_index.position(offset)
+    }
+
+    /// Same as [`SourceFile::position`], but with the column counted in code points, as editors
+    /// count them, instead of bytes.
+    pub fn char_position(&self, offset: u32) -> SourcePosition {
+        let position = self.position(offset);
+        let line_start = (offset - position.column) as usize;
+        let column = (self.source_code.get(line_start..offset as usize))
+            .map_or(position.column, |text| text.chars().count() as u32);
+        SourcePosition {
+            line: position.line,
+            column,
+        }
+    }
+
+    /// Returns the text of the line without its line ending, or an empty string if the line
+    /// doesn't exist.
+    pub fn line_text(&self, line: u32) -> &str {
+        let span = self.line_index.line_span(line).unwrap_or_default();
+        self.source_code[span.start as usize..span.end as usize].trim_end_matches(['\n', '\r'])
+    }
+}
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.path, self.start)
+    }
+}
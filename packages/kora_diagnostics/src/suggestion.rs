@@ -0,0 +1,85 @@
+use kora_lexer::Span;
+
+/// A change to the source code that fixes a diagnostic, such as replacing `=` with `==`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Suggestion {
+    /// Description of the change, such as "did you mean `==`?".
+    pub message: String,
+    /// The code to replace. Empty spans insert the replacement.
+    pub span: Span,
+    /// The new code. Empty replacements remove the code of the span.
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+/// How confident a [`Suggestion`] is, which decides whether tools apply it without asking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended and can be applied automatically,
+    /// such as replacing a tab with spaces.
+    MachineApplicable,
+    /// The suggestion is likely what the user intended, but should be reviewed, such as replacing
+    /// `=` with `==` in a condition.
+    MaybeIncorrect,
+}
+
+impl Suggestion {
+    pub fn new(
+        message: impl Into<String>,
+        span: Span,
+        replacement: impl Into<String>,
+        applicability: Applicability,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            span,
+            replacement: replacement.into(),
+            applicability,
+        }
+    }
+}
+
+/// Returns the source code with the [`Applicability::MachineApplicable`] suggestions applied.
+/// Suggestions that overlap a suggestion that starts before them are skipped, so that applying
+/// the result again can fix the rest.
+///
+/// ```
+/// # use kora_diagnostics::{apply_suggestions, Applicability, Suggestion};
+/// # use kora_lexer::Span;
+/// let suggestions = [
+///     Suggestion::new("use spaces", Span::new(0, 1), "    ", Applicability::MachineApplicable),
+///     Suggestion::new("compare", Span::new(6, 7), "==", Applicability::MaybeIncorrect),
+/// ];
+/// assert_eq!(apply_suggestions("\tif a = b {}", &suggestions), "    if a = b {}");
+/// ```
+pub fn apply_suggestions<'a>(
+    source_code: &str,
+    suggestions: impl IntoIterator<Item = &'a Suggestion>,
+) -> String {
+    let mut suggestions = suggestions
+        .into_iter()
+        .filter(|suggestion| suggestion.applicability == Applicability::MachineApplicable)
+        .collect::<Vec<_>>();
+    suggestions.sort_by_key(|suggestion| (suggestion.span.start, suggestion.span.end));
+
+    let mut fixed = String::with_capacity(source_code.len());
+    let mut copied_len = 0;
+    for suggestion in suggestions {
+        let start = suggestion.span.start as usize;
+        // Insertions at the end of the previous replacement are kept, as they don't overlap it
+        if start < copied_len {
+            continue;
+        }
+        fixed.push_str(&source_code[copied_len..start]);
+        fixed.push_str(&suggestion.replacement);
+        copied_len = suggestion.span.end as usize;
+    }
+    fixed.push_str(&source_code[copied_len..]);
+    fixed
+}
@@ -0,0 +1,64 @@
+use kora_lexer::SyntaxError;
+
+use crate::{
+    code::ErrorCode,
+    diagnostic::Diagnostic,
+    suggestion::{Applicability, Suggestion},
+};
+
+impl From<&SyntaxError> for Diagnostic {
+    fn from(error: &SyntaxError) -> Self {
+        let diagnostic = Diagnostic::new(error.severity(), error.message(), error.span())
+            .with_code(ErrorCode(error.code() as u32));
+
+        match error {
+            SyntaxError::UnterminatedString { .. }
+            | SyntaxError::UnterminatedMultilineString { .. }
+            | SyntaxError::UnterminatedRawString { .. } => {
+                diagnostic.with_primary_message("missing closing quote")
+            }
+            SyntaxError::UnterminatedBlockComment { .. } => {
+                diagnostic.with_primary_message("missing closing `*/`")
+            }
+            SyntaxError::InvalidEscape {
+                span, escaped_char, ..
+            } => diagnostic.with_suggestion(Suggestion::new(
+                "use `\\\\` to include a literal backslash",
+                *span,
+                format!("\\\\{escaped_char}"),
+                Applicability::MaybeIncorrect,
+            )),
+            SyntaxError::TabCharacter { span } => diagnostic.with_suggestion(Suggestion::new(
+                "replace the tab with spaces",
+                *span,
+                "    ",
+                Applicability::MachineApplicable,
+            )),
+            SyntaxError::ByteOrderMark { span } => diagnostic.with_suggestion(Suggestion::new(
+                "remove the byte order mark",
+                *span,
+                "",
+                Applicability::MachineApplicable,
+            )),
+            SyntaxError::NonNfcIdentifier {
+                span, normalized, ..
+            } => diagnostic.with_suggestion(Suggestion::new(
+                "use the normalized identifier",
+                *span,
+                normalized.clone(),
+                Applicability::MachineApplicable,
+            )),
+            SyntaxError::BidiControlCharacter { .. } => diagnostic
+                .with_help("remove the character, or write it as a `\\u{...}` escape in strings"),
+            SyntaxError::ConfusableIdentifier { .. } => diagnostic
+                .with_note("identifiers that look the same but differ are hard to tell apart"),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<SyntaxError> for Diagnostic {
+    fn from(error: SyntaxError) -> Self {
+        Self::from(&error)
+    }
+}
@@ -0,0 +1,64 @@
+use kora_diagnostics::{Diagnostic, ErrorCode, Label, Severity};
+use kora_lexer::{Lexer, Span};
+
+#[test]
+fn test_error_code() {
+    assert_eq!(ErrorCode(12).to_string(), "E0012");
+    assert_eq!(ErrorCode(12345).to_string(), "E12345");
+    assert_eq!("E0012".parse(), Ok(ErrorCode(12)));
+    assert_eq!("E12345".parse(), Ok(ErrorCode(12345)));
+    for invalid in ["", "E", "E12", "0012", "e0012", "E00+1"] {
+        assert!(invalid.parse::<ErrorCode>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_build_diagnostic() {
+    let diagnostic = Diagnostic::warning("unused variable `a`", Span::new(4, 5))
+        .with_label(Span::new(10, 11), "overwritten here")
+        .with_note("the value is never read")
+        .with_help("remove the assignment");
+
+    assert_eq!(diagnostic.to_string(), "warning: unused variable `a`");
+    assert!(!diagnostic.is_error());
+    assert_eq!(
+        diagnostic.labels().collect::<Vec<_>>(),
+        [
+            &Label::new(Span::new(4, 5)),
+            &Label::with_message(Span::new(10, 11), "overwritten here"),
+        ]
+    );
+    assert_eq!(diagnostic.notes, ["the value is never read"]);
+    assert_eq!(diagnostic.help, ["remove the assignment"]);
+}
+
+#[test]
+fn test_syntax_errors_to_diagnostics() {
+    let (_, errors) = Lexer::tokenize("\u{feff}'ab' /* a");
+    let diagnostics = errors.iter().map(Diagnostic::from).collect::<Vec<_>>();
+
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.to_string()))
+            .collect::<Vec<_>>(),
+        [
+            (
+                Severity::Warning,
+                "warning[E0014]: unnecessary byte order mark".to_string()
+            ),
+            (
+                Severity::Error,
+                "error[E0006]: char literal must contain exactly one character".to_string()
+            ),
+            (
+                Severity::Error,
+                "error[E0011]: unterminated block comment".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        diagnostics[2].primary,
+        Label::with_message(Span::new(8, 12), "missing closing `*/`")
+    );
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "serde")]
+
+use kora_diagnostics::{to_json, Applicability, Diagnostic, SourceMap, Suggestion};
+use kora_lexer::{Lexer, Span};
+
+#[test]
+fn test_to_json() {
+    let source_code = "a\nlet b = \"c";
+    let (_, errors) = Lexer::tokenize(source_code);
+    let mut source_map = SourceMap::new();
+    let file = source_map.add_file("main.kora", source_code);
+    let diagnostic = Diagnostic::from(&errors[0])
+        .with_label(Span::new(0, 1), "previous line")
+        .with_note("a note")
+        .with_suggestion(Suggestion::new(
+            "close the string",
+            Span::new(12, 12),
+            "\"",
+            Applicability::MachineApplicable,
+        ));
+
+    assert_eq!(
+        to_json(&diagnostic, &source_map, file),
+        concat!(
+            r#"{"severity":"error","code":"E0001","message":"unterminated string literal","#,
+            r#""file":"main.kora","spans":["#,
+            r#"{"start":10,"end":12,"line_start":2,"column_start":9,"line_end":2,"column_end":11,"#,
+            r#""is_primary":true,"label":"missing closing quote"},"#,
+            r#"{"start":0,"end":1,"line_start":1,"column_start":1,"line_end":1,"column_end":2,"#,
+            r#""is_primary":false,"label":"previous line"}],"#,
+            r#""notes":["a note"],"help":[],"suggestions":["#,
+            r#"{"message":"close the string","start":12,"end":12,"line_start":2,"column_start":11,"#,
+            r#""line_end":2,"column_end":11,"replacement":"\"","applicability":"machine-applicable"}]}"#,
+        )
+    );
+}
+
+#[test]
+fn test_diagnostic_serde_round_trip() {
+    let diagnostic =
+        Diagnostic::warning("unused", Span::new(0, 1)).with_code("E0042".parse().unwrap());
+
+    let json = serde_json::to_string(&diagnostic).unwrap();
+    assert!(json.contains(r#""code":"E0042""#), "{json}");
+    assert_eq!(
+        serde_json::from_str::<Diagnostic>(&json).unwrap(),
+        diagnostic
+    );
+}
+
+#[test]
+fn test_to_json_non_ascii_columns() {
+    let source_code = "let é = \"ü";
+    let (_, errors) = Lexer::tokenize(source_code);
+    let mut source_map = SourceMap::new();
+    let file = source_map.add_file("main.kora", source_code);
+
+    let json = to_json(&Diagnostic::from(&errors[0]), &source_map, file);
+    assert!(
+        json.contains(
+            r#""start":9,"end":12,"line_start":1,"column_start":9,"line_end":1,"column_end":11"#
+        ),
+        "{json}"
+    );
+}
+
+#[test]
+fn test_to_json_label_in_other_file() {
+    let mut source_map = SourceMap::new();
+    let main = source_map.add_file("main.kora", "import util\nutil::f(1)\n");
+    let util = source_map.add_file("util.kora", "def f() {}\n");
+    let diagnostic = Diagnostic::error("wrong number of arguments", Span::new(19, 22))
+        .with_label_in(util, Span::new(4, 5), "function defined here");
+
+    let json = to_json(&diagnostic, &source_map, main);
+    assert!(
+        json.contains(r#"{"file":"util.kora","start":4,"end":5,"line_start":1,"column_start":5,"#),
+        "{json}"
+    );
+}
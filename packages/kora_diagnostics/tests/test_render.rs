@@ -0,0 +1,203 @@
+use kora_diagnostics::{Applicability, Diagnostic, ErrorCode, Renderer, SourceMap, Suggestion};
+use kora_lexer::{Lexer, Span};
+
+/// Renders the diagnostic without colors.
+fn render(diagnostic: &Diagnostic, source_code: &str) -> String {
+    let mut source_map = SourceMap::new();
+    let file = source_map.add_file("main.kora", source_code);
+    Renderer::new(false).render(diagnostic, &source_map, file)
+}
+
+#[test]
+fn test_render_syntax_errors() {
+    let source_code = "let a = \"b\n\tlet c = '\\q'\n";
+    let (_, errors) = Lexer::tokenize(source_code);
+    let rendered = errors
+        .iter()
+        .map(|error| render(&error.into(), source_code))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        rendered,
+        [
+            "\
+error[E0001]: unterminated string literal
+ --> main.kora:1:9
+  |
+1 | let a = \"b
+  |         ^^ missing closing quote
+",
+            "\
+error[E0007]: unknown escape sequence `\\q`
+ --> main.kora:2:11
+  |
+2 |     let c = '\\q'
+  |              ^^
+help: use `\\\\` to include a literal backslash
+  |
+2 |     let c = '\\\\q'
+  |              ~~~
+",
+        ]
+    );
+}
+
+#[test]
+fn test_render_labels() {
+    let source_code = "def f(a: Int) -> Int {\n    a\n}\n\n\n\nf(\"a\")\n";
+    let diagnostic = Diagnostic::error("mismatched types", Span::new(36, 39))
+        .with_code(ErrorCode(200))
+        .with_primary_message("expected `Int`, found `Str`")
+        .with_label(Span::new(6, 12), "parameter declared here")
+        .with_label(Span::new(21, 30), "in this function")
+        .with_note("arguments are never converted implicitly");
+
+    assert_eq!(
+        render(&diagnostic, source_code),
+        "\
+error[E0200]: mismatched types
+ --> main.kora:7:3
+  |
+1 | def f(a: Int) -> Int {
+  |       ------ parameter declared here
+  |                      -
+2 |     a
+  | -----
+3 | }
+  | - in this function
+...
+7 | f(\"a\")
+  |   ^^^ expected `Int`, found `Str`
+  |
+  = note: arguments are never converted implicitly
+"
+    );
+}
+
+#[test]
+fn test_render_empty_and_multiline_spans() {
+    let source_code = "f(a\n\n";
+    let diagnostic = Diagnostic::error("expected `)`", Span::new(3, 3))
+        .with_label(Span::new(1, 5), "unclosed group");
+
+    assert_eq!(
+        render(&diagnostic, source_code),
+        "\
+error: expected `)`
+ --> main.kora:1:4
+  |
+1 | f(a
+  |    ^
+  |  --
+2 |
+  | - unclosed group
+"
+    );
+}
+
+#[test]
+fn test_render_colors() {
+    let diagnostic = Diagnostic::warning("unused", Span::new(0, 1));
+    let mut source_map = SourceMap::new();
+    let file = source_map.add_file("main.kora", "a");
+
+    assert_eq!(
+        Renderer::new(true).render(&diagnostic, &source_map, file),
+        "\
+\x1b[1;33mwarning\x1b[0m\x1b[1m: unused\x1b[0m
+ \x1b[1;34m-->\x1b[0m main.kora:1:1
+  \x1b[1;34m|\x1b[0m
+\x1b[1;34m1 |\x1b[0m a
+  \x1b[1;34m|\x1b[0m \x1b[1;33m^\x1b[0m
+"
+    );
+}
+
+#[test]
+fn test_render_suggestions() {
+    let source_code = "f(a b)\n";
+    let diagnostic = Diagnostic::error("expected `,`", Span::new(4, 5))
+        .with_suggestion(Suggestion::new(
+            "add a comma",
+            Span::new(3, 3),
+            ",",
+            Applicability::MaybeIncorrect,
+        ))
+        .with_suggestion(Suggestion::new(
+            "remove the argument",
+            Span::new(3, 5),
+            "",
+            Applicability::MaybeIncorrect,
+        ))
+        .with_suggestion(Suggestion::new(
+            "split the call",
+            Span::new(3, 4),
+            "\n",
+            Applicability::MaybeIncorrect,
+        ));
+
+    assert_eq!(
+        render(&diagnostic, source_code),
+        "\
+error: expected `,`
+ --> main.kora:1:5
+  |
+1 | f(a b)
+  |     ^
+help: add a comma
+  |
+1 | f(a, b)
+  |    +
+help: remove the argument
+  |
+1 | f(a b)
+  |    --
+help: split the call
+"
+    );
+}
+
+#[test]
+fn test_render_non_ascii_columns() {
+    let source_code = "let s = \"ééé\" + 1\n";
+    let diagnostic = Diagnostic::error("mismatched types", Span::new(19, 20))
+        .with_primary_message("expected `Str`, found `Int`");
+
+    // The column of the header is the column of the marker, in code points like JSON and SARIF
+    assert_eq!(
+        render(&diagnostic, source_code),
+        "\
+error: mismatched types
+ --> main.kora:1:17
+  |
+1 | let s = \"ééé\" + 1
+  |                 ^ expected `Str`, found `Int`
+"
+    );
+}
+
+#[test]
+fn test_render_label_in_other_file() {
+    let mut source_map = SourceMap::new();
+    let main = source_map.add_file("main.kora", "import util\nutil::f(1)\n");
+    let util = source_map.add_file("util.kora", "def f() {}\n");
+    let diagnostic = Diagnostic::error("wrong number of arguments", Span::new(19, 22))
+        .with_code(ErrorCode(301))
+        .with_primary_message("expected 0 arguments")
+        .with_label_in(util, Span::new(4, 5), "function defined here");
+
+    assert_eq!(
+        Renderer::new(false).render(&diagnostic, &source_map, main),
+        "\
+error[E0301]: wrong number of arguments
+ --> main.kora:2:8
+  |
+2 | util::f(1)
+  |        ^^^ expected 0 arguments
+ ::: util.kora:1:5
+  |
+1 | def f() {}
+  |     - function defined here
+"
+    );
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "serde")]
+
+use kora_diagnostics::{to_sarif, Applicability, Diagnostic, ErrorCode, SourceMap, Suggestion};
+use kora_lexer::Span;
+use serde_json::{json, Value};
+
+#[test]
+fn test_to_sarif() {
+    let mut source_map = SourceMap::new();
+    let main = source_map.add_file("src\\main.kora", "e\nf(\"é\" + é)");
+    let other = source_map.add_file("/home/other.kora", "a");
+    let diagnostics = [
+        (
+            main,
+            Diagnostic::error("unknown name `é`", Span::new(11, 13))
+                .with_code(ErrorCode(300))
+                .with_label(Span::new(0, 1), "similar name")
+                .with_help("check the spelling"),
+        ),
+        (
+            other,
+            Diagnostic::warning("unused variable", Span::new(0, 1))
+                .with_code(ErrorCode(12))
+                .with_suggestion(Suggestion::new(
+                    "prefix it with an underscore",
+                    Span::new(0, 0),
+                    "_",
+                    Applicability::MachineApplicable,
+                )),
+        ),
+    ];
+    let sarif = to_sarif(&source_map, &diagnostics);
+    let log = serde_json::from_str::<Value>(&sarif).unwrap();
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "kora");
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        json!([{ "id": "E0012" }, { "id": "E0300" }])
+    );
+    assert_eq!(
+        run["results"],
+        json!([
+            {
+                "ruleId": "E0300",
+                "ruleIndex": 1,
+                "level": "error",
+                "message": { "text": "unknown name `é`\nhelp: check the spelling" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/main.kora" },
+                        "region": { "startLine": 2, "startColumn": 9, "endLine": 2, "endColumn": 10 },
+                    },
+                }],
+                "relatedLocations": [{
+                    "id": 0,
+                    "message": { "text": "similar name" },
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/main.kora" },
+                        "region": { "startLine": 1, "startColumn": 1, "endLine": 1, "endColumn": 2 },
+                    },
+                }],
+            },
+            {
+                "ruleId": "E0012",
+                "ruleIndex": 0,
+                "level": "warning",
+                "message": { "text": "unused variable" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "file:///home/other.kora" },
+                        "region": { "startLine": 1, "startColumn": 1, "endLine": 1, "endColumn": 2 },
+                    },
+                }],
+                "fixes": [{
+                    "description": { "text": "prefix it with an underscore" },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": "file:///home/other.kora" },
+                        "replacements": [{
+                            "deletedRegion": { "startLine": 1, "startColumn": 1, "endLine": 1, "endColumn": 1 },
+                            "insertedContent": { "text": "_" },
+                        }],
+                    }],
+                }],
+            },
+        ])
+    );
+}
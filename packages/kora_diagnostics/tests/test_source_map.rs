@@ -0,0 +1,54 @@
+use std::io::Write;
+
+use kora_diagnostics::{Location, SourceMap};
+use kora_lexer::{SourcePosition, Span};
+
+#[test]
+fn test_source_map() {
+    let mut source_map = SourceMap::new();
+    let main = source_map.add_file("main.kora", "import util\nutil::f()\n");
+    let util = source_map.add_file("util.kora", "def f() {\r\n    g()\r\n}\r\n");
+
+    assert_ne!(main, util);
+    assert_eq!(
+        source_map.location(util, Span::new(15, 18)),
+        Location {
+            path: "util.kora",
+            start: SourcePosition { line: 1, column: 4 },
+            end: SourcePosition { line: 1, column: 7 },
+        }
+    );
+    assert_eq!(
+        source_map.location(main, Span::new(12, 16)).to_string(),
+        "main.kora:2:1"
+    );
+
+    let file = source_map.file(util);
+    assert_eq!(file.path(), "util.kora");
+    assert_eq!(file.line_text(1), "    g()");
+    assert_eq!(file.line_text(10), "");
+    assert_eq!(
+        source_map
+            .files()
+            .map(|(id, file)| (id, file.path()))
+            .collect::<Vec<_>>(),
+        [(main, "main.kora"), (util, "util.kora")]
+    );
+}
+
+#[test]
+fn test_load_file() {
+    let path = std::env::temp_dir().join("kora_test_load_file.kora");
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(b"let a = 1\n")
+        .unwrap();
+
+    let mut source_map = SourceMap::new();
+    let file = source_map.load_file(&path).unwrap();
+    assert_eq!(source_map.file(file).source_code(), "let a = 1\n");
+    assert_eq!(source_map.file(file).path(), path.to_string_lossy());
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(source_map.load_file(&path).is_err());
+}
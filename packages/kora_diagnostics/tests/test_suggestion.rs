@@ -0,0 +1,56 @@
+use kora_diagnostics::{apply_suggestions, similar_names, Applicability, Diagnostic, Suggestion};
+use kora_lexer::{Lexer, Span};
+
+#[test]
+fn test_apply_suggestions() {
+    let suggestion = |start, end, replacement: &str| {
+        Suggestion::new(
+            "",
+            Span::new(start, end),
+            replacement,
+            Applicability::MachineApplicable,
+        )
+    };
+    let suggestions = [
+        suggestion(8, 9, "c"),
+        suggestion(0, 3, "let"),
+        // Overlaps the previous suggestion
+        suggestion(2, 5, "x"),
+        // Inserted right after the previous suggestion
+        suggestion(3, 3, " mut"),
+        Suggestion::new("", Span::new(4, 5), "b", Applicability::MaybeIncorrect),
+    ];
+
+    assert_eq!(
+        apply_suggestions("var a = b", &suggestions),
+        "let mut a = c"
+    );
+    assert_eq!(apply_suggestions("var a = b", []), "var a = b");
+}
+
+#[test]
+fn test_fix_syntax_errors() {
+    let source_code = "\u{feff}def f() {\n\t\tlet e\u{301} = 1\n}\n";
+    let mut lexer = Lexer::builder().forbid_tabs(true).build(source_code);
+    lexer.by_ref().for_each(drop);
+    let errors = lexer.finish();
+    let diagnostics = errors.iter().map(Diagnostic::from).collect::<Vec<_>>();
+
+    let fixed = apply_suggestions(
+        source_code,
+        diagnostics
+            .iter()
+            .flat_map(|diagnostic| &diagnostic.suggestions),
+    );
+    assert_eq!(fixed, "def f() {\n        let \u{e9} = 1\n}\n");
+}
+
+#[test]
+fn test_similar_names() {
+    let names = ["count", "amount", "counter", "total", "cnt", "count"];
+
+    assert_eq!(similar_names("cuont", names), ["count"]);
+    assert_eq!(similar_names("countr", names), ["count", "counter"]);
+    assert_eq!(similar_names("ab", ["a", "abc", "xy"]), ["a", "abc"]);
+    assert_eq!(similar_names("total", ["x", "y"]), Vec::<&str>::new());
+}
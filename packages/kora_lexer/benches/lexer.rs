@@ -0,0 +1,57 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use kora_lexer::Lexer;
+
+/// Source code that is mostly operators and punctuation.
+fn operator_heavy(lines: usize) -> String {
+    "a+=b**2-(c<<=3)>>d&&e||!f;g[h]..=i::j->k=>l%m^n|o&p~q?\n".repeat(lines)
+}
+
+/// Source code that is mostly identifiers and keywords.
+fn identifier_heavy(lines: usize) -> String {
+    "let mut total_count = previous_total match value in items return continue_with_value\n"
+        .repeat(lines)
+}
+
+/// Source code that is mostly comments and indentation.
+fn comment_heavy(lines: usize) -> String {
+    "        // This comment explains what the next line of code does, at length.\n\
+     /* A block comment /* with a nested one */ in the middle */ a\n"
+        .repeat(lines)
+}
+
+/// Realistic source code mixing every kind of token.
+fn mixed(lines: usize) -> String {
+    r##"/// Adds two numbers.
+def add(a: Int, b: Int) -> Int {
+    let result = a + b * 0x7d1 - 3.14e-2
+    // Print the result
+    print(f"{a} + {b} = {result}", 'c', r#"raw"#)
+    return result
+}
+"##
+    .repeat(lines / 7)
+}
+
+fn bench_corpora(criterion: &mut Criterion) {
+    let corpora = [
+        ("operator_heavy", operator_heavy(10_000)),
+        ("identifier_heavy", identifier_heavy(10_000)),
+        ("comment_heavy", comment_heavy(10_000)),
+        ("mixed", mixed(10_000)),
+        ("mixed_large", mixed(200_000)),
+    ];
+
+    let mut group = criterion.benchmark_group("tokenize");
+    for (name, source_code) in &corpora {
+        group.throughput(Throughput::Bytes(source_code.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            source_code,
+            |b, source| b.iter(|| Lexer::new(source).count()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_corpora);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+//! Feeds arbitrary bytes to the lexer, checking that it never panics, always terminates and
+//! always produces tokens that cover the source code without splitting characters.
+//!
+//! Run with `cargo +nightly fuzz run lex` from `packages/kora_lexer`.
+
+#![no_main]
+
+use std::ops::Range;
+
+use kora_lexer::{Lexer, StreamingLexer};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Invalid UTF-8 must be reported by the streaming lexer instead of panicking
+    let streamed = StreamingLexer::new(data).collect::<Result<Vec<_>, _>>();
+
+    // Near-UTF-8 input is still lexed, with the invalid bytes replaced
+    let source_code = String::from_utf8_lossy(data);
+    let (tokens, errors) = Lexer::tokenize(&source_code);
+
+    // Every token is non-empty, so the lexer always makes progress and terminates, and the
+    // tokens are contiguous and match their spans, which can only be sliced at char boundaries
+    let mut offset = 0;
+    for token in &tokens {
+        assert!(!token.text.is_empty(), "empty token {token}");
+        assert_eq!(token.span.start as usize, offset);
+        assert_eq!(&source_code[Range::from(token.span)], token.text);
+        offset = token.span.end as usize;
+    }
+    assert_eq!(offset, source_code.len());
+
+    for error in &errors {
+        let span = error.span();
+        assert!(span.start <= span.end && span.end as usize <= source_code.len());
+    }
+
+    // Valid UTF-8 is lexed losslessly by the streaming lexer too
+    if let Ok(streamed) = streamed {
+        let streamed_text = streamed
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect::<String>();
+        assert_eq!(streamed_text, source_code);
+    }
+});
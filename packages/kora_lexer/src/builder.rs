@@ -0,0 +1,94 @@
+use alloc::collections::BTreeMap;
+
+use crate::prelude::*;
+use crate::{lexer::Lexer, token::TokenKind};
+
+/// Options that change how the lexer tokenizes the source code. See [`LexerBuilder`].
+#[derive(Debug, Clone)]
+pub(crate) struct LexerOptions {
+    /// Whether whitespace and comment tokens are skipped.
+    pub(crate) skip_trivia: bool,
+    /// Whether `//` comments include the newline that ends them.
+    pub(crate) comment_includes_newline: bool,
+    /// Whether a [`TokenKind::Eof`] token is returned at the end of the source code.
+    pub(crate) emit_eof: bool,
+    /// Whether tab characters in whitespace are reported as syntax errors.
+    pub(crate) forbid_tabs: bool,
+    /// Words whose token kind differs from the default keyword table. `None` means that the word
+    /// is an identifier.
+    pub(crate) keyword_overrides: BTreeMap<String, Option<TokenKind>>,
+}
+
+impl Default for LexerOptions {
+    fn default() -> Self {
+        Self {
+            skip_trivia: false,
+            comment_includes_newline: true,
+            emit_eof: false,
+            forbid_tabs: false,
+            keyword_overrides: BTreeMap::new(),
+        }
+    }
+}
+
+/// Builder to create a [`Lexer`] with non-default options.
+///
+/// ```
+/// # use kora_lexer::Lexer;
+/// let lexer = Lexer::builder().skip_trivia(true).build("def add");
+/// assert_eq!(lexer.count(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LexerBuilder {
+    options: LexerOptions,
+}
+
+impl LexerBuilder {
+    /// Whether whitespace and comment tokens are skipped, which is useful for parsers that don't
+    /// care about them. Doc comments are never skipped. Disabled by default.
+    pub fn skip_trivia(mut self, skip_trivia: bool) -> Self {
+        self.options.skip_trivia = skip_trivia;
+        self
+    }
+
+    /// Whether `//` comments include the newline that ends them. When disabled, the newline is
+    /// part of the whitespace that follows the comment. Enabled by default.
+    pub fn comment_includes_newline(mut self, comment_includes_newline: bool) -> Self {
+        self.options.comment_includes_newline = comment_includes_newline;
+        self
+    }
+
+    /// Whether a [`TokenKind::Eof`] token is returned at the end of the source code, so parsers
+    /// don't have to special-case running out of tokens. Disabled by default.
+    pub fn emit_eof(mut self, emit_eof: bool) -> Self {
+        self.options.emit_eof = emit_eof;
+        self
+    }
+
+    /// Whether tab characters in whitespace are reported as syntax errors, for projects that
+    /// only indent with spaces. Disabled by default.
+    pub fn forbid_tabs(mut self, forbid_tabs: bool) -> Self {
+        self.options.forbid_tabs = forbid_tabs;
+        self
+    }
+
+    /// Lexes `word` as a token of the given kind, adding a keyword or changing the kind of an
+    /// existing one.
+    pub fn keyword(mut self, word: impl Into<String>, kind: TokenKind) -> Self {
+        self.options
+            .keyword_overrides
+            .insert(word.into(), Some(kind));
+        self
+    }
+
+    /// Lexes `word` as an identifier even if it is a keyword, such as when embedding Kora in a
+    /// host that reserves fewer words.
+    pub fn remove_keyword(mut self, word: impl Into<String>) -> Self {
+        self.options.keyword_overrides.insert(word.into(), None);
+        self
+    }
+
+    pub fn build(self, source_code: &str) -> Lexer<'_> {
+        Lexer::with_options(source_code, self.options)
+    }
+}
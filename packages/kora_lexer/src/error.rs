@@ -1,6 +1,289 @@
-#[derive(Debug)]
-pub struct SyntaxError {
-    code: i32,
-    message: String,
-    file_name: String,
+use core::fmt;
+
+use crate::prelude::*;
+use crate::span::Span;
+
+/// How serious a [`SyntaxError`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    /// The source code is invalid.
+    Error,
+    /// The source code is valid but likely contains a mistake, like a lint.
+    Warning,
+}
+
+/// An error found while tokenizing the source code. Every error carries the byte range of the
+/// offending code in the source code, see [`SyntaxError::span`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyntaxError {
+    /// A string literal is missing its closing quote.
+    UnterminatedString { span: Span },
+    /// A multiline string literal is missing its closing `"""`.
+    UnterminatedMultilineString { span: Span },
+    /// A raw string literal is missing its closing quote and hashes.
+    UnterminatedRawString { span: Span },
+    /// A number literal contains a digit that is not valid in its base, such as `0b2`.
+    InvalidDigitForBase { span: Span, digit: char, radix: u32 },
+    /// A prefixed number literal has no digits after the prefix, such as `0x`.
+    MissingDigits { span: Span, radix: u32 },
+    /// A char literal is missing its closing quote.
+    UnterminatedCharLiteral { span: Span },
+    /// A char literal contains no character, such as `''`.
+    EmptyCharLiteral { span: Span },
+    /// A char literal contains more than one character, such as `'ab'`.
+    CharLiteralTooLong { span: Span },
+    /// A string or char literal contains an unknown escape sequence, such as `\q`.
+    InvalidEscape { span: Span, escaped_char: char },
+    /// A unicode escape sequence is malformed or doesn't encode a valid character, such as
+    /// `\u{D800}`.
+    InvalidUnicodeEscape {
+        span: Span,
+        problem: UnicodeEscapeProblem,
+    },
+    /// An interpolated string contains a `{` without its closing `}`, such as `f"{name"`.
+    UnterminatedInterpolation { span: Span },
+    /// An interpolated string contains a `}` that doesn't close an interpolation, such as
+    /// `f"a}"`.
+    UnmatchedClosingBrace { span: Span },
+    /// A block comment is missing its closing `*/`.
+    UnterminatedBlockComment { span: Span },
+    /// A run of characters that can't start any token, such as `€`.
+    IllegalCharacter { span: Span, text: String },
+    /// A tab character in whitespace, when tabs are forbidden with
+    /// [`crate::LexerBuilder::forbid_tabs`].
+    TabCharacter { span: Span },
+    /// The source code starts with a UTF-8 byte order mark, which is ignored. Reported as a
+    /// warning.
+    ByteOrderMark { span: Span },
+    /// A Unicode bidirectional control character, which can make code display differently from
+    /// how it is lexed.
+    BidiControlCharacter { span: Span, char: char },
+    /// An identifier contains a character that looks like an ASCII letter, such as the Cyrillic
+    /// `а`. Reported as a warning.
+    ConfusableIdentifier {
+        span: Span,
+        char: char,
+        ascii_char: char,
+    },
+    /// An identifier is not in Unicode Normalization Form C, so it may look the same as a
+    /// differently encoded identifier. Reported as a warning.
+    NonNfcIdentifier {
+        span: Span,
+        identifier: String,
+        normalized: String,
+    },
+}
+
+/// Why a unicode escape sequence is invalid. See [`SyntaxError::InvalidUnicodeEscape`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnicodeEscapeProblem {
+    /// The `\u` is not followed by `{`.
+    MissingOpeningBrace,
+    /// The escape is missing its closing `}`.
+    Unterminated,
+    /// The escape doesn't contain between 1 and 6 hexadecimal digits.
+    InvalidDigits,
+    /// The digits don't encode a valid character, such as a surrogate.
+    InvalidCharacter { digits: String },
+}
+
+/// Maximum number of characters of a run of illegal characters that are shown in its message.
+const MAX_ILLEGAL_CHARS_IN_MESSAGE: usize = 16;
+
+impl SyntaxError {
+    /// Stable numeric code of the error, such as `1` for unterminated strings.
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::UnterminatedString { .. }
+            | Self::UnterminatedMultilineString { .. }
+            | Self::UnterminatedRawString { .. } => 1,
+            Self::InvalidDigitForBase { .. } => 2,
+            Self::MissingDigits { .. } => 3,
+            Self::UnterminatedCharLiteral { .. } => 4,
+            Self::EmptyCharLiteral { .. } => 5,
+            Self::CharLiteralTooLong { .. } => 6,
+            Self::InvalidEscape { .. } => 7,
+            Self::InvalidUnicodeEscape { .. } => 8,
+            Self::UnterminatedInterpolation { .. } => 9,
+            Self::UnmatchedClosingBrace { .. } => 10,
+            Self::UnterminatedBlockComment { .. } => 11,
+            Self::IllegalCharacter { .. } => 12,
+            Self::TabCharacter { .. } => 13,
+            Self::ByteOrderMark { .. } => 14,
+            Self::BidiControlCharacter { .. } => 15,
+            Self::ConfusableIdentifier { .. } => 16,
+            Self::NonNfcIdentifier { .. } => 17,
+        }
+    }
+
+    /// Human-readable description of the error, same as its [`fmt::Display`] output.
+    pub fn message(&self) -> String {
+        self.to_string()
+    }
+
+    /// Byte range of the offending code in the source code.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::UnterminatedString { span }
+            | Self::UnterminatedMultilineString { span }
+            | Self::UnterminatedRawString { span }
+            | Self::InvalidDigitForBase { span, .. }
+            | Self::MissingDigits { span, .. }
+            | Self::UnterminatedCharLiteral { span }
+            | Self::EmptyCharLiteral { span }
+            | Self::CharLiteralTooLong { span }
+            | Self::InvalidEscape { span, .. }
+            | Self::InvalidUnicodeEscape { span, .. }
+            | Self::UnterminatedInterpolation { span }
+            | Self::UnmatchedClosingBrace { span }
+            | Self::UnterminatedBlockComment { span }
+            | Self::IllegalCharacter { span, .. }
+            | Self::TabCharacter { span }
+            | Self::ByteOrderMark { span }
+            | Self::BidiControlCharacter { span, .. }
+            | Self::ConfusableIdentifier { span, .. }
+            | Self::NonNfcIdentifier { span, .. } => *span,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::ByteOrderMark { .. }
+            | Self::ConfusableIdentifier { .. }
+            | Self::NonNfcIdentifier { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Returns the same error with its span moved by `delta` bytes.
+    pub fn moved_by(&self, delta: i64) -> Self {
+        let mut error = self.clone();
+        let span = match &mut error {
+            Self::UnterminatedString { span }
+            | Self::UnterminatedMultilineString { span }
+            | Self::UnterminatedRawString { span }
+            | Self::InvalidDigitForBase { span, .. }
+            | Self::MissingDigits { span, .. }
+            | Self::UnterminatedCharLiteral { span }
+            | Self::EmptyCharLiteral { span }
+            | Self::CharLiteralTooLong { span }
+            | Self::InvalidEscape { span, .. }
+            | Self::InvalidUnicodeEscape { span, .. }
+            | Self::UnterminatedInterpolation { span }
+            | Self::UnmatchedClosingBrace { span }
+            | Self::UnterminatedBlockComment { span }
+            | Self::IllegalCharacter { span, .. }
+            | Self::TabCharacter { span }
+            | Self::ByteOrderMark { span }
+            | Self::BidiControlCharacter { span, .. }
+            | Self::ConfusableIdentifier { span, .. }
+            | Self::NonNfcIdentifier { span, .. } => span,
+        };
+        *span = Span::new(
+            (span.start as i64 + delta) as u32,
+            (span.end as i64 + delta) as u32,
+        );
+        error
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnterminatedString { .. } => write!(f, "unterminated string literal"),
+            Self::UnterminatedMultilineString { .. } => {
+                write!(f, "unterminated multiline string literal")
+            }
+            Self::UnterminatedRawString { .. } => write!(f, "unterminated raw string literal"),
+            Self::InvalidDigitForBase { digit, radix, .. } => {
+                write!(f, "invalid digit `{digit}` in {} literal", base_name(*radix))
+            }
+            Self::MissingDigits { radix, .. } => {
+                write!(f, "missing digits after the {} prefix", base_name(*radix))
+            }
+            Self::UnterminatedCharLiteral { .. } => write!(f, "unterminated char literal"),
+            Self::EmptyCharLiteral { .. } => write!(f, "empty char literal"),
+            Self::CharLiteralTooLong { .. } => {
+                write!(f, "char literal must contain exactly one character")
+            }
+            Self::InvalidEscape { escaped_char, .. } => {
+                write!(f, "unknown escape sequence `\\{escaped_char}`")
+            }
+            Self::InvalidUnicodeEscape { problem, .. } => match problem {
+                UnicodeEscapeProblem::MissingOpeningBrace => write!(f, "expected `{{` after `\\u`"),
+                UnicodeEscapeProblem::Unterminated => write!(f, "unterminated unicode escape"),
+                UnicodeEscapeProblem::InvalidDigits => write!(
+                    f,
+                    "unicode escape must contain between 1 and 6 hexadecimal digits"
+                ),
+                UnicodeEscapeProblem::InvalidCharacter { digits } => {
+                    write!(f, "`{digits}` is not a valid unicode character")
+                }
+            },
+            Self::UnterminatedInterpolation { .. } => {
+                write!(f, "unterminated interpolation, expected `}}`")
+            }
+            Self::UnmatchedClosingBrace { .. } => write!(
+                f,
+                "unmatched `}}` in interpolated string, use `}}}}` to include a literal `}}`"
+            ),
+            Self::UnterminatedBlockComment { .. } => write!(f, "unterminated block comment"),
+            Self::IllegalCharacter { text, .. } => match text.chars().count() {
+                1 => write!(f, "illegal character `{}`", text.escape_debug()),
+                count if count <= MAX_ILLEGAL_CHARS_IN_MESSAGE => {
+                    write!(f, "{count} illegal characters `{}`", text.escape_debug())
+                }
+                count => {
+                    let shown_width = text
+                        .char_indices()
+                        .nth(MAX_ILLEGAL_CHARS_IN_MESSAGE)
+                        .map_or(text.len(), |(index, _)| index);
+                    write!(
+                        f,
+                        "{count} illegal characters `{}…`",
+                        text[..shown_width].escape_debug()
+                    )
+                }
+            },
+            Self::TabCharacter { .. } => {
+                write!(f, "tab characters are not allowed, use spaces instead")
+            }
+            Self::ByteOrderMark { .. } => write!(f, "unnecessary byte order mark"),
+            Self::BidiControlCharacter { char, .. } => write!(
+                f,
+                "unicode bidirectional control character U+{:04X} can make code display differently from how it is read",
+                *char as u32
+            ),
+            Self::ConfusableIdentifier {
+                char, ascii_char, ..
+            } => write!(
+                f,
+                "identifier contains `{char}` (U+{:04X}), which looks like `{ascii_char}`",
+                *char as u32
+            ),
+            Self::NonNfcIdentifier {
+                identifier,
+                normalized,
+                ..
+            } => write!(
+                f,
+                "identifier `{identifier}` is not in normalization form C, write it as `{normalized}`"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for SyntaxError {}
+
+/// Name of the base of a number literal with the given radix, used in messages.
+fn base_name(radix: u32) -> &'static str {
+    match radix {
+        2 => "binary",
+        8 => "octal",
+        16 => "hexadecimal",
+        _ => "decimal",
+    }
 }
@@ -0,0 +1,41 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rayon::prelude::*;
+
+use crate::{error::SyntaxError, lexer::Lexer, token::RawToken};
+
+/// The tokens of a file tokenized with [`tokenize_files`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTokens {
+    pub path: PathBuf,
+    pub source_code: String,
+    /// Compact tokens, whose text can be resolved with [`RawToken::text`] and the source code.
+    pub tokens: Vec<RawToken>,
+    pub errors: Vec<SyntaxError>,
+}
+
+/// Reads and tokenizes the files in parallel, returning the tokens and syntax errors of each file
+/// in the same order as the paths.
+/// Files that can't be read are returned as errors that include their path.
+pub fn tokenize_files<P: AsRef<Path> + Sync>(paths: &[P]) -> Vec<io::Result<FileTokens>> {
+    paths
+        .par_iter()
+        .map(|path| tokenize_file(path.as_ref()))
+        .collect()
+}
+
+fn tokenize_file(path: &Path) -> io::Result<FileTokens> {
+    let source_code = fs::read_to_string(path)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {error}", path.display())))?;
+    let (tokens, errors) = Lexer::tokenize_raw(&source_code);
+
+    Ok(FileTokens {
+        path: path.to_path_buf(),
+        source_code,
+        tokens,
+        errors,
+    })
+}
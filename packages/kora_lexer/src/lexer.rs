@@ -1,10 +1,29 @@
+use memchr::memchr;
 use unicode_ident::{is_xid_continue, is_xid_start};
+use unicode_normalization::{is_nfc, UnicodeNormalization};
 
+use crate::prelude::*;
 use crate::{
-    error::SyntaxError,
-    token::{Token, TokenKind},
+    builder::{LexerBuilder, LexerOptions},
+    error::{SyntaxError, UnicodeEscapeProblem},
+    literal::{interpolation_width, scan_interpolations},
+    span::Span,
+    token::{RawToken, Token, TokenKind},
+    unicode::{confusable_ascii, is_bidi_control},
 };
 
+/// Prefixes that can precede the opening `"` of a string literal.
+const STRING_PREFIXES: [&str; 1] = ["f"];
+
+/// An opaque marker of a position in the source code, used to rewind the lexer.
+/// See [`Lexer::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    offset: usize,
+    error_count: usize,
+    has_emitted_eof: bool,
+}
+
 pub struct Lexer<'source> {
     /// Original, unmodified source code.
     /// This is used to calculate the current position in the source code.
@@ -16,36 +35,263 @@ pub struct Lexer<'source> {
 
     /// Syntax errors.
     errors: Vec<SyntaxError>,
+
+    /// Options that change how the source code is tokenized.
+    options: LexerOptions,
+
+    /// Whether the [`TokenKind::Eof`] token has been returned.
+    has_emitted_eof: bool,
+
+    /// Byte offset of the source code in its file, which is not 0 when lexing a later part of a
+    /// stream. Byte order marks and shebangs are only recognized at the start of the file.
+    file_offset: usize,
+    /// Whether the code of the file before the source code is a byte order mark, which a
+    /// shebang can follow.
+    follows_byte_order_mark: bool,
 }
 
 impl<'source> Lexer<'source> {
     pub fn new(source_code: &'source str) -> Self {
+        Self::with_options(source_code, LexerOptions::default())
+    }
+
+    /// Returns a builder to create a lexer with non-default options.
+    pub fn builder() -> LexerBuilder {
+        LexerBuilder::default()
+    }
+
+    pub(crate) fn with_options(source_code: &'source str, options: LexerOptions) -> Self {
         Self {
             original_source_code: source_code,
             source_code,
             errors: Vec::new(),
+            options,
+            has_emitted_eof: false,
+            file_offset: 0,
+            follows_byte_order_mark: false,
         }
     }
 
+    /// Creates a lexer for the part of a file that starts at the given byte offset, such as the
+    /// buffer of the streaming lexer. Spans stay relative to the start of the part.
+    pub(crate) fn continuing_at(
+        source_code: &'source str,
+        file_offset: usize,
+        follows_byte_order_mark: bool,
+    ) -> Self {
+        let mut lexer = Self::new(source_code);
+        lexer.file_offset = file_offset;
+        lexer.follows_byte_order_mark = follows_byte_order_mark;
+        lexer
+    }
+
+    /// Creates a lexer that starts lexing at the given byte offset of the source code.
+    pub(crate) fn starting_at(source_code: &'source str, offset: usize) -> Self {
+        let mut lexer = Self::new(source_code);
+        lexer.source_code = &source_code[offset..];
+        lexer
+    }
+
+    /// Tokenizes the whole source code at once, returning every token, including trivia, and the
+    /// syntax errors found.
+    ///
+    /// Lexing is lossless: concatenating the text of every token reproduces the source code byte
+    /// for byte, even if it contains errors.
+    pub fn tokenize(source_code: &'source str) -> (Vec<Token<'source>>, Vec<SyntaxError>) {
+        let mut lexer = Self::new(source_code);
+        let tokens = lexer.by_ref().collect();
+        (tokens, lexer.finish())
+    }
+
+    /// Same as [`Lexer::tokenize`], but without whitespace and comment tokens.
+    /// Doc comments are kept because they are meaningful to the code that follows them.
+    pub fn tokenize_without_trivia(
+        source_code: &'source str,
+    ) -> (Vec<Token<'source>>, Vec<SyntaxError>) {
+        let mut lexer = Self::builder().skip_trivia(true).build(source_code);
+        let tokens = lexer.by_ref().collect();
+        (tokens, lexer.finish())
+    }
+
+    /// Same as [`Lexer::tokenize`], but returns compact tokens that don't borrow the source code.
+    pub fn tokenize_raw(source_code: &str) -> (Vec<RawToken>, Vec<SyntaxError>) {
+        let mut lexer = Lexer::new(source_code);
+        let tokens = lexer.by_ref().map(RawToken::from).collect();
+        (tokens, lexer.finish())
+    }
+
+    /// Syntax errors found so far.
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+
+    /// Consumes the lexer, returning the syntax errors found.
+    /// This is usually called after all the tokens have been consumed.
+    pub fn finish(self) -> Vec<SyntaxError> {
+        self.errors
+    }
+
+    /// Returns a marker of the current position, which can be passed to [`Lexer::rewind`] to
+    /// continue lexing from this position again.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            offset: self.offset(),
+            error_count: self.errors.len(),
+            has_emitted_eof: self.has_emitted_eof,
+        }
+    }
+
+    /// Rewinds the lexer to a checkpoint taken from this lexer, discarding the syntax errors found
+    /// since then.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.source_code = &self.original_source_code[checkpoint.offset..];
+        self.errors.truncate(checkpoint.error_count);
+        self.has_emitted_eof = checkpoint.has_emitted_eof;
+    }
+
+    /// Byte offset of the next character to be lexed, relative to the original source code.
+    fn offset(&self) -> usize {
+        self.original_source_code.len() - self.source_code.len()
+    }
+
+    /// Creates a token of the given kind out of the next `width` bytes and consumes them from the
+    /// source code.
+    fn make_token(&mut self, kind: TokenKind, width: usize) -> Token<'source> {
+        let start = self.offset();
+        let text = &self.source_code[..width];
+        self.source_code = &self.source_code[width..];
+
+        // Illegal characters already have their own error
+        if kind != TokenKind::Illegal {
+            self.check_bidi_controls(text, start);
+        }
+
+        Token {
+            kind,
+            text,
+            span: Span::from(start..start + width),
+        }
+    }
+
+    /// Records an error for every bidirectional control character in a token text, because they
+    /// can hide code inside comments or strings.
+    fn check_bidi_controls(&mut self, text: &str, start: usize) {
+        if text.is_ascii() {
+            return;
+        }
+        for (index, char) in text.char_indices() {
+            if is_bidi_control(char) {
+                let char_start = start + index;
+                self.errors.push(SyntaxError::BidiControlCharacter {
+                    span: (char_start..char_start + char.len_utf8()).into(),
+                    char,
+                });
+            }
+        }
+    }
+
+    /// Records a warning if an identifier could be mistaken for an ASCII one, because it mixes
+    /// ASCII letters with lookalike characters, such as `аdmin` with a Cyrillic `а`, or because all
+    /// of its letters are lookalikes. Identifiers written in other scripts, such as `λόγος`, are
+    /// fine.
+    fn check_confusables(&mut self, identifier: &str) {
+        if identifier.is_ascii() {
+            return;
+        }
+        let Some((confusable_char, ascii_char)) = identifier
+            .chars()
+            .find_map(|char| Some((char, confusable_ascii(char)?)))
+        else {
+            return;
+        };
+
+        let has_ascii_letters = identifier.chars().any(|char| char.is_ascii_alphabetic());
+        let all_confusable = identifier.chars().all(|char| {
+            !char.is_alphabetic() || char.is_ascii() || confusable_ascii(char).is_some()
+        });
+        if has_ascii_letters || all_confusable {
+            let start = self.offset();
+            self.errors.push(SyntaxError::ConfusableIdentifier {
+                span: (start..start + identifier.len()).into(),
+                char: confusable_char,
+                ascii_char,
+            });
+        }
+    }
+
+    /// Records a warning if an identifier is not NFC-normalized, because it would be a different
+    /// name from a canonically equivalent identifier. See [`Token::normalized_text`].
+    fn check_normalization(&mut self, identifier: &str) {
+        if identifier.is_ascii() || is_nfc(identifier) {
+            return;
+        }
+        let start = self.offset();
+        self.errors.push(SyntaxError::NonNfcIdentifier {
+            span: (start..start + identifier.len()).into(),
+            identifier: identifier.to_string(),
+            normalized: identifier.nfc().collect(),
+        });
+    }
+
     pub fn consume_token(&mut self) -> Option<Token<'source>> {
-        // We finished tokenizing the source code
-        if self.source_code.len() == 0 {
-            return None;
+        // Return the trivia token, if any. If trivia is skipped, consume all of it, except for doc
+        // comments.
+        while let Some(trivia_token) = self.consume_trivia() {
+            if !self.options.skip_trivia || !trivia_token.kind.is_trivia() {
+                return Some(trivia_token);
+            }
         }
 
-        // Return the trivia token, if any.
-        let trivia_token = self.consume_trivia();
-        if trivia_token.is_some() {
-            return trivia_token;
+        // We finished tokenizing the source code
+        if self.source_code.is_empty() {
+            return self.consume_eof();
         }
 
         let mut chars = self.source_code.chars();
 
         let current_char = chars.next();
         let next_char = chars.next();
+        let third_char = chars.next();
 
         match current_char {
             Some(current_char) => {
+                // Check for string literals. This must happen before checking for identifiers
+                // because string prefixes such as `f` are valid identifiers on their own.
+                let raw_string_literal_token = self.consume_raw_string_literal_token();
+                if raw_string_literal_token.is_some() {
+                    return raw_string_literal_token;
+                }
+                let multiline_string_literal_token = self.consume_multiline_string_literal_token();
+                if multiline_string_literal_token.is_some() {
+                    return multiline_string_literal_token;
+                }
+                let string_literal_token = self.consume_string_literal_token();
+                if string_literal_token.is_some() {
+                    return string_literal_token;
+                }
+
+                // Check for char literals
+                let char_literal_token = self.consume_char_literal_token();
+                if char_literal_token.is_some() {
+                    return char_literal_token;
+                }
+
+                // Check for number literals
+                let number_literal_token = self.consume_number_literal_token();
+                if number_literal_token.is_some() {
+                    return number_literal_token;
+                }
+
+                // Check for three-char tokens before two-char tokens so that the longest match
+                // wins, e.g. `<<=` is not lexed as `<<` and `=`
+                if let (Some(next_char), Some(third_char)) = (next_char, third_char) {
+                    let three_char_token =
+                        self.consume_three_chars_token(current_char, next_char, third_char);
+                    if three_char_token.is_some() {
+                        return three_char_token;
+                    }
+                }
+
                 // Check for two-char tokens
                 if let Some(next_char) = next_char {
                     let two_char_token = self.consume_two_chars_token(current_char, next_char);
@@ -68,17 +314,59 @@ impl<'source> Lexer<'source> {
 
                 // If none of the past consumers got a token, then the reason is that the next
                 // character is illegal
-                let token_text = &self.source_code[..1];
-                self.source_code = &self.source_code[1..];
-                return Some(Token {
-                    kind: TokenKind::Illegal,
-                    text: token_text,
-                });
+                Some(self.consume_illegal_token())
             }
-            None => return None,
+            None => None,
         }
     }
 
+    /// Consumes the end of the source code, which is only a token if it has been enabled with
+    /// [`LexerBuilder::emit_eof`]. It is only returned once.
+    fn consume_eof(&mut self) -> Option<Token<'source>> {
+        if !self.options.emit_eof || self.has_emitted_eof {
+            return None;
+        }
+        self.has_emitted_eof = true;
+        Some(self.make_token(TokenKind::Eof, 0))
+    }
+
+    /// Consumes a run of illegal characters as a single token with a single error, so that a
+    /// pasted binary blob doesn't flood the output.
+    fn consume_illegal_token(&mut self) -> Token<'source> {
+        let illegal_width = self
+            .source_code
+            .char_indices()
+            .skip(1)
+            .find(|&(_, char)| can_start_token(char))
+            .map_or(self.source_code.len(), |(index, _)| index);
+
+        let start = self.offset();
+        self.errors.push(SyntaxError::IllegalCharacter {
+            span: (start..start + illegal_width).into(),
+            text: self.source_code[..illegal_width].to_string(),
+        });
+        self.make_token(TokenKind::Illegal, illegal_width)
+    }
+
+    /// Consumes the next three-char token (if any) such as `<<=` or `...`.
+    pub fn consume_three_chars_token(
+        &mut self,
+        current_char: char,
+        next_char: char,
+        third_char: char,
+    ) -> Option<Token<'source>> {
+        let token_kind = match (current_char, next_char, third_char) {
+            ('<', '<', '=') => TokenKind::LessThanLessThanEqual,
+            ('>', '>', '=') => TokenKind::GreaterThanGreaterThanEqual,
+            ('*', '*', '=') => TokenKind::PowerEqual,
+            ('.', '.', '=') => TokenKind::DotDotEqual,
+            ('.', '.', '.') => TokenKind::DotDotDot,
+            (_, _, _) => return None,
+        };
+
+        Some(self.make_token(token_kind, 3))
+    }
+
     /// Consumes the next two-char token (if any) such as `&&` or `+=`.
     pub fn consume_two_chars_token(
         &mut self,
@@ -98,61 +386,385 @@ impl<'source> Lexer<'source> {
             ('>', '=') => TokenKind::GreaterThanEqual,
             ('<', '<') => TokenKind::LessThanLessThan,
             ('>', '>') => TokenKind::GreaterThanGreaterThan,
+            ('=', '=') => TokenKind::EqualEqual,
+            ('*', '*') => TokenKind::Power,
+            ('-', '>') => TokenKind::Arrow,
+            ('=', '>') => TokenKind::FatArrow,
+            ('|', '>') => TokenKind::Pipe,
+            (':', ':') => TokenKind::ColonColon,
+            ('.', '.') => TokenKind::DotDot,
             (_, _) => return None,
         };
 
-        let text = &self.source_code[..2];
-        self.source_code = &self.source_code[2..];
-
-        Some(Token {
-            kind: token_kind,
-            text,
-        })
+        Some(self.make_token(token_kind, 2))
     }
 
     /// Consumes the next one-char token (if any) such as `+` or `|`.
     pub fn consume_one_char_token(&mut self, current_char: char) -> Option<Token<'source>> {
-        let token_kind = match current_char {
-            '=' => TokenKind::Equal,
-            '+' => TokenKind::Plus,
-            '-' => TokenKind::Minus,
-            '*' => TokenKind::Multiply,
-            '/' => TokenKind::Divide,
-            '%' => TokenKind::Modulo,
-            '&' => TokenKind::And,
-            '|' => TokenKind::Or,
-            '^' => TokenKind::Caret,
-            '!' => TokenKind::Not,
-            '<' => TokenKind::LessThan,
-            '>' => TokenKind::GreaterThan,
-            '(' => TokenKind::LeftParenthesis,
-            ')' => TokenKind::RightParenthesis,
-            '[' => TokenKind::LeftBracket,
-            ']' => TokenKind::RightBracket,
-            '{' => TokenKind::LeftBrace,
-            '}' => TokenKind::RightBrace,
-            ',' => TokenKind::Comma,
-            '.' => TokenKind::Dot,
-            ';' => TokenKind::Semicolon,
-            ':' => TokenKind::Colon,
-            _ => return None,
+        let token_kind = one_char_token_kind(current_char)?;
+        Some(self.make_token(token_kind, 1))
+    }
+
+    /// Consumes the next string literal token (if any), including prefixed strings such as
+    /// `f"Hello, {world}"`.
+    /// Strings can't span multiple lines, so an unterminated string ends at the next newline.
+    pub fn consume_string_literal_token(&mut self) -> Option<Token<'source>> {
+        let prefix_width = self.string_prefix_width("\"")?;
+
+        // We initialize it past the opening `"` because we want to include the prefix and the
+        // quote in the token text.
+        let (string_width, is_terminated) = self.scan_string_body(prefix_width + 1, "\"");
+
+        if !is_terminated {
+            let start = self.offset();
+            self.errors.push(SyntaxError::UnterminatedString {
+                span: (start..start + string_width).into(),
+            });
+        }
+
+        Some(self.finish_string_literal_token(string_width))
+    }
+
+    /// Consumes the next multiline string literal token (if any) such as `"""Hello, world"""`.
+    /// Multiline strings preserve newlines and can contain escape sequences. Their indentation is
+    /// not stripped by the lexer, see [`crate::strip_indentation`].
+    pub fn consume_multiline_string_literal_token(&mut self) -> Option<Token<'source>> {
+        let prefix_width = self.string_prefix_width("\"\"\"")?;
+
+        // We initialize it past the opening `"""` because we want to include the prefix and the
+        // quotes in the token text.
+        let (string_width, is_terminated) = self.scan_string_body(prefix_width + 3, "\"\"\"");
+
+        if !is_terminated {
+            let start = self.offset();
+            self.errors.push(SyntaxError::UnterminatedMultilineString {
+                span: (start..start + string_width).into(),
+            });
+        }
+
+        Some(self.finish_string_literal_token(string_width))
+    }
+
+    /// Scans the contents of the string literal that starts the source code, from `start` (right
+    /// after the opening quote) up to and including the closing `quote`, validating its escape
+    /// sequences. Single-line strings end at the end of the line.
+    ///
+    /// The expressions inside the braces of interpolated strings can contain their own strings,
+    /// which are skipped as a whole, see [`interpolation_width`].
+    /// Returns the width of the string and whether it is terminated.
+    fn scan_string_body(&mut self, start: usize, quote: &str) -> (usize, bool) {
+        let is_interpolated = self.source_code.starts_with('f');
+        let is_single_line = quote == "\"";
+        let mut string_width = start;
+
+        while let Some(current_char) = self.source_code[string_width..].chars().next() {
+            let rest = &self.source_code[string_width..];
+            match current_char {
+                _ if rest.starts_with(quote) => return (string_width + quote.len(), true),
+                '\n' if is_single_line => break,
+                '\\' => {
+                    // The escaped character is always part of the string, even if it is a quote
+                    string_width += self.consume_escape_sequence(string_width);
+                }
+                '{' if is_interpolated && !rest.starts_with("{{") => {
+                    let (expression_width, is_closed) =
+                        interpolation_width(&rest[1..], is_single_line);
+                    string_width += 1 + expression_width;
+                    if !is_closed {
+                        break;
+                    }
+                    string_width += 1;
+                }
+                '{' | '}' if is_interpolated && rest[1..].starts_with(current_char) => {
+                    string_width += 2;
+                }
+                _ => string_width += current_char.len_utf8(),
+            }
+        }
+
+        (string_width, false)
+    }
+
+    /// Consumes a string literal of the given width, checking the braces of interpolated strings.
+    fn finish_string_literal_token(&mut self, string_width: usize) -> Token<'source> {
+        let token_text = &self.source_code[..string_width];
+
+        if token_text.starts_with('f') {
+            scan_interpolations(token_text, self.offset(), &mut self.errors);
+        }
+
+        self.make_token(TokenKind::StringLiteral, string_width)
+    }
+
+    /// Returns the width of the string prefix (if any) if the source code starts with a string
+    /// literal opened by `quote`, such as `f"` or `"`.
+    fn string_prefix_width(&self, quote: &str) -> Option<usize> {
+        let prefix_width = STRING_PREFIXES
+            .iter()
+            .find(|prefix| {
+                self.source_code.starts_with(*prefix)
+                    && self.source_code[prefix.len()..].starts_with(quote)
+            })
+            .map_or(0, |prefix| prefix.len());
+
+        self.source_code[prefix_width..]
+            .starts_with(quote)
+            .then_some(prefix_width)
+    }
+
+    /// Consumes the next raw string literal token (if any) such as `r"C:\Users"` or
+    /// `r#"a "quoted" word"#`.
+    /// Raw strings don't process escape sequences and can span multiple lines. They are closed by
+    /// a `"` followed by as many `#` characters as the ones used to open them.
+    pub fn consume_raw_string_literal_token(&mut self) -> Option<Token<'source>> {
+        let after_prefix = self.source_code.strip_prefix('r')?;
+        let hash_count = after_prefix.len() - after_prefix.trim_start_matches('#').len();
+        if !after_prefix[hash_count..].starts_with('"') {
+            return None;
+        }
+
+        // `r` + hashes + `"`
+        let opening_width = 1 + hash_count + 1;
+        let closing_delimiter = format!("\"{}", "#".repeat(hash_count));
+
+        let string_width = match self.source_code[opening_width..].find(&closing_delimiter) {
+            Some(content_width) => opening_width + content_width + closing_delimiter.len(),
+            None => {
+                let start = self.offset();
+                self.errors.push(SyntaxError::UnterminatedRawString {
+                    span: (start..start + self.source_code.len()).into(),
+                });
+                self.source_code.len()
+            }
         };
-        let text = &self.source_code[..1];
-        self.source_code = &self.source_code[1..];
-        Some(Token {
-            kind: token_kind,
-            text,
-        })
+
+        Some(self.make_token(TokenKind::StringLiteral, string_width))
+    }
+
+    /// Consumes the next char literal token (if any) such as `'a'`, `'\n'` or `'\u{1F600}'`.
+    /// An escape sequence counts as a single character.
+    pub fn consume_char_literal_token(&mut self) -> Option<Token<'source>> {
+        if !self.source_code.starts_with('\'') {
+            return None;
+        }
+
+        // We initialize it to `1` because we want to include the opening `'` in the token text.
+        let mut literal_width = 1;
+        let mut char_count = 0;
+        let mut is_terminated = false;
+
+        let mut chars = self.source_code[literal_width..].chars();
+        while let Some(current_char) = chars.next() {
+            match current_char {
+                '\'' => {
+                    literal_width += 1;
+                    is_terminated = true;
+                    break;
+                }
+                '\n' => break,
+                '\\' => {
+                    literal_width += self.consume_escape_sequence(literal_width);
+                    chars = self.source_code[literal_width..].chars();
+                }
+                _ => literal_width += current_char.len_utf8(),
+            }
+            char_count += 1;
+        }
+
+        let start = self.offset();
+        let range = start..start + literal_width;
+        if !is_terminated {
+            self.errors
+                .push(SyntaxError::UnterminatedCharLiteral { span: range.into() });
+        } else if char_count == 0 {
+            self.errors
+                .push(SyntaxError::EmptyCharLiteral { span: range.into() });
+        } else if char_count > 1 {
+            self.errors
+                .push(SyntaxError::CharLiteralTooLong { span: range.into() });
+        }
+
+        Some(self.make_token(TokenKind::CharLiteral, literal_width))
+    }
+
+    /// Validates the escape sequence that starts with the `\\` at `start` (relative to the source
+    /// code that has yet to be lexed), recording an error with the exact range of the escape
+    /// sequence if it is invalid.
+    /// Returns the width of the escape sequence, which never includes a newline.
+    fn consume_escape_sequence(&mut self, start: usize) -> usize {
+        let escape = &self.source_code[start + 1..];
+        let escape_start = self.offset() + start;
+
+        match escape.chars().next() {
+            // A lone `\\` at the end of the line is reported by the literal as unterminated.
+            None | Some('\n') => 1,
+            Some('n' | 't' | 'r' | '0' | '\\' | '"' | '\'') => 2,
+            Some('u') => {
+                let Some(body) = escape[1..].strip_prefix('{') else {
+                    self.errors.push(SyntaxError::InvalidUnicodeEscape {
+                        span: (escape_start..escape_start + 2).into(),
+                        problem: UnicodeEscapeProblem::MissingOpeningBrace,
+                    });
+                    return 2;
+                };
+
+                // The body spans until the closing brace, but we stop early if we find the end of
+                // the literal so that a missing brace doesn't swallow the closing quote.
+                let (body_width, is_terminated) = match body.find(['}', '"', '\'', '\n']) {
+                    Some(index) => (index, body[index..].starts_with('}')),
+                    None => (body.len(), false),
+                };
+                // `\\u{` + body + `}`
+                let escape_width = 3 + body_width + usize::from(is_terminated);
+                let range = escape_start..escape_start + escape_width;
+
+                let digits = &body[..body_width];
+                if !is_terminated {
+                    self.errors.push(SyntaxError::InvalidUnicodeEscape {
+                        span: range.into(),
+                        problem: UnicodeEscapeProblem::Unterminated,
+                    });
+                } else if digits.is_empty()
+                    || digits.len() > 6
+                    || !digits.chars().all(|char| char.is_ascii_hexdigit())
+                {
+                    self.errors.push(SyntaxError::InvalidUnicodeEscape {
+                        span: range.into(),
+                        problem: UnicodeEscapeProblem::InvalidDigits,
+                    });
+                } else if u32::from_str_radix(digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .is_none()
+                {
+                    self.errors.push(SyntaxError::InvalidUnicodeEscape {
+                        span: range.into(),
+                        problem: UnicodeEscapeProblem::InvalidCharacter {
+                            digits: digits.to_string(),
+                        },
+                    });
+                }
+
+                escape_width
+            }
+            Some(escaped_char) => {
+                let escape_width = 1 + escaped_char.len_utf8();
+                self.errors.push(SyntaxError::InvalidEscape {
+                    span: (escape_start..escape_start + escape_width).into(),
+                    escaped_char,
+                });
+                escape_width
+            }
+        }
+    }
+
+    /// Consumes the next number literal token (if any) such as `123`, `1_000`, `0x7d1`, `0o17`,
+    /// `0b1010`, `3.14` or `2.5e-3`.
+    pub fn consume_number_literal_token(&mut self) -> Option<Token<'source>> {
+        if !self
+            .source_code
+            .starts_with(|char: char| char.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let (radix, prefix_width) = match self.source_code.get(..2) {
+            Some("0x") => (16, 2),
+            Some("0o") => (8, 2),
+            Some("0b") => (2, 2),
+            _ => (10, 0),
+        };
+
+        let (digits_width, digit_count) = self.scan_digits(prefix_width, radix);
+        let mut literal_width = prefix_width + digits_width;
+        let mut token_kind = TokenKind::IntegerLiteral;
+
+        if digit_count == 0 {
+            let start = self.offset();
+            self.errors.push(SyntaxError::MissingDigits {
+                span: (start..start + literal_width).into(),
+                radix,
+            });
+        }
+
+        // Only decimal literals can have a fractional part or an exponent.
+        if radix == 10 {
+            // The `.` is only part of the literal if a digit follows it, so that `1.foo()` and
+            // `1..10` are not lexed as floats.
+            let rest = &self.source_code.as_bytes()[literal_width..];
+            if let [b'.', b'0'..=b'9', ..] = rest {
+                let (fraction_width, _) = self.scan_digits(literal_width + 1, radix);
+                literal_width += 1 + fraction_width;
+                token_kind = TokenKind::FloatLiteral;
+            }
+
+            // The exponent is only part of the literal if a digit follows it (after the optional
+            // sign), otherwise the `e` starts a new token.
+            let rest = &self.source_code.as_bytes()[literal_width..];
+            let exponent_prefix_width = match rest {
+                [b'e' | b'E', b'+' | b'-', b'0'..=b'9', ..] => 2,
+                [b'e' | b'E', b'0'..=b'9', ..] => 1,
+                _ => 0,
+            };
+            if exponent_prefix_width > 0 {
+                let exponent_start = literal_width + exponent_prefix_width;
+                let (exponent_width, _) = self.scan_digits(exponent_start, radix);
+                literal_width = exponent_start + exponent_width;
+                token_kind = TokenKind::FloatLiteral;
+            }
+        }
+
+        Some(self.make_token(token_kind, literal_width))
+    }
+
+    /// Scans the digits and `_` separators of a number literal starting at `start`, recording an
+    /// error for every digit that is not valid in the given radix.
+    /// Returns the width of the digits and the number of digits found, excluding separators.
+    fn scan_digits(&mut self, start: usize, radix: u32) -> (usize, usize) {
+        let mut digits_width = 0;
+        let mut digit_count = 0;
+
+        for current_char in self.source_code[start..].chars() {
+            if current_char == '_' {
+                digits_width += 1;
+                continue;
+            }
+
+            // Decimal literals stop at the first non-digit, but prefixed literals consume every
+            // alphanumeric character so that `0b102` is reported as a single invalid literal
+            // instead of being split into `0b10` and `2`.
+            let is_part_of_literal = if radix == 10 {
+                current_char.is_ascii_digit()
+            } else {
+                current_char.is_ascii_alphanumeric()
+            };
+            if !is_part_of_literal {
+                break;
+            }
+
+            if !current_char.is_digit(radix) {
+                let digit_start = self.offset() + start + digits_width;
+                self.errors.push(SyntaxError::InvalidDigitForBase {
+                    span: (digit_start..digit_start + current_char.len_utf8()).into(),
+                    digit: current_char,
+                    radix,
+                });
+            }
+
+            digits_width += 1;
+            digit_count += 1;
+        }
+
+        (digits_width, digit_count)
     }
 
     /// Consumes the next keyword token (if any).
     pub fn consume_keyword_or_identifier_token(&mut self) -> Option<Token<'source>> {
         let mut chars = self.source_code.chars();
-        let Some(first_char) = chars.next() else {
-            return None;
-        };
-        // Check if the first char has the Unicode XID_Start property.
-        if !is_xid_start(first_char) {
+        let first_char = chars.next()?;
+        // Check if the first char has the Unicode XID_Start property. Identifiers can also start
+        // with `_`, such as `_unused` or the `_` wildcard.
+        if !is_xid_start(first_char) && first_char != '_' {
             return None;
         }
 
@@ -167,29 +779,36 @@ impl<'source> Lexer<'source> {
         }
 
         let token_text = &self.source_code[..keyword_width];
-        self.source_code = &self.source_code[keyword_width..];
-
-        let token_kind = match token_text {
-            "def" => TokenKind::Def,
-            "extend" => TokenKind::Extend,
-            "with" => TokenKind::With,
-            "if" => TokenKind::If,
-            "else" => TokenKind::Else,
-            "for" => TokenKind::For,
-            "struct" => TokenKind::Struct,
-            _ => TokenKind::Identifier,
-        };
+        self.check_confusables(token_text);
+        self.check_normalization(token_text);
+
+        // Most lexers don't override keywords, so skip hashing every identifier in that case
+        if !self.options.keyword_overrides.is_empty() {
+            if let Some(&token_kind) = self.options.keyword_overrides.get(token_text) {
+                let token_kind = token_kind.unwrap_or(TokenKind::Identifier);
+                return Some(self.make_token(token_kind, keyword_width));
+            }
+        }
 
-        Some(Token {
-            kind: token_kind,
-            text: token_text,
-        })
+        let token_kind = TokenKind::from_keyword(token_text).unwrap_or(TokenKind::Identifier);
+
+        Some(self.make_token(token_kind, keyword_width))
     }
 
     /// Consumes whitespace and comments.
     /// This function will never join two type of trivia in the same token.
     /// This means that a token is either whitespace or a comment, but not both.
     pub fn consume_trivia(&mut self) -> Option<Token<'source>> {
+        let byte_order_mark = self.consume_byte_order_mark();
+        if byte_order_mark.is_some() {
+            return byte_order_mark;
+        }
+
+        let shebang = self.consume_shebang();
+        if shebang.is_some() {
+            return shebang;
+        }
+
         let whitespace = self.consume_whitespace();
         if whitespace.is_some() {
             return whitespace;
@@ -199,77 +818,190 @@ impl<'source> Lexer<'source> {
         if comment.is_some() {
             return comment;
         }
+
+        let block_comment = self.consume_block_comment();
+        if block_comment.is_some() {
+            return block_comment;
+        }
         None
     }
 
+    /// Consumes the UTF-8 byte order mark that some editors save at the start of files. It is
+    /// trivia, but a warning is recorded because it is not needed in UTF-8.
+    pub fn consume_byte_order_mark(&mut self) -> Option<Token<'source>> {
+        const BOM: char = '\u{FEFF}';
+        if self.file_offset + self.offset() != 0 || !self.source_code.starts_with(BOM) {
+            return None;
+        }
+
+        self.errors.push(SyntaxError::ByteOrderMark {
+            span: (0..BOM.len_utf8()).into(),
+        });
+        Some(self.make_token(TokenKind::Trivia, BOM.len_utf8()))
+    }
+
+    /// Consumes a shebang line such as `#!/usr/bin/env kora`, which is trivia so that Kora scripts
+    /// can be executable. It is only recognized at the start of the source code, after the byte
+    /// order mark if any, and, like comments, includes the newline.
+    pub fn consume_shebang(&mut self) -> Option<Token<'source>> {
+        let code_before = &self.original_source_code[..self.offset()];
+        let at_start = match self.file_offset {
+            0 => matches!(code_before, "" | "\u{FEFF}"),
+            _ => self.follows_byte_order_mark && code_before.is_empty(),
+        };
+        if !at_start || !self.source_code.starts_with("#!") {
+            return None;
+        }
+
+        let shebang_width = match self.source_code.find('\n') {
+            Some(newline_index) if self.options.comment_includes_newline => newline_index + 1,
+            Some(newline_index) => newline_index,
+            None => self.source_code.len(),
+        };
+        Some(self.make_token(TokenKind::Trivia, shebang_width))
+    }
+
     /// Consumes whitespace.
     pub fn consume_whitespace(&mut self) -> Option<Token<'source>> {
+        let bytes = self.source_code.as_bytes();
         let mut whitespace_width = 0;
 
-        for current_char in self.source_code.chars() {
-            // If we found a non-whitespace character, stop iterating
-            if !current_char.is_whitespace() {
+        // Fast path for ASCII whitespace, which is almost all of it. Runs of spaces, such as
+        // indentation, are skipped 8 bytes at a time.
+        loop {
+            if let Some(chunk) = bytes.get(whitespace_width..whitespace_width + 8) {
+                if chunk == b"        " {
+                    whitespace_width += 8;
+                    continue;
+                }
+            }
+
+            let Some(&byte) = bytes.get(whitespace_width) else {
                 break;
+            };
+            if !matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0B' | b'\x0C') {
+                break;
+            }
+            if byte == b'\t' {
+                self.report_tab(whitespace_width);
+            }
+            whitespace_width += 1;
+        }
+
+        // Slow path for Unicode whitespace, such as U+00A0 NO-BREAK SPACE
+        if bytes
+            .get(whitespace_width)
+            .is_some_and(|byte| !byte.is_ascii())
+        {
+            for current_char in self.source_code[whitespace_width..].chars() {
+                // If we found a non-whitespace character, stop iterating
+                if !current_char.is_whitespace() {
+                    break;
+                }
+                if current_char == '\t' {
+                    self.report_tab(whitespace_width);
+                }
+                // If it is whitespace, increment the whitespace width by
+                // the length of the character in UTF-8
+                whitespace_width += current_char.len_utf8();
             }
-            // If it is whitespace, increment the whitespace width by
-            // the length of the character in UTF-8
-            whitespace_width += current_char.len_utf8();
         }
 
         // Check if there was any whitespace found
         if whitespace_width > 0 {
-            // If there was whitespace, extract the token text from the
-            // source code
-            let token_text = &self.source_code[..whitespace_width];
-
-            // Update the source code by removing the consumed whitespace
-            self.source_code = &self.source_code[whitespace_width..];
-
-            // Return a Token with the kind set to Trivia and the extracted token text
-            Some(Token {
-                kind: TokenKind::Trivia,
-                text: token_text,
-            })
+            // If there was whitespace, return a Token with the kind set to Trivia and consume it
+            // from the source code
+            Some(self.make_token(TokenKind::Trivia, whitespace_width))
         } else {
             // If no whitespace was found, return None
             None
         }
     }
 
+    /// Records an error for the tab at `start` (relative to the source code that has yet to be
+    /// lexed) if tabs are forbidden.
+    fn report_tab(&mut self, start: usize) {
+        if self.options.forbid_tabs {
+            let tab_start = self.offset() + start;
+            self.errors.push(SyntaxError::TabCharacter {
+                span: (tab_start..tab_start + 1).into(),
+            });
+        }
+    }
+
     /// Consumes a comment include the newline.
+    /// Doc comments (`/// comment` and `//! comment`) get their own token kind, but comments that
+    /// start with four or more slashes are regular comments.
     pub fn consume_comment(&mut self) -> Option<Token<'source>> {
         // Abort if the source code does not start with `//`
         if !self.source_code.starts_with("//") {
             return None;
         }
 
-        // We initialize it to `2` because we want to include the `//` characters at the start.
-        let mut comment_width = 2;
-
-        for current_char in self.source_code.chars() {
-            // Increment the comment width by the length of the current character in UTF-8 bytes
-            comment_width += current_char.len_utf8();
-
-            // We stop until we find a newline character but after we have included it in the token text
-            if current_char == '\n' {
-                break;
-            }
-        }
+        // The comment spans until the next newline, which is searched for with `memchr` because
+        // comments are long and the newline is a single byte that can't be part of another
+        // character in UTF-8. The newline is included in the token text unless it has been
+        // disabled.
+        let comment_width = match memchr(b'\n', &self.source_code.as_bytes()[2..]) {
+            Some(newline_index) if self.options.comment_includes_newline => 2 + newline_index + 1,
+            Some(newline_index) => 2 + newline_index,
+            None => self.source_code.len(),
+        };
 
         // If the comment width is greater than 0, extract the token text and update the source code
         if comment_width > 0 {
-            let token_text = &self.source_code[..comment_width];
-            self.source_code = &self.source_code[comment_width..];
+            let is_doc_comment = (self.source_code.starts_with("///")
+                && !self.source_code.starts_with("////"))
+                || self.source_code.starts_with("//!");
+            let token_kind = if is_doc_comment {
+                TokenKind::DocComment
+            } else {
+                TokenKind::Trivia
+            };
 
-            Some(Token {
-                kind: TokenKind::Trivia,
-                text: token_text,
-            })
+            Some(self.make_token(token_kind, comment_width))
         } else {
             // If the comment width is 0, return None
             None
         }
     }
+
+    /// Consumes a block comment such as `/* comment */`.
+    /// Block comments can be nested, so `/* outer /* inner */ still a comment */` is a single
+    /// comment.
+    pub fn consume_block_comment(&mut self) -> Option<Token<'source>> {
+        // Abort if the source code does not start with `/*`
+        if !self.source_code.starts_with("/*") {
+            return None;
+        }
+
+        // We initialize it to `2` because we want to include the `/*` characters at the start.
+        let mut comment_width = 2;
+        // Number of comments that have been opened but not closed yet.
+        let mut depth = 1;
+
+        while depth > 0 {
+            let rest = &self.source_code[comment_width..];
+            if rest.starts_with("/*") {
+                depth += 1;
+                comment_width += 2;
+            } else if rest.starts_with("*/") {
+                depth -= 1;
+                comment_width += 2;
+            } else if let Some(current_char) = rest.chars().next() {
+                comment_width += current_char.len_utf8();
+            } else {
+                // We reached the end of the source code without closing every comment
+                let start = self.offset();
+                self.errors.push(SyntaxError::UnterminatedBlockComment {
+                    span: (start..start + comment_width).into(),
+                });
+                break;
+            }
+        }
+
+        Some(self.make_token(TokenKind::Trivia, comment_width))
+    }
 }
 
 impl<'source> Iterator for Lexer<'source> {
@@ -278,3 +1010,47 @@ impl<'source> Iterator for Lexer<'source> {
         self.consume_token()
     }
 }
+
+/// Whether a token can start with the character. Every other character is illegal.
+fn can_start_token(char: char) -> bool {
+    char.is_whitespace()
+        || is_xid_start(char)
+        || char.is_ascii_digit()
+        || matches!(char, '_' | '"' | '\'')
+        || one_char_token_kind(char).is_some()
+}
+
+/// Kind of the one-char token that the character is lexed as, if any.
+fn one_char_token_kind(char: char) -> Option<TokenKind> {
+    let token_kind = match char {
+        '=' => TokenKind::Equal,
+        '+' => TokenKind::Plus,
+        '-' => TokenKind::Minus,
+        '*' => TokenKind::Multiply,
+        '/' => TokenKind::Divide,
+        '%' => TokenKind::Modulo,
+        '&' => TokenKind::And,
+        '|' => TokenKind::Or,
+        '^' => TokenKind::Caret,
+        '!' => TokenKind::Not,
+        '<' => TokenKind::LessThan,
+        '>' => TokenKind::GreaterThan,
+        '(' => TokenKind::LeftParenthesis,
+        ')' => TokenKind::RightParenthesis,
+        '[' => TokenKind::LeftBracket,
+        ']' => TokenKind::RightBracket,
+        '{' => TokenKind::LeftBrace,
+        '}' => TokenKind::RightBrace,
+        ',' => TokenKind::Comma,
+        '.' => TokenKind::Dot,
+        ';' => TokenKind::Semicolon,
+        ':' => TokenKind::Colon,
+        '?' => TokenKind::Question,
+        '@' => TokenKind::At,
+        '#' => TokenKind::Hash,
+        '$' => TokenKind::Dollar,
+        '~' => TokenKind::Tilde,
+        _ => return None,
+    };
+    Some(token_kind)
+}
@@ -1,10 +1,35 @@
 #![allow(unused)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+mod builder;
 mod error;
+#[cfg(feature = "parallel")]
+mod files;
 mod lexer;
+mod line_index;
+mod literal;
 mod macros;
+mod peekable;
+mod prelude;
+mod relex;
+mod span;
+#[cfg(feature = "std")]
+mod streaming;
 mod token;
+mod unicode;
 
-pub use lexer::Lexer;
-pub use token::{Token, TokenKind};
-pub use error::SyntaxError;
+pub use builder::LexerBuilder;
+pub use error::{Severity, SyntaxError, UnicodeEscapeProblem};
+#[cfg(feature = "parallel")]
+pub use files::{tokenize_files, FileTokens};
+pub use lexer::{Checkpoint, Lexer};
+pub use line_index::{LineIndex, SourcePosition};
+pub use literal::{interpolation_parts, strip_indentation, InterpolationPart};
+pub use peekable::PeekableLexer;
+pub use relex::TextEdit;
+pub use span::Span;
+#[cfg(feature = "std")]
+pub use streaming::{ChunkReader, StreamingLexer};
+pub use token::{OwnedToken, RawToken, Token, TokenKind};
@@ -0,0 +1,85 @@
+use core::fmt;
+
+use crate::prelude::*;
+use crate::span::Span;
+
+/// Line and column of a byte offset in the source code. Both are zero-based, and the column is the
+/// byte offset from the start of the line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourcePosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Positions are displayed one-based, as editors and compilers usually show them (`1:1`).
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.column + 1)
+    }
+}
+
+/// Converts byte offsets in the source code to line/column pairs and back.
+/// The start of every line is precomputed so that lookups only need a binary search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Byte offset of the start of every line. The first line always starts at `0`.
+    line_starts: Vec<u32>,
+    /// Length of the source code in bytes.
+    len: u32,
+}
+
+impl LineIndex {
+    pub fn new(source_code: &str) -> Self {
+        let line_starts = core::iter::once(0)
+            .chain(
+                source_code
+                    .match_indices('\n')
+                    .map(|(index, _)| index as u32 + 1),
+            )
+            .collect();
+
+        Self {
+            line_starts,
+            len: source_code.len() as u32,
+        }
+    }
+
+    /// Number of lines in the source code. A trailing newline starts a new, empty line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the line/column pair of the given byte offset.
+    /// Offsets past the end of the source code are clamped to the end.
+    pub fn position(&self, offset: u32) -> SourcePosition {
+        let offset = offset.min(self.len);
+        // `partition_point` returns the number of lines that start at or before the offset, which
+        // is always at least one because the first line starts at `0`.
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+
+        SourcePosition {
+            line: line as u32,
+            column: offset - self.line_starts[line],
+        }
+    }
+
+    /// Returns the byte offset of the given line/column pair, or `None` if the line doesn't exist.
+    /// Columns past the end of the line are clamped to the end of the line.
+    pub fn offset(&self, position: SourcePosition) -> Option<u32> {
+        let line_span = self.line_span(position.line)?;
+        Some((line_span.start + position.column).min(line_span.end))
+    }
+
+    /// Returns the span of the given line, including its newline, or `None` if the line doesn't
+    /// exist.
+    pub fn line_span(&self, line: u32) -> Option<Span> {
+        let start = *self.line_starts.get(line as usize)?;
+        let end = self
+            .line_starts
+            .get(line as usize + 1)
+            .copied()
+            .unwrap_or(self.len);
+        Some(Span::new(start, end))
+    }
+}
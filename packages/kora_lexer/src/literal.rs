@@ -0,0 +1,307 @@
+use core::ops::Range;
+
+use crate::error::SyntaxError;
+use crate::prelude::*;
+
+/// Returns the contents of a multiline string literal token such as `"""Hello, world"""` with its
+/// indentation stripped. Escape sequences are left as they are.
+///
+/// The indentation is stripped with the following rules:
+/// - The newline right after the opening `"""` is removed.
+/// - If the closing `"""` is on its own line, the whitespace that precedes it is the indentation
+///   of the string. That line is removed, and the indentation is removed from the start of every
+///   other line. Lines made up only of whitespace become empty.
+/// - Otherwise, the contents are returned as they are.
+///
+/// ```text
+/// let greeting = """
+///     Hello,
+///       world
+///     """
+/// ```
+///
+/// The string above contains `Hello,\n  world`.
+pub fn strip_indentation(token_text: &str) -> String {
+    let content = match token_text.find("\"\"\"") {
+        Some(index) => &token_text[index + 3..],
+        None => token_text,
+    };
+    let content = content.strip_suffix("\"\"\"").unwrap_or(content);
+    let content = content
+        .strip_prefix("\r\n")
+        .or_else(|| content.strip_prefix('\n'))
+        .unwrap_or(content);
+
+    let Some((body, indentation)) = content.rsplit_once('\n') else {
+        return content.to_string();
+    };
+    if !indentation.chars().all(|char| char == ' ' || char == '\t') {
+        return content.to_string();
+    }
+
+    let body = body.strip_suffix('\r').unwrap_or(body);
+    body.split('\n')
+        .map(|line| match line.strip_prefix(indentation) {
+            Some(line) => line,
+            None if line.trim().is_empty() => "",
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A part of an interpolated string literal such as `f"Hello, {world}!"`.
+/// Ranges are byte ranges relative to the start of the token text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpolationPart {
+    /// Literal text, such as `Hello, ` and `!`. Escape sequences and escaped braces (`{{` and `}}`)
+    /// are left as they are.
+    Fragment(Range<usize>),
+    /// The source code of an embedded expression, such as `world`, excluding the braces.
+    Expression(Range<usize>),
+}
+
+/// Splits the contents of an interpolated string literal token, such as `f"Hello, {world}!"` or
+/// `f"""Hello, {world}!"""`, into literal fragments and embedded expressions.
+///
+/// Braces can be included in the literal text by doubling them, such as `f"{{not interpolated}}"`.
+pub fn interpolation_parts(token_text: &str) -> Vec<InterpolationPart> {
+    scan_interpolations(token_text, 0, &mut Vec::new())
+}
+
+/// Splits an interpolated string literal token into its parts, recording unbalanced braces as
+/// syntax errors. The ranges of the errors are offset by `offset`, which is the position of the
+/// token in the source code.
+pub(crate) fn scan_interpolations(
+    token_text: &str,
+    offset: usize,
+    errors: &mut Vec<SyntaxError>,
+) -> Vec<InterpolationPart> {
+    let quote = if token_text[1..].starts_with("\"\"\"") {
+        "\"\"\""
+    } else {
+        "\""
+    };
+    // Skip the `f` prefix and the opening quote
+    let start = 1 + quote.len();
+    let end = if token_text.len() >= start + quote.len() && token_text.ends_with(quote) {
+        token_text.len() - quote.len()
+    } else {
+        token_text.len()
+    };
+
+    let bytes = token_text.as_bytes();
+    let mut parts = Vec::new();
+    let mut fragment_start = start;
+    let mut index = start;
+
+    // All the delimiters are ASCII, so we can scan the bytes without worrying about slicing in the
+    // middle of a character.
+    while index < end {
+        match (bytes[index], bytes.get(index + 1)) {
+            (b'\\', _) => index += 2,
+            (b'{', Some(b'{')) | (b'}', Some(b'}')) => index += 2,
+            (b'{', _) => {
+                if fragment_start < index {
+                    parts.push(InterpolationPart::Fragment(fragment_start..index));
+                }
+
+                let expression_start = index + 1;
+                let (expression_width, is_closed) =
+                    interpolation_width(&token_text[expression_start..end], quote == "\"");
+                let closing_brace = is_closed.then_some(expression_start + expression_width);
+
+                let expression_end = closing_brace.unwrap_or(end);
+                parts.push(InterpolationPart::Expression(
+                    expression_start..expression_end,
+                ));
+
+                if closing_brace.is_none() {
+                    errors.push(SyntaxError::UnterminatedInterpolation {
+                        span: (offset + index..offset + end).into(),
+                    });
+                }
+
+                index = (expression_end + 1).min(end);
+                fragment_start = index;
+            }
+            (b'}', _) => {
+                errors.push(SyntaxError::UnmatchedClosingBrace {
+                    span: (offset + index..offset + index + 1).into(),
+                });
+                index += 1;
+            }
+            _ => index += 1,
+        }
+    }
+
+    if fragment_start < end {
+        parts.push(InterpolationPart::Fragment(fragment_start..end));
+    }
+
+    parts
+}
+
+/// A context that the scanner of an interpolated expression can be in. The lexer itself is in the
+/// normal mode outside of any string, which is the bottom of the stack and is not represented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Source code inside the braces of an interpolation, with the number of `{` that are still
+    /// open, such as the ones of map literals.
+    Interpolation { brace_depth: usize },
+    /// Inside a string literal closed by `quote`, which can contain its own interpolations.
+    String {
+        quote: &'static str,
+        is_interpolated: bool,
+    },
+    /// Inside a raw string literal closed by a `"` and `hash_count` hashes.
+    RawString { hash_count: usize },
+}
+
+/// Scans the expression of an interpolation, starting right after its opening `{`.
+/// Returns the width of the expression up to its closing `}`, and whether the `}` was found.
+///
+/// The expression can contain strings with their own braces and quotes, including interpolated
+/// strings such as `f"a{ f"b{c}" }"`, so the scanner keeps an explicit stack of the modes it has
+/// entered instead of just counting braces.
+/// In single-line strings, the expression stops at the end of the line.
+pub(crate) fn interpolation_width(text: &str, is_single_line: bool) -> (usize, bool) {
+    let mut modes = vec![Mode::Interpolation { brace_depth: 0 }];
+    let mut width = 0;
+
+    while let Some(&mode) = modes.last() {
+        let rest = &text[width..];
+        let Some(current_char) = rest.chars().next() else {
+            break;
+        };
+        if current_char == '\n' && is_single_line {
+            break;
+        }
+
+        match mode {
+            Mode::Interpolation { brace_depth } => {
+                if let Some((string_mode, opening_width)) = string_opening(rest) {
+                    modes.push(string_mode);
+                    width += opening_width;
+                    continue;
+                }
+                if let Some(char_literal_width) = char_literal_width(rest) {
+                    width += char_literal_width;
+                    continue;
+                }
+                match current_char {
+                    '{' => {
+                        *modes.last_mut().unwrap() = Mode::Interpolation {
+                            brace_depth: brace_depth + 1,
+                        }
+                    }
+                    '}' if brace_depth == 0 => {
+                        modes.pop();
+                        // The closing brace of the expression we were asked to scan
+                        if modes.is_empty() {
+                            return (width, true);
+                        }
+                    }
+                    '}' => {
+                        *modes.last_mut().unwrap() = Mode::Interpolation {
+                            brace_depth: brace_depth - 1,
+                        }
+                    }
+                    // Identifiers are skipped whole so that their last letter isn't mistaken for
+                    // a string prefix, such as the `f` of `elf"..."`
+                    _ if current_char.is_alphanumeric() || current_char == '_' => {
+                        width += rest
+                            .find(|char: char| !char.is_alphanumeric() && char != '_')
+                            .unwrap_or(rest.len());
+                        continue;
+                    }
+                    _ => {}
+                }
+                width += current_char.len_utf8();
+            }
+            Mode::String {
+                quote,
+                is_interpolated,
+            } => {
+                if rest.starts_with(quote) {
+                    modes.pop();
+                    width += quote.len();
+                } else if current_char == '\n' && quote == "\"" {
+                    // An unterminated nested string ends at the end of the line
+                    modes.pop();
+                } else if current_char == '\\' {
+                    width += 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+                } else if is_interpolated && (rest.starts_with("{{") || rest.starts_with("}}")) {
+                    width += 2;
+                } else if is_interpolated && current_char == '{' {
+                    modes.push(Mode::Interpolation { brace_depth: 0 });
+                    width += 1;
+                } else {
+                    width += current_char.len_utf8();
+                }
+            }
+            Mode::RawString { hash_count } => {
+                let is_closing = rest.strip_prefix('"').is_some_and(|after_quote| {
+                    after_quote.len() >= hash_count
+                        && after_quote.as_bytes()[..hash_count]
+                            .iter()
+                            .all(|&byte| byte == b'#')
+                });
+                if is_closing {
+                    modes.pop();
+                    width += 1 + hash_count;
+                } else {
+                    width += current_char.len_utf8();
+                }
+            }
+        }
+    }
+
+    (width, false)
+}
+
+/// Returns the mode of the string literal that `text` starts with (if any), and the width of its
+/// opening prefix and quotes.
+fn string_opening(text: &str) -> Option<(Mode, usize)> {
+    let (is_interpolated, after_prefix) = match text.strip_prefix('f') {
+        Some(after_prefix) => (true, after_prefix),
+        None => (false, text),
+    };
+    let prefix_width = text.len() - after_prefix.len();
+
+    if after_prefix.starts_with("\"\"\"") {
+        let mode = Mode::String {
+            quote: "\"\"\"",
+            is_interpolated,
+        };
+        return Some((mode, prefix_width + 3));
+    }
+    if after_prefix.starts_with('"') {
+        let mode = Mode::String {
+            quote: "\"",
+            is_interpolated,
+        };
+        return Some((mode, prefix_width + 1));
+    }
+
+    let after_prefix = text.strip_prefix('r')?;
+    let hash_count = after_prefix.len() - after_prefix.trim_start_matches('#').len();
+    after_prefix[hash_count..]
+        .starts_with('"')
+        .then_some((Mode::RawString { hash_count }, 1 + hash_count + 1))
+}
+
+/// Returns the width of the char literal that `text` starts with, if any, so that quotes and
+/// braces inside it such as `'}'` are skipped.
+fn char_literal_width(text: &str) -> Option<usize> {
+    let mut chars = text.strip_prefix('\'')?.char_indices();
+    while let Some((index, current_char)) = chars.next() {
+        match current_char {
+            '\'' => return Some(1 + index + 1),
+            '\\' => _ = chars.next(),
+            '\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
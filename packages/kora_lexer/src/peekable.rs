@@ -0,0 +1,63 @@
+use alloc::collections::VecDeque;
+
+use crate::prelude::*;
+use crate::{
+    error::SyntaxError,
+    lexer::Lexer,
+    token::{Token, TokenKind},
+};
+
+/// A lexer that supports looking ahead any number of tokens without consuming them.
+/// Whitespace and comment tokens are skipped, so only significant tokens (including doc comments)
+/// are returned.
+pub struct PeekableLexer<'source> {
+    lexer: Lexer<'source>,
+
+    /// Tokens that have been lexed to look ahead but have not been consumed yet.
+    lookahead: VecDeque<Token<'source>>,
+}
+
+impl<'source> PeekableLexer<'source> {
+    pub fn new(source_code: &'source str) -> Self {
+        Self {
+            lexer: Lexer::builder().skip_trivia(true).build(source_code),
+            lookahead: VecDeque::new(),
+        }
+    }
+
+    /// Returns the next token without consuming it.
+    pub fn peek(&mut self) -> Option<&Token<'source>> {
+        self.peek_nth(0)
+    }
+
+    /// Returns the `n`th next token without consuming it, where `peek_nth(0)` is the next token.
+    pub fn peek_nth(&mut self, n: usize) -> Option<&Token<'source>> {
+        while self.lookahead.len() <= n {
+            let token = self.lexer.next()?;
+            self.lookahead.push_back(token);
+        }
+        self.lookahead.get(n)
+    }
+
+    /// Returns the kind of the `n`th next token without consuming it.
+    pub fn peek_kind(&mut self, n: usize) -> Option<TokenKind> {
+        self.peek_nth(n).map(|token| token.kind)
+    }
+
+    /// Syntax errors found so far, including the ones found while looking ahead.
+    pub fn errors(&self) -> &[SyntaxError] {
+        self.lexer.errors()
+    }
+
+    /// Consumes the lexer, returning the syntax errors found.
+    pub fn finish(self) -> Vec<SyntaxError> {
+        self.lexer.finish()
+    }
+}
+
+impl<'source> Iterator for PeekableLexer<'source> {
+    type Item = Token<'source>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lookahead.pop_front().or_else(|| self.lexer.next())
+    }
+}
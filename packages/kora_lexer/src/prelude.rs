@@ -0,0 +1,10 @@
+//! Items of the standard prelude that come from `alloc`, so that the crate can be built without
+//! `std`. Every module that allocates imports this prelude.
+
+pub(crate) use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
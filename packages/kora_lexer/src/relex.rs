@@ -0,0 +1,138 @@
+use crate::prelude::*;
+use crate::{error::SyntaxError, lexer::Lexer, span::Span, token::Token};
+
+/// A replacement of a range of the source code with new text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Range of the old source code that is replaced.
+    pub span: Span,
+    /// Text that replaces the range. An empty text deletes the range.
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(span: Span, text: impl Into<String>) -> Self {
+        Self {
+            span,
+            text: text.into(),
+        }
+    }
+
+    /// Returns the source code with the edit applied.
+    pub fn apply(&self, source_code: &str) -> String {
+        let mut edited = String::with_capacity(source_code.len() + self.text.len());
+        edited.push_str(&source_code[..self.span.start as usize]);
+        edited.push_str(&self.text);
+        edited.push_str(&source_code[self.span.end as usize..]);
+        edited
+    }
+
+    /// Number of bytes that the code after the edit moves by.
+    fn delta(&self) -> i64 {
+        self.text.len() as i64 - self.span.len() as i64
+    }
+}
+
+impl<'source> Lexer<'source> {
+    /// Tokenizes `source_code`, which is the result of applying `edit` to the source code of
+    /// `old_tokens` and `old_errors`, only re-tokenizing the region damaged by the edit.
+    /// The tokens and errors before and after the damaged region are reused.
+    ///
+    /// The result is the same as calling [`Lexer::tokenize`] on the edited source code.
+    pub fn relex(
+        source_code: &'source str,
+        old_tokens: &[Token<'_>],
+        old_errors: &[SyntaxError],
+        edit: &TextEdit,
+    ) -> (Vec<Token<'source>>, Vec<SyntaxError>) {
+        // Tokens only look ahead within their own line, so the code that follows a token with a
+        // newline can't change how the tokens before it are lexed. We restart after the last of
+        // these tokens that ends before the edit.
+        let reused_prefix_len = old_tokens
+            .iter()
+            .rposition(|token| token.span.end < edit.span.start && token.text.contains('\n'))
+            .map_or(0, |index| index + 1);
+        let restart_offset = match reused_prefix_len {
+            0 => 0,
+            len => old_tokens[len - 1].span.end,
+        };
+
+        let mut tokens = Vec::with_capacity(old_tokens.len());
+        let mut errors = Vec::new();
+
+        tokens.extend(
+            old_tokens[..reused_prefix_len]
+                .iter()
+                .map(|token| rebase_token(source_code, token, 0)),
+        );
+        errors.extend(
+            old_errors
+                .iter()
+                .filter(|error| error.span().start < restart_offset)
+                .cloned(),
+        );
+
+        // Tokens that start after the edit have the same text as before, just moved by the size
+        // difference of the edit. Once the new tokens reach the start of one of them, the rest of
+        // the tokens are the same as before.
+        let delta = edit.delta();
+        let mut old_suffix = old_tokens[reused_prefix_len..]
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| token.span.start >= edit.span.end)
+            .peekable();
+
+        let mut lexer = Lexer::starting_at(source_code, restart_offset as usize);
+        let mut resync = None;
+        for token in lexer.by_ref() {
+            let new_end = token.span.end as i64;
+            tokens.push(token);
+
+            // Skip the old tokens that the new tokens have already moved past
+            while old_suffix
+                .next_if(|(_, old_token)| old_token.span.start as i64 + delta < new_end)
+                .is_some()
+            {}
+            if let Some(&(index, old_token)) = old_suffix.peek() {
+                if old_token.span.start as i64 + delta == new_end {
+                    resync = Some((reused_prefix_len + index, old_token.span.start));
+                    break;
+                }
+            }
+        }
+        errors.extend(lexer.finish());
+
+        if let Some((resync_index, resync_offset)) = resync {
+            tokens.extend(
+                old_tokens[resync_index..]
+                    .iter()
+                    .map(|token| rebase_token(source_code, token, delta)),
+            );
+            errors.extend(
+                old_errors
+                    .iter()
+                    .filter(|error| error.span().start >= resync_offset)
+                    .map(|error| error.moved_by(delta)),
+            );
+        }
+
+        (tokens, errors)
+    }
+}
+
+/// Returns the same token moved by `delta` bytes, with its text taken from the new source code.
+fn rebase_token<'source>(
+    source_code: &'source str,
+    token: &Token<'_>,
+    delta: i64,
+) -> Token<'source> {
+    let span = Span::new(
+        (token.span.start as i64 + delta) as u32,
+        (token.span.end as i64 + delta) as u32,
+    );
+    Token {
+        kind: token.kind,
+        text: &source_code[span.start as usize..span.end as usize],
+        span,
+    }
+}
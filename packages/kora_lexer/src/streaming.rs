@@ -0,0 +1,214 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+};
+
+use crate::{
+    error::SyntaxError,
+    lexer::Lexer,
+    span::Span,
+    token::{OwnedToken, Token},
+};
+
+/// Number of bytes read from the reader at once.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A lexer that tokenizes source code from any [`Read`] without loading all of it in memory.
+/// It returns owned tokens, with spans relative to the start of the stream.
+///
+/// The code that is read is buffered until the lexer is sure that the next tokens can't change
+/// when more code is read. Tokens never look ahead past the end of their line, so this happens at
+/// least once per line. Code that stays in the buffer, such as a long line or a block comment, is
+/// tokenized again each time the buffer doubles, so lexing takes linear time.
+pub struct StreamingLexer<R> {
+    reader: R,
+
+    /// Source code that has been read but not tokenized yet.
+    buffer: String,
+    /// Byte offset of the start of the buffer in the stream.
+    buffer_offset: usize,
+    /// Whether the stream starts with a byte order mark, once the start has been tokenized.
+    has_byte_order_mark: bool,
+    /// Length that the buffer must reach before it is tokenized again.
+    min_tokenize_len: usize,
+    /// Bytes at the end of the last read that don't make up a whole UTF-8 character yet.
+    incomplete_char: Vec<u8>,
+    /// Whether the reader has reached the end of the stream.
+    is_end_of_stream: bool,
+
+    /// Tokens that are ready to be returned.
+    tokens: VecDeque<OwnedToken>,
+    /// Syntax errors.
+    errors: Vec<SyntaxError>,
+}
+
+impl<R: Read> StreamingLexer<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            buffer_offset: 0,
+            has_byte_order_mark: false,
+            min_tokenize_len: 0,
+            incomplete_char: Vec::new(),
+            is_end_of_stream: false,
+            tokens: VecDeque::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Syntax errors found so far.
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+
+    /// Consumes the lexer, returning the syntax errors found.
+    pub fn finish(self) -> Vec<SyntaxError> {
+        self.errors
+    }
+
+    /// Reads the next chunk of the stream into the buffer.
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let mut chunk = std::mem::take(&mut self.incomplete_char);
+        let incomplete_char_len = chunk.len();
+        chunk.resize(incomplete_char_len + CHUNK_SIZE, 0);
+
+        let read_len = loop {
+            match self.reader.read(&mut chunk[incomplete_char_len..]) {
+                Ok(read_len) => break read_len,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+        };
+        chunk.truncate(incomplete_char_len + read_len);
+
+        if read_len == 0 {
+            self.is_end_of_stream = true;
+            if !chunk.is_empty() {
+                return Err(invalid_utf8_error());
+            }
+            return Ok(());
+        }
+
+        let valid_len = match std::str::from_utf8(&chunk) {
+            Ok(_) => chunk.len(),
+            // The chunk ends in the middle of a character, which is completed by the next chunk
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(_) => return Err(invalid_utf8_error()),
+        };
+        self.incomplete_char = chunk.split_off(valid_len);
+        self.buffer
+            .push_str(std::str::from_utf8(&chunk).expect("chunk is valid UTF-8"));
+
+        Ok(())
+    }
+
+    /// Tokenizes the buffered code, keeping the tokens that can't change when more code is read.
+    fn tokenize_buffer(&mut self) {
+        // Tokens that end after the last newline may continue on the code that hasn't been read
+        // yet, unless there is no more code to read.
+        let final_end = if self.is_end_of_stream {
+            self.buffer.len()
+        } else {
+            self.buffer.rfind('\n').map_or(0, |index| index + 1)
+        };
+
+        let follows_byte_order_mark =
+            self.has_byte_order_mark && self.buffer_offset == '\u{FEFF}'.len_utf8();
+        let mut lexer =
+            Lexer::continuing_at(&self.buffer, self.buffer_offset, follows_byte_order_mark);
+        let mut tokenized_len = 0;
+        for token in lexer.by_ref() {
+            let end = token.span.end as usize;
+            let is_final = self.is_end_of_stream || (end <= final_end && end < self.buffer.len());
+            if !is_final {
+                break;
+            }
+
+            self.tokens
+                .push_back(OwnedToken::from(token).moved_by(self.buffer_offset));
+            tokenized_len = end;
+        }
+
+        let delta = self.buffer_offset as i64;
+        self.errors.extend(
+            lexer
+                .errors()
+                .iter()
+                .filter(|error| (error.span().start as usize) < tokenized_len)
+                .map(|error| error.moved_by(delta)),
+        );
+
+        if self.buffer_offset == 0 && tokenized_len > 0 {
+            self.has_byte_order_mark = self.buffer.starts_with('\u{FEFF}');
+        }
+        self.buffer.drain(..tokenized_len);
+        self.buffer_offset += tokenized_len;
+        self.min_tokenize_len = self.buffer.len() * 2;
+    }
+}
+
+impl<'chunk, I: Iterator<Item = &'chunk str>> StreamingLexer<ChunkReader<'chunk, I>> {
+    /// Creates a lexer that tokenizes the source code fed by an iterator of chunks, such as lines
+    /// or network messages.
+    pub fn from_chunks(chunks: impl IntoIterator<IntoIter = I>) -> Self {
+        Self::new(ChunkReader {
+            chunks: chunks.into_iter(),
+            current_chunk: &[],
+        })
+    }
+}
+
+impl<R: Read> Iterator for StreamingLexer<R> {
+    type Item = io::Result<OwnedToken>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.tokens.pop_front() {
+                return Some(Ok(token));
+            }
+            if self.is_end_of_stream {
+                return None;
+            }
+
+            if let Err(error) = self.read_chunk() {
+                // Stop reading after an error, but keep the tokens read so far
+                self.is_end_of_stream = true;
+                return Some(Err(error));
+            }
+            if self.is_end_of_stream || self.buffer.len() >= self.min_tokenize_len {
+                self.tokenize_buffer();
+            }
+        }
+    }
+}
+
+/// Adapts an iterator of string chunks to [`Read`]. See [`StreamingLexer::from_chunks`].
+pub struct ChunkReader<'chunk, I> {
+    chunks: I,
+    /// Part of the current chunk that has not been read yet.
+    current_chunk: &'chunk [u8],
+}
+
+impl<'chunk, I: Iterator<Item = &'chunk str>> Read for ChunkReader<'chunk, I> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        while self.current_chunk.is_empty() {
+            match self.chunks.next() {
+                Some(chunk) => self.current_chunk = chunk.as_bytes(),
+                None => return Ok(0),
+            }
+        }
+
+        let read_len = buffer.len().min(self.current_chunk.len());
+        buffer[..read_len].copy_from_slice(&self.current_chunk[..read_len]);
+        self.current_chunk = &self.current_chunk[read_len..];
+        Ok(read_len)
+    }
+}
+
+fn invalid_utf8_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    )
+}
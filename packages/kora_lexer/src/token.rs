@@ -1,26 +1,134 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::prelude::*;
+use crate::span::Span;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token<'source> {
     pub kind: TokenKind,
     pub text: &'source str,
+    /// Byte range of the token in the source code.
+    pub span: Span,
+}
+
+/// A compact token that stores the position of its text instead of the text itself, for when
+/// many files are tokenized at once, such as when indexing a workspace. The text can be resolved
+/// with [`RawToken::text`] given the source code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawToken {
+    pub kind: TokenKind,
+    /// Byte offset of the token in the source code.
+    pub start: u32,
+    /// Length of the token text in bytes.
+    pub len: u32,
+}
+
+impl RawToken {
+    /// Byte range of the token in the source code.
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.start + self.len)
+    }
+
+    /// Returns the text of the token, given the source code that it was lexed from.
+    pub fn text<'source>(&self, source_code: &'source str) -> &'source str {
+        &source_code[self.start as usize..(self.start + self.len) as usize]
+    }
+}
+
+impl From<Token<'_>> for RawToken {
+    fn from(token: Token<'_>) -> Self {
+        Self {
+            kind: token.kind,
+            start: token.span.start,
+            len: token.span.len(),
+        }
+    }
+}
+
+/// A token that owns its text, for when the source code doesn't outlive the token, such as when
+/// tokenizing a stream with [`crate::StreamingLexer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedToken {
+    pub kind: TokenKind,
+    pub text: String,
+    /// Byte range of the token in the source code.
+    pub span: Span,
+}
+
+impl<'source> Token<'source> {
+    /// Text of the token, NFC-normalized if it is an identifier, so that canonically equivalent
+    /// identifiers such as `café` written with a precomposed or a combining accent compare equal.
+    /// Only allocates if the identifier is not already normalized.
+    pub fn normalized_text(&self) -> Cow<'source, str> {
+        if self.kind != TokenKind::Identifier || is_nfc(self.text) {
+            return Cow::Borrowed(self.text);
+        }
+        Cow::Owned(self.text.nfc().collect())
+    }
+}
+
+/// Prints the token as `PlusEqual ("+=") @ 10..12`.
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?}) @ {:?}", self.kind, self.text, self.span)
+    }
+}
+
+impl OwnedToken {
+    /// Returns the same token with its span moved forward by `offset` bytes.
+    pub(crate) fn moved_by(mut self, offset: usize) -> Self {
+        self.span = Span::new(
+            self.span.start + offset as u32,
+            self.span.end + offset as u32,
+        );
+        self
+    }
+}
+
+/// Prints the token as `PlusEqual ("+=") @ 10..12`.
+impl fmt::Display for OwnedToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?}) @ {:?}", self.kind, self.text, self.span)
+    }
+}
+
+impl From<Token<'_>> for OwnedToken {
+    fn from(token: Token<'_>) -> Self {
+        Self {
+            kind: token.kind,
+            text: token.text.to_string(),
+            span: token.span,
+        }
+    }
 }
 
 #[rustfmt::skip]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TokenKind {
     // # Literals.
     /// Any word made up of valid identifier characters that is not a keyword.
     Identifier,
     /// An integer literal such as `0`, `0x123`, `0o123` and `0b1010`
     IntegerLiteral,
-    /// A float literal such as `3.14`.
+    /// A float literal such as `3.14`, `1e10` and `2.5e-3`.
     FloatLiteral,
-    /// A string literal, including prefixed strings, such as `"Hello, world"` or `f"Hello, {world}"`.
+    /// A string literal, including prefixed, raw and multiline strings, such as `"Hello, world"`,
+    /// `f"Hello, {world}"`, `r#"Hello, "world""#` or `"""Hello, world"""`.
     StringLiteral,
+    /// A char literal, including escape sequences, such as `'a'`, `'\n'` or `'\u{1F600}'`.
+    CharLiteral,
 
     // # Arithmetic operators.
     /// The `=` character.
     Equal,
-    /// The `==` character..
+    /// The `==` characters.
     EqualEqual,
     /// The `!=` characters.
     NotEqual,
@@ -34,6 +142,8 @@ pub enum TokenKind {
     Divide,
     /// The `%` character.
     Modulo,
+    /// The `**` characters.
+    Power,
     /// The `+=` characters.
     PlusEqual,
     /// The `-=` character.
@@ -44,6 +154,8 @@ pub enum TokenKind {
     DivideEqual,
     /// The `%=` character.
     ModuloEqual,
+    /// The `**=` characters.
+    PowerEqual,
 
     // # Logical operators.
     /// The `!` character.
@@ -72,6 +184,10 @@ pub enum TokenKind {
     LessThanLessThan,
     /// The `>>` characters.
     GreaterThanGreaterThan,
+    /// The `<<=` characters.
+    LessThanLessThanEqual,
+    /// The `>>=` characters.
+    GreaterThanGreaterThanEqual,
 
     // # Punctuation.
     /// The `(` character.
@@ -94,6 +210,30 @@ pub enum TokenKind {
     Semicolon,
     /// The `:` character.
     Colon,
+    /// The `::` characters.
+    ColonColon,
+    /// The `->` characters.
+    Arrow,
+    /// The `=>` characters.
+    FatArrow,
+    /// The `|>` characters.
+    Pipe,
+    /// The `..` characters.
+    DotDot,
+    /// The `..=` characters.
+    DotDotEqual,
+    /// The `...` characters.
+    DotDotDot,
+    /// The `?` character.
+    Question,
+    /// The `@` character.
+    At,
+    /// The `#` character.
+    Hash,
+    /// The `$` character.
+    Dollar,
+    /// The `~` character.
+    Tilde,
 
     // # Keywords.
     /// The `def` keyword.
@@ -110,8 +250,258 @@ pub enum TokenKind {
     For,
     /// The `struct` keyword.
     Struct,
+    /// The `enum` keyword.
+    Enum,
+    /// The `fn` keyword.
+    Fn,
+    /// The `let` keyword.
+    Let,
+    /// The `mut` keyword.
+    Mut,
+    /// The `return` keyword.
+    Return,
+    /// The `while` keyword.
+    While,
+    /// The `in` keyword.
+    In,
+    /// The `break` keyword.
+    Break,
+    /// The `continue` keyword.
+    Continue,
+    /// The `match` keyword.
+    Match,
+    /// The `import` keyword.
+    Import,
+    /// The `as` keyword.
+    As,
+    /// The `pub` keyword.
+    Pub,
+    /// The `const` keyword.
+    Const,
+    /// The `true` literal keyword.
+    True,
+    /// The `false` literal keyword.
+    False,
+    /// The `none` literal keyword, which represents the absence of a value.
+    None,
     /// Trivia, such as whitespace or comments.
     Trivia,
+    /// A doc comment, such as `/// Documentation.` for the next item or `//! Documentation.` for
+    /// the enclosing item, including the newline.
+    DocComment,
     /// Illegal character.
-    Illegal
+    Illegal,
+    /// The end of the source code, with empty text. Only emitted if it has been enabled with
+    /// [`crate::LexerBuilder::emit_eof`].
+    Eof,
+}
+
+impl TokenKind {
+    /// Returns the kind of the keyword with the given text, if it is one.
+    ///
+    /// Keywords are bucketed by length, so an identifier is only compared against the few
+    /// keywords that have its length, and identifiers longer than every keyword are rejected
+    /// without any comparison.
+    pub fn from_keyword(text: &str) -> Option<Self> {
+        let kind = match text.len() {
+            2 => match text {
+                "if" => Self::If,
+                "fn" => Self::Fn,
+                "in" => Self::In,
+                "as" => Self::As,
+                _ => return None,
+            },
+            3 => match text {
+                "def" => Self::Def,
+                "for" => Self::For,
+                "let" => Self::Let,
+                "mut" => Self::Mut,
+                "pub" => Self::Pub,
+                _ => return None,
+            },
+            4 => match text {
+                "with" => Self::With,
+                "else" => Self::Else,
+                "enum" => Self::Enum,
+                "true" => Self::True,
+                "none" => Self::None,
+                _ => return None,
+            },
+            5 => match text {
+                "while" => Self::While,
+                "break" => Self::Break,
+                "match" => Self::Match,
+                "false" => Self::False,
+                "const" => Self::Const,
+                _ => return None,
+            },
+            6 => match text {
+                "extend" => Self::Extend,
+                "struct" => Self::Struct,
+                "return" => Self::Return,
+                "import" => Self::Import,
+                _ => return None,
+            },
+            8 => match text {
+                "continue" => Self::Continue,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// Whether the kind is a keyword, including the literal keywords `true`, `false` and `none`.
+    pub fn is_keyword(self) -> bool {
+        matches!(
+            self,
+            Self::Def
+                | Self::Extend
+                | Self::With
+                | Self::If
+                | Self::Else
+                | Self::For
+                | Self::Struct
+                | Self::Enum
+                | Self::Fn
+                | Self::Let
+                | Self::Mut
+                | Self::Return
+                | Self::While
+                | Self::In
+                | Self::Break
+                | Self::Continue
+                | Self::Match
+                | Self::Import
+                | Self::As
+                | Self::Pub
+                | Self::Const
+                | Self::True
+                | Self::False
+                | Self::None
+        )
+    }
+
+    /// Whether the kind is a literal, including the literal keywords `true`, `false` and `none`.
+    pub fn is_literal(self) -> bool {
+        matches!(
+            self,
+            Self::IntegerLiteral
+                | Self::FloatLiteral
+                | Self::StringLiteral
+                | Self::CharLiteral
+                | Self::True
+                | Self::False
+                | Self::None
+        )
+    }
+
+    /// Whether the kind is an arithmetic, logical or bitwise operator, including assignments such
+    /// as `=` and `+=`.
+    pub fn is_operator(self) -> bool {
+        matches!(
+            self,
+            Self::Equal
+                | Self::EqualEqual
+                | Self::NotEqual
+                | Self::Plus
+                | Self::Minus
+                | Self::Multiply
+                | Self::Divide
+                | Self::Modulo
+                | Self::Power
+                | Self::PlusEqual
+                | Self::MinusEqual
+                | Self::MultiplyEqual
+                | Self::DivideEqual
+                | Self::ModuloEqual
+                | Self::PowerEqual
+                | Self::Not
+                | Self::OrOr
+                | Self::AndAnd
+                | Self::LessThan
+                | Self::GreaterThan
+                | Self::LessThanEqual
+                | Self::GreaterThanEqual
+                | Self::And
+                | Self::Or
+                | Self::Caret
+                | Self::LessThanLessThan
+                | Self::GreaterThanGreaterThan
+                | Self::LessThanLessThanEqual
+                | Self::GreaterThanGreaterThanEqual
+        )
+    }
+
+    /// Whether the kind is punctuation, such as `,`, `::` or a delimiter.
+    pub fn is_punctuation(self) -> bool {
+        matches!(
+            self,
+            Self::LeftParenthesis
+                | Self::RightParenthesis
+                | Self::LeftBracket
+                | Self::RightBracket
+                | Self::LeftBrace
+                | Self::RightBrace
+                | Self::Comma
+                | Self::Dot
+                | Self::Semicolon
+                | Self::Colon
+                | Self::ColonColon
+                | Self::Arrow
+                | Self::FatArrow
+                | Self::Pipe
+                | Self::DotDot
+                | Self::DotDotEqual
+                | Self::DotDotDot
+                | Self::Question
+                | Self::At
+                | Self::Hash
+                | Self::Dollar
+                | Self::Tilde
+        )
+    }
+
+    /// Whether the kind is whitespace or a comment. Doc comments are not trivia because they
+    /// document the code that follows them.
+    pub fn is_trivia(self) -> bool {
+        self == Self::Trivia
+    }
+
+    /// Whether the kind is `(`, `[` or `{`.
+    pub fn is_opening_delimiter(self) -> bool {
+        matches!(
+            self,
+            Self::LeftParenthesis | Self::LeftBracket | Self::LeftBrace
+        )
+    }
+
+    /// Whether the kind is `)`, `]` or `}`.
+    pub fn is_closing_delimiter(self) -> bool {
+        matches!(
+            self,
+            Self::RightParenthesis | Self::RightBracket | Self::RightBrace
+        )
+    }
+
+    /// Returns the delimiter that closes or opens this one, such as `)` for `(` and `(` for `)`.
+    pub fn matching_delimiter(self) -> Option<Self> {
+        let delimiter = match self {
+            Self::LeftParenthesis => Self::RightParenthesis,
+            Self::RightParenthesis => Self::LeftParenthesis,
+            Self::LeftBracket => Self::RightBracket,
+            Self::RightBracket => Self::LeftBracket,
+            Self::LeftBrace => Self::RightBrace,
+            Self::RightBrace => Self::LeftBrace,
+            _ => return None,
+        };
+        Some(delimiter)
+    }
+}
+
+/// Prints the canonical name of the kind, which is the name of its variant, such as `PlusEqual`.
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
 }
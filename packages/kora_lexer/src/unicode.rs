@@ -0,0 +1,68 @@
+//! Unicode characters that can make source code look different from how it is lexed, used to
+//! detect "trojan source" attacks.
+
+/// Whether the character is a Unicode bidirectional control character, which can reorder how the
+/// surrounding code is displayed, such as hiding code inside a comment.
+pub(crate) fn is_bidi_control(char: char) -> bool {
+    matches!(
+        char,
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// Returns the ASCII character that the given character is easily confused with, if any.
+/// This only covers the most common Cyrillic and Greek lookalikes of ASCII letters, not the
+/// whole Unicode confusables table.
+pub(crate) fn confusable_ascii(char: char) -> Option<char> {
+    let ascii_char = match char {
+        // Cyrillic
+        'а' => 'a',
+        'в' => 'B',
+        'е' => 'e',
+        'і' => 'i',
+        'ј' => 'j',
+        'к' => 'k',
+        'о' => 'o',
+        'р' => 'p',
+        'с' => 'c',
+        'ѕ' => 's',
+        'у' => 'y',
+        'х' => 'x',
+        'А' => 'A',
+        'В' => 'B',
+        'Е' => 'E',
+        'І' => 'I',
+        'Ј' => 'J',
+        'К' => 'K',
+        'М' => 'M',
+        'Н' => 'H',
+        'О' => 'O',
+        'Р' => 'P',
+        'С' => 'C',
+        'Ѕ' => 'S',
+        'Т' => 'T',
+        'Х' => 'X',
+        // Greek
+        'α' => 'a',
+        'ι' => 'i',
+        'ν' => 'v',
+        'ο' => 'o',
+        'ρ' => 'p',
+        'Α' => 'A',
+        'Β' => 'B',
+        'Ε' => 'E',
+        'Ζ' => 'Z',
+        'Η' => 'H',
+        'Ι' => 'I',
+        'Κ' => 'K',
+        'Μ' => 'M',
+        'Ν' => 'N',
+        'Ο' => 'O',
+        'Ρ' => 'P',
+        'Τ' => 'T',
+        'Υ' => 'Y',
+        'Χ' => 'X',
+        _ => return None,
+    };
+    Some(ascii_char)
+}
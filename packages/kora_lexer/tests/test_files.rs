@@ -0,0 +1,32 @@
+#![cfg(feature = "parallel")]
+
+use std::{fs, io, path::PathBuf};
+
+use kora_lexer::{tokenize_files, Lexer};
+
+#[test]
+fn test_tokenize_files() {
+    let mut paths = fs::read_dir("tests/inputs")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.push(PathBuf::from("tests/inputs/missing.kora"));
+
+    let results = tokenize_files(&paths);
+
+    assert_eq!(results.len(), paths.len());
+    for (path, result) in paths.iter().zip(&results[..paths.len() - 1]) {
+        let file_tokens = result.as_ref().unwrap();
+        let source_code = fs::read_to_string(path).unwrap();
+        let (tokens, errors) = Lexer::tokenize(&source_code);
+
+        assert_eq!(&file_tokens.path, path);
+        assert_eq!(file_tokens.tokens.len(), tokens.len());
+        assert_eq!(file_tokens.errors, errors);
+    }
+
+    let error = results.last().unwrap().as_ref().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    assert!(error.to_string().contains("missing.kora"));
+}
@@ -1,17 +1,200 @@
-use kora_lexer::Lexer;
+use kora_lexer::{Lexer, RawToken, Severity, SyntaxError, TokenKind};
 
 #[test]
 fn test_lexer() {
     insta::glob!("inputs/*.kora", |path| {
         let input = std::fs::read_to_string(path).unwrap();
 
-        let tokens = Lexer::new(&input).collect::<Vec<_>>();
+        let (tokens, errors) = Lexer::tokenize(&input);
 
         insta::with_settings!({
             description => &input,
             omit_expression => true,
         }, {
             insta::assert_debug_snapshot!(tokens);
+            insta::assert_debug_snapshot!("errors", errors);
         });
     })
 }
+
+#[test]
+fn test_tokenize_without_trivia() {
+    let (tokens, errors) = Lexer::tokenize_without_trivia("/// Docs\ndef  add // comment\n€");
+
+    let kinds = tokens.iter().map(|token| token.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::DocComment,
+            TokenKind::Def,
+            TokenKind::Identifier,
+            TokenKind::Illegal
+        ]
+    );
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_checkpoint_and_rewind() {
+    let mut lexer = Lexer::new("a € b");
+    lexer.next();
+
+    let checkpoint = lexer.checkpoint();
+    let speculative_tokens = lexer.by_ref().collect::<Vec<_>>();
+    assert_eq!(lexer.errors().len(), 1);
+
+    lexer.rewind(checkpoint);
+    assert!(lexer.errors().is_empty());
+    assert_eq!(lexer.collect::<Vec<_>>(), speculative_tokens);
+}
+
+#[test]
+fn test_skip_trivia() {
+    let lexer = Lexer::builder()
+        .skip_trivia(true)
+        .build("  // comment\n/// Docs\ndef /* comment */ add  ");
+
+    let kinds = lexer.map(|token| token.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [TokenKind::DocComment, TokenKind::Def, TokenKind::Identifier]
+    );
+}
+
+#[test]
+fn test_lexer_builder() {
+    let kinds = |lexer: Lexer| lexer.map(|token| token.kind).collect::<Vec<_>>();
+
+    let lexer = Lexer::builder()
+        .skip_trivia(true)
+        .keyword("fun", TokenKind::Def)
+        .remove_keyword("match")
+        .emit_eof(true)
+        .build("fun match");
+    assert_eq!(
+        kinds(lexer),
+        [TokenKind::Def, TokenKind::Identifier, TokenKind::Eof]
+    );
+
+    let lexer = Lexer::builder()
+        .comment_includes_newline(false)
+        .build("// comment\n");
+    let texts = lexer.map(|token| token.text).collect::<Vec<_>>();
+    assert_eq!(texts, ["// comment", "\n"]);
+
+    let mut lexer = Lexer::builder().forbid_tabs(true).build("\t a \t");
+    assert_eq!(lexer.by_ref().count(), 3);
+    let tab_spans = lexer
+        .finish()
+        .iter()
+        .map(|error| error.span().start)
+        .collect::<Vec<_>>();
+    assert_eq!(tab_spans, [0, 4]);
+}
+
+#[test]
+fn test_byte_order_mark_is_a_warning() {
+    let (tokens, errors) = Lexer::tokenize("\u{FEFF}let a = 1 \u{FEFF}");
+
+    assert_eq!(tokens[0].kind, TokenKind::Trivia);
+    assert_eq!(tokens.last().unwrap().kind, TokenKind::Illegal);
+    let severities = errors
+        .iter()
+        .map(|error| error.severity())
+        .collect::<Vec<_>>();
+    assert_eq!(severities, [Severity::Warning, Severity::Error]);
+
+    // After the start, a byte order mark is part of the illegal characters around it
+    let (tokens, errors) = Lexer::tokenize("a €\u{FEFF}€");
+    assert_eq!(tokens.last().unwrap().kind, TokenKind::Illegal);
+    assert_eq!(tokens.last().unwrap().text, "€\u{FEFF}€");
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_normalized_text() {
+    let (tokens, _) = Lexer::tokenize_without_trivia("caf\u{e9} cafe\u{301}");
+
+    assert_ne!(tokens[0].text, tokens[1].text);
+    assert_eq!(tokens[0].normalized_text(), tokens[1].normalized_text());
+}
+
+#[test]
+fn test_syntax_error_display() {
+    let (_, errors) = Lexer::tokenize("0b12 \"\\q");
+
+    assert!(matches!(
+        errors[0],
+        SyntaxError::InvalidDigitForBase {
+            digit: '2',
+            radix: 2,
+            ..
+        }
+    ));
+    assert_eq!(errors[0].to_string(), "invalid digit `2` in binary literal");
+    assert_eq!(errors[1].to_string(), "unknown escape sequence `\\q`");
+    assert_eq!(errors[2].to_string(), "unterminated string literal");
+}
+
+#[test]
+fn test_token_display() {
+    let (tokens, _) = Lexer::tokenize("a += \"b\"");
+
+    let lines = tokens.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "Identifier (\"a\") @ 0..1",
+            "Trivia (\" \") @ 1..2",
+            "PlusEqual (\"+=\") @ 2..4",
+            "Trivia (\" \") @ 4..5",
+            "StringLiteral (\"\\\"b\\\"\") @ 5..8",
+        ]
+    );
+}
+
+#[test]
+fn test_token_kind_classification() {
+    assert!(TokenKind::Match.is_keyword());
+    assert!(TokenKind::True.is_keyword() && TokenKind::True.is_literal());
+    assert!(TokenKind::PlusEqual.is_operator());
+    assert!(!TokenKind::Arrow.is_operator() && TokenKind::Arrow.is_punctuation());
+    assert!(TokenKind::Trivia.is_trivia() && !TokenKind::DocComment.is_trivia());
+    assert!(TokenKind::LeftBracket.is_opening_delimiter());
+    assert!(TokenKind::RightBrace.is_closing_delimiter());
+    assert_eq!(
+        TokenKind::LeftParenthesis.matching_delimiter(),
+        Some(TokenKind::RightParenthesis)
+    );
+    assert_eq!(TokenKind::Comma.matching_delimiter(), None);
+}
+
+#[test]
+fn test_from_keyword() {
+    let (tokens, _) = Lexer::tokenize_without_trivia(
+        "def extend with if else for struct enum fn let mut return while in break continue \
+         match import as pub true false none",
+    );
+
+    for token in tokens {
+        assert!(token.kind.is_keyword());
+        assert_eq!(TokenKind::from_keyword(token.text), Some(token.kind));
+    }
+    assert_eq!(TokenKind::from_keyword("define"), None);
+    assert_eq!(TokenKind::from_keyword("continues"), None);
+}
+
+#[test]
+fn test_tokenize_raw() {
+    let source_code = "let é = \"ü\" // comment";
+    let (tokens, _) = Lexer::tokenize(source_code);
+    let (raw_tokens, _) = Lexer::tokenize_raw(source_code);
+
+    assert_eq!(std::mem::size_of::<RawToken>(), 12);
+    assert_eq!(raw_tokens.len(), tokens.len());
+    for (raw_token, token) in raw_tokens.iter().zip(&tokens) {
+        assert_eq!(raw_token.kind, token.kind);
+        assert_eq!(raw_token.span(), token.span);
+        assert_eq!(raw_token.text(source_code), token.text);
+    }
+}
@@ -0,0 +1,26 @@
+use kora_lexer::{LineIndex, SourcePosition, Span};
+
+#[test]
+fn test_line_index() {
+    let source_code = "def\n  ídentifier\n\nend";
+    let line_index = LineIndex::new(source_code);
+
+    assert_eq!(line_index.line_count(), 4);
+
+    let position = |line, column| SourcePosition { line, column };
+    assert_eq!(line_index.position(0), position(0, 0));
+    assert_eq!(line_index.position(3), position(0, 3));
+    assert_eq!(line_index.position(4), position(1, 0));
+    assert_eq!(line_index.position(6), position(1, 2));
+    assert_eq!(line_index.position(18), position(2, 0));
+    assert_eq!(line_index.position(19), position(3, 0));
+    // Offsets past the end are clamped
+    assert_eq!(line_index.position(100), position(3, 3));
+
+    assert_eq!(line_index.offset(position(1, 2)), Some(6));
+    assert_eq!(line_index.offset(position(0, 100)), Some(4));
+    assert_eq!(line_index.offset(position(4, 0)), None);
+
+    assert_eq!(line_index.line_span(1), Some(Span::new(4, 18)));
+    assert_eq!(position(1, 2).to_string(), "2:3");
+}
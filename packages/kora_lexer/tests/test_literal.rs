@@ -0,0 +1,54 @@
+use kora_lexer::{interpolation_parts, strip_indentation};
+
+#[test]
+fn test_strip_indentation() {
+    let token_text = "\"\"\"\n    Hello,\n      world\n\n    \"\"\"";
+    assert_eq!(strip_indentation(token_text), "Hello,\n  world\n");
+
+    // The closing quotes are not on their own line, so the indentation is kept
+    let token_text = "\"\"\"\n    Hello,\n    world\"\"\"";
+    assert_eq!(strip_indentation(token_text), "    Hello,\n    world");
+
+    let token_text = "f\"\"\"Hello, {world}\"\"\"";
+    assert_eq!(strip_indentation(token_text), "Hello, {world}");
+}
+
+#[test]
+fn test_interpolation_parts() {
+    use kora_lexer::InterpolationPart::{Expression, Fragment};
+
+    let token_text = r#"f"Hello, {user.name}! {{literal}} {count + 1}""#;
+    let parts = interpolation_parts(token_text);
+    assert_eq!(
+        parts,
+        [
+            Fragment(2..9),
+            Expression(10..19),
+            Fragment(20..34),
+            Expression(35..44)
+        ]
+    );
+    assert_eq!(&token_text[10..19], "user.name");
+    assert_eq!(&token_text[35..44], "count + 1");
+
+    let token_text = "f\"\"\"{ {nested} }\"\"\"";
+    assert_eq!(interpolation_parts(token_text), [Expression(5..15)]);
+
+    let token_text = r#"f"a{ f"b{c}" } {"}"}""#;
+    assert_eq!(
+        interpolation_parts(token_text),
+        [
+            Fragment(2..3),
+            Expression(4..13),
+            Fragment(14..15),
+            Expression(16..19)
+        ]
+    );
+    assert_eq!(&token_text[4..13], r#" f"b{c}" "#);
+
+    let token_text = r#"f"unterminated {name""#;
+    assert_eq!(
+        interpolation_parts(token_text),
+        [Fragment(2..15), Expression(16..20)]
+    );
+}
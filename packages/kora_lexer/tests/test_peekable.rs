@@ -0,0 +1,25 @@
+use kora_lexer::{PeekableLexer, TokenKind};
+
+#[test]
+fn test_peekable_lexer() {
+    let mut lexer = PeekableLexer::new("def add /* comment */ ( a )");
+
+    assert_eq!(lexer.peek_kind(0), Some(TokenKind::Def));
+    assert_eq!(lexer.peek_kind(2), Some(TokenKind::LeftParenthesis));
+    assert_eq!(lexer.peek_nth(4).map(|token| token.text), Some(")"));
+    assert_eq!(lexer.peek_nth(5), None);
+
+    // Looking ahead doesn't consume tokens
+    let kinds = lexer.by_ref().map(|token| token.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::Def,
+            TokenKind::Identifier,
+            TokenKind::LeftParenthesis,
+            TokenKind::Identifier,
+            TokenKind::RightParenthesis
+        ]
+    );
+    assert_eq!(lexer.peek(), None);
+}
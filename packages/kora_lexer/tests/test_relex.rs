@@ -0,0 +1,43 @@
+use kora_lexer::{Lexer, Span, TextEdit};
+
+/// Asserts that relexing after the edit gives the same result as tokenizing the edited source code
+/// from scratch.
+fn assert_relex(source_code: &str, start: u32, end: u32, text: &str) {
+    let (old_tokens, old_errors) = Lexer::tokenize(source_code);
+    let edit = TextEdit::new(Span::new(start, end), text);
+    let edited_source_code = edit.apply(source_code);
+
+    let relexed = Lexer::relex(&edited_source_code, &old_tokens, &old_errors, &edit);
+    assert_eq!(
+        relexed,
+        Lexer::tokenize(&edited_source_code),
+        "{edited_source_code:?}"
+    );
+}
+
+#[test]
+fn test_relex() {
+    let source_code =
+        "def add(a, b) {\n    a + b\n}\n\nlet x = \"text\" // comment\nlet y = 0b01\n";
+
+    // Insertions
+    assert_relex(source_code, 22, 22, "=");
+    assert_relex(source_code, 20, 20, "c");
+    assert_relex(source_code, 0, 0, "\n\n");
+    assert_relex(
+        source_code,
+        source_code.len() as u32,
+        source_code.len() as u32,
+        "z",
+    );
+    // Deletions
+    assert_relex(source_code, 4, 7, "");
+    assert_relex(source_code, 14, 16, "");
+    // Opening a string or comment changes the tokens until the end of the line or file
+    assert_relex(source_code, 35, 35, "\"");
+    assert_relex(source_code, 16, 16, "/*");
+    // Errors before, inside and after the edit
+    assert_relex("€\nlet y = 0b2\n€", 10, 11, "3");
+    assert_relex("€\nlet y = 0b2\n€", 10, 11, "1");
+    assert_relex("a\n€\n'", 0, 1, "b\nc");
+}
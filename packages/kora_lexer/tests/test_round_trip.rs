@@ -0,0 +1,34 @@
+use kora_lexer::Lexer;
+use proptest::prelude::*;
+
+/// Fragments of Kora source code that are combined into inputs that are likely to contain every
+/// kind of token, including unterminated and malformed ones.
+const FRAGMENTS: &[&str] = &[
+    "def", "let", "none", "name", "_", "é", "а", "0", "0x7d1", "0b2", "3.14", "1e-3", "\"", "'",
+    "\\", "\\u{", "}", "{", "f\"", "r#\"", "\"#", "\"\"\"", "//", "///", "/*", "*/", "#!", "+",
+    "**=", "<<=", "..=", "::", "->", "(", ")", " ", "\t", "\n", "\r\n", "\u{a0}", "\u{feff}",
+    "\u{202e}", "€", "`",
+];
+
+/// Checks that concatenating the text of every token, including trivia, reproduces the source
+/// code byte for byte.
+fn check_lossless(source_code: &str) -> Result<(), TestCaseError> {
+    let (tokens, _) = Lexer::tokenize(source_code);
+    let text = tokens.iter().map(|token| token.text).collect::<String>();
+    prop_assert_eq!(text, source_code);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_round_trip_arbitrary(source_code in any::<String>()) {
+        check_lossless(&source_code)?;
+    }
+
+    #[test]
+    fn test_round_trip_fragments(
+        fragments in proptest::collection::vec(proptest::sample::select(FRAGMENTS), 0..32),
+    ) {
+        check_lossless(&fragments.concat())?;
+    }
+}
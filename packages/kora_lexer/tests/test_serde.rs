@@ -0,0 +1,21 @@
+#![cfg(feature = "serde")]
+
+use kora_lexer::{Lexer, SyntaxError, Token};
+
+#[test]
+fn test_serde_round_trip() {
+    let (tokens, errors) = Lexer::tokenize("let a = 0b2");
+
+    let json = serde_json::to_string(&tokens).unwrap();
+    assert_eq!(serde_json::from_str::<Vec<Token>>(&json).unwrap(), tokens);
+
+    let json = serde_json::to_string(&errors).unwrap();
+    assert_eq!(
+        json,
+        r#"[{"InvalidDigitForBase":{"span":{"start":10,"end":11},"digit":"2","radix":2}}]"#
+    );
+    assert_eq!(
+        serde_json::from_str::<Vec<SyntaxError>>(&json).unwrap(),
+        errors
+    );
+}
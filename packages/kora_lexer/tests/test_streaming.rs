@@ -0,0 +1,77 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, Read};
+
+use kora_lexer::{Lexer, OwnedToken, StreamingLexer};
+
+/// A reader that returns one byte at a time, splitting multi-byte characters across reads.
+struct ByteReader<'a>(&'a [u8]);
+
+impl Read for ByteReader<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let Some((&byte, rest)) = self.0.split_first() else {
+            return Ok(0);
+        };
+        buffer[0] = byte;
+        self.0 = rest;
+        Ok(1)
+    }
+}
+
+const SOURCE_CODE: &str = "def añadir(a, b) {\n    a += 0x1F // sumar\n}\n/* a\nmultiline € comment */ \"text\"\n\"\"\"\nmultiline\n\"\"\" 'ab'   \n\n";
+
+#[test]
+fn test_streaming_lexer() {
+    let (tokens, errors) = Lexer::tokenize(SOURCE_CODE);
+    let expected_tokens = tokens.into_iter().map(OwnedToken::from).collect::<Vec<_>>();
+
+    let mut lexer = StreamingLexer::new(ByteReader(SOURCE_CODE.as_bytes()));
+    let streamed_tokens = lexer.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+    assert_eq!(streamed_tokens, expected_tokens);
+    assert_eq!(lexer.finish(), errors);
+
+    let mut lexer = StreamingLexer::from_chunks(SOURCE_CODE.split_inclusive(' '));
+    let streamed_tokens = lexer.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+    assert_eq!(streamed_tokens, expected_tokens);
+    assert_eq!(lexer.finish(), errors);
+}
+
+#[test]
+fn test_streaming_lexer_invalid_utf8() {
+    let mut lexer = StreamingLexer::new(&b"def a\n\xFF"[..]);
+    let error = lexer.find_map(Result::err).unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_streaming_lexer_long_line() {
+    let source_code = format!("\"{}\"\n", "a".repeat(4_000_000));
+    let (tokens, errors) = Lexer::tokenize(&source_code);
+    let expected_tokens = tokens.into_iter().map(OwnedToken::from).collect::<Vec<_>>();
+
+    let mut lexer = StreamingLexer::new(source_code.as_bytes());
+    let streamed_tokens = lexer.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+    assert_eq!(streamed_tokens, expected_tokens);
+    assert_eq!(lexer.finish(), errors);
+}
+
+#[test]
+fn test_streaming_lexer_start_of_file() {
+    for chunks in [
+        &["a\n#!", "x\n"][..],
+        &["#!/usr/bin/env kora\n", "#!x\n"],
+        &["\u{FEFF}", "#!/usr/bin/env kora\n"],
+        &["\u{FEFF}#!/usr/bin/env kora", "\n", "a\n"],
+        &["a\n\u{FEFF}", "b\n"],
+        &["\u{FEFF}", "\u{FEFF}\n"],
+    ] {
+        let source_code = chunks.concat();
+        let (tokens, errors) = Lexer::tokenize(&source_code);
+        let expected_tokens = tokens.into_iter().map(OwnedToken::from).collect::<Vec<_>>();
+
+        let mut lexer = StreamingLexer::from_chunks(chunks.iter().copied());
+        let streamed_tokens = lexer.by_ref().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(streamed_tokens, expected_tokens, "{source_code:?}");
+        assert_eq!(lexer.finish(), errors, "{source_code:?}");
+    }
+}
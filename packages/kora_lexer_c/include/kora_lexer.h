@@ -0,0 +1,124 @@
+/* C ABI for the Kora lexer. See packages/kora_lexer_c/src/lib.rs. */
+
+#ifndef KORA_LEXER_H
+#define KORA_LEXER_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Token kinds. These values are stable, new kinds are only added at the end. */
+#define KORA_TOKEN_IDENTIFIER 0
+#define KORA_TOKEN_INTEGER_LITERAL 1
+#define KORA_TOKEN_FLOAT_LITERAL 2
+#define KORA_TOKEN_STRING_LITERAL 3
+#define KORA_TOKEN_CHAR_LITERAL 4
+#define KORA_TOKEN_EQUAL 5
+#define KORA_TOKEN_EQUAL_EQUAL 6
+#define KORA_TOKEN_NOT_EQUAL 7
+#define KORA_TOKEN_PLUS 8
+#define KORA_TOKEN_MINUS 9
+#define KORA_TOKEN_MULTIPLY 10
+#define KORA_TOKEN_DIVIDE 11
+#define KORA_TOKEN_MODULO 12
+#define KORA_TOKEN_POWER 13
+#define KORA_TOKEN_PLUS_EQUAL 14
+#define KORA_TOKEN_MINUS_EQUAL 15
+#define KORA_TOKEN_MULTIPLY_EQUAL 16
+#define KORA_TOKEN_DIVIDE_EQUAL 17
+#define KORA_TOKEN_MODULO_EQUAL 18
+#define KORA_TOKEN_POWER_EQUAL 19
+#define KORA_TOKEN_NOT 20
+#define KORA_TOKEN_OR_OR 21
+#define KORA_TOKEN_AND_AND 22
+#define KORA_TOKEN_LESS_THAN 23
+#define KORA_TOKEN_GREATER_THAN 24
+#define KORA_TOKEN_LESS_THAN_EQUAL 25
+#define KORA_TOKEN_GREATER_THAN_EQUAL 26
+#define KORA_TOKEN_AND 27
+#define KORA_TOKEN_OR 28
+#define KORA_TOKEN_CARET 29
+#define KORA_TOKEN_LESS_THAN_LESS_THAN 30
+#define KORA_TOKEN_GREATER_THAN_GREATER_THAN 31
+#define KORA_TOKEN_LESS_THAN_LESS_THAN_EQUAL 32
+#define KORA_TOKEN_GREATER_THAN_GREATER_THAN_EQUAL 33
+#define KORA_TOKEN_LEFT_PARENTHESIS 34
+#define KORA_TOKEN_RIGHT_PARENTHESIS 35
+#define KORA_TOKEN_LEFT_BRACKET 36
+#define KORA_TOKEN_RIGHT_BRACKET 37
+#define KORA_TOKEN_LEFT_BRACE 38
+#define KORA_TOKEN_RIGHT_BRACE 39
+#define KORA_TOKEN_COMMA 40
+#define KORA_TOKEN_DOT 41
+#define KORA_TOKEN_SEMICOLON 42
+#define KORA_TOKEN_COLON 43
+#define KORA_TOKEN_COLON_COLON 44
+#define KORA_TOKEN_ARROW 45
+#define KORA_TOKEN_FAT_ARROW 46
+#define KORA_TOKEN_DOT_DOT 47
+#define KORA_TOKEN_DOT_DOT_EQUAL 48
+#define KORA_TOKEN_DOT_DOT_DOT 49
+#define KORA_TOKEN_QUESTION 50
+#define KORA_TOKEN_AT 51
+#define KORA_TOKEN_HASH 52
+#define KORA_TOKEN_DOLLAR 53
+#define KORA_TOKEN_TILDE 54
+#define KORA_TOKEN_DEF 55
+#define KORA_TOKEN_EXTEND 56
+#define KORA_TOKEN_WITH 57
+#define KORA_TOKEN_IF 58
+#define KORA_TOKEN_ELSE 59
+#define KORA_TOKEN_FOR 60
+#define KORA_TOKEN_STRUCT 61
+#define KORA_TOKEN_ENUM 62
+#define KORA_TOKEN_FN 63
+#define KORA_TOKEN_LET 64
+#define KORA_TOKEN_MUT 65
+#define KORA_TOKEN_RETURN 66
+#define KORA_TOKEN_WHILE 67
+#define KORA_TOKEN_IN 68
+#define KORA_TOKEN_BREAK 69
+#define KORA_TOKEN_CONTINUE 70
+#define KORA_TOKEN_MATCH 71
+#define KORA_TOKEN_IMPORT 72
+#define KORA_TOKEN_AS 73
+#define KORA_TOKEN_PUB 74
+#define KORA_TOKEN_TRUE 75
+#define KORA_TOKEN_FALSE 76
+#define KORA_TOKEN_NONE 77
+#define KORA_TOKEN_TRIVIA 78
+#define KORA_TOKEN_DOC_COMMENT 79
+#define KORA_TOKEN_ILLEGAL 80
+#define KORA_TOKEN_EOF 81
+#define KORA_TOKEN_PIPE 82
+#define KORA_TOKEN_CONST 83
+
+/* A token, with its kind and the byte range of its text in the source code. */
+typedef struct KoraToken {
+    uint32_t kind;
+    uint32_t start;
+    uint32_t end;
+} KoraToken;
+
+typedef struct KoraLexer KoraLexer;
+
+/* Creates a lexer for the `length` bytes of UTF-8 source code at `source`, which are copied.
+ * Returns NULL if `source` is NULL or not valid UTF-8. */
+KoraLexer *kora_lexer_new(const uint8_t *source, size_t length);
+
+/* Writes the next token to `token`, returning false if there are no tokens left or `lexer` is
+ * NULL. */
+bool kora_lexer_next_token(KoraLexer *lexer, KoraToken *token);
+
+/* Frees a lexer. Does nothing if `lexer` is NULL. */
+void kora_lexer_free(KoraLexer *lexer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* KORA_LEXER_H */
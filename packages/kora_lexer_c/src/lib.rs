@@ -0,0 +1,188 @@
+//! C ABI for the Kora lexer, so that editors and tools written in other languages can embed it.
+//! See `include/kora_lexer.h` for the declarations.
+//!
+//! ```c
+//! KoraLexer *lexer = kora_lexer_new(source, strlen(source));
+//! KoraToken token;
+//! while (kora_lexer_next_token(lexer, &token)) {
+//!     printf("%u %u..%u\n", token.kind, token.start, token.end);
+//! }
+//! kora_lexer_free(lexer);
+//! ```
+
+use std::{ptr, slice};
+
+use kora_lexer::{Lexer, TokenKind};
+
+/// A token, with its kind and the byte range of its text in the source code.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KoraToken {
+    /// One of the `KORA_TOKEN_*` constants, see [`kind_code`].
+    pub kind: u32,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// A lexer that owns a copy of the source code and its tokens.
+pub struct KoraLexer {
+    tokens: Vec<KoraToken>,
+    next_index: usize,
+}
+
+/// Creates a lexer for the `length` bytes of UTF-8 source code at `source`, which are copied so
+/// they can be freed right after this call.
+/// Returns null if `source` is null or not valid UTF-8.
+///
+/// # Safety
+///
+/// `source` must be null or point to `length` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn kora_lexer_new(source: *const u8, length: usize) -> *mut KoraLexer {
+    if source.is_null() {
+        return ptr::null_mut();
+    }
+    let bytes = slice::from_raw_parts(source, length);
+    let Ok(source_code) = std::str::from_utf8(bytes) else {
+        return ptr::null_mut();
+    };
+
+    let tokens = Lexer::new(source_code)
+        .map(|token| KoraToken {
+            kind: kind_code(token.kind),
+            start: token.span.start,
+            end: token.span.end,
+        })
+        .collect();
+    Box::into_raw(Box::new(KoraLexer {
+        tokens,
+        next_index: 0,
+    }))
+}
+
+/// Writes the next token to `token`, returning false without writing anything if there are no
+/// tokens left or `lexer` is null, like the lexer of an invalid source code.
+///
+/// # Safety
+///
+/// `lexer` must be null or have been returned by [`kora_lexer_new`] and not freed, and `token`
+/// must point to writable memory for a `KoraToken`.
+#[no_mangle]
+pub unsafe extern "C" fn kora_lexer_next_token(
+    lexer: *mut KoraLexer,
+    token: *mut KoraToken,
+) -> bool {
+    let Some(lexer) = lexer.as_mut() else {
+        return false;
+    };
+    let Some(&next_token) = lexer.tokens.get(lexer.next_index) else {
+        return false;
+    };
+    lexer.next_index += 1;
+    *token = next_token;
+    true
+}
+
+/// Frees a lexer. Does nothing if `lexer` is null.
+///
+/// # Safety
+///
+/// `lexer` must be null or have been returned by [`kora_lexer_new`] and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn kora_lexer_free(lexer: *mut KoraLexer) {
+    if !lexer.is_null() {
+        drop(Box::from_raw(lexer));
+    }
+}
+
+/// Stable numeric code of a token kind, which is part of the ABI and must match the
+/// `KORA_TOKEN_*` constants in `include/kora_lexer.h`. New kinds get new codes at the end instead
+/// of following the declaration order of [`TokenKind`].
+fn kind_code(kind: TokenKind) -> u32 {
+    match kind {
+        TokenKind::Identifier => 0,
+        TokenKind::IntegerLiteral => 1,
+        TokenKind::FloatLiteral => 2,
+        TokenKind::StringLiteral => 3,
+        TokenKind::CharLiteral => 4,
+        TokenKind::Equal => 5,
+        TokenKind::EqualEqual => 6,
+        TokenKind::NotEqual => 7,
+        TokenKind::Plus => 8,
+        TokenKind::Minus => 9,
+        TokenKind::Multiply => 10,
+        TokenKind::Divide => 11,
+        TokenKind::Modulo => 12,
+        TokenKind::Power => 13,
+        TokenKind::PlusEqual => 14,
+        TokenKind::MinusEqual => 15,
+        TokenKind::MultiplyEqual => 16,
+        TokenKind::DivideEqual => 17,
+        TokenKind::ModuloEqual => 18,
+        TokenKind::PowerEqual => 19,
+        TokenKind::Not => 20,
+        TokenKind::OrOr => 21,
+        TokenKind::AndAnd => 22,
+        TokenKind::LessThan => 23,
+        TokenKind::GreaterThan => 24,
+        TokenKind::LessThanEqual => 25,
+        TokenKind::GreaterThanEqual => 26,
+        TokenKind::And => 27,
+        TokenKind::Or => 28,
+        TokenKind::Caret => 29,
+        TokenKind::LessThanLessThan => 30,
+        TokenKind::GreaterThanGreaterThan => 31,
+        TokenKind::LessThanLessThanEqual => 32,
+        TokenKind::GreaterThanGreaterThanEqual => 33,
+        TokenKind::LeftParenthesis => 34,
+        TokenKind::RightParenthesis => 35,
+        TokenKind::LeftBracket => 36,
+        TokenKind::RightBracket => 37,
+        TokenKind::LeftBrace => 38,
+        TokenKind::RightBrace => 39,
+        TokenKind::Comma => 40,
+        TokenKind::Dot => 41,
+        TokenKind::Semicolon => 42,
+        TokenKind::Colon => 43,
+        TokenKind::ColonColon => 44,
+        TokenKind::Arrow => 45,
+        TokenKind::FatArrow => 46,
+        TokenKind::DotDot => 47,
+        TokenKind::DotDotEqual => 48,
+        TokenKind::DotDotDot => 49,
+        TokenKind::Question => 50,
+        TokenKind::At => 51,
+        TokenKind::Hash => 52,
+        TokenKind::Dollar => 53,
+        TokenKind::Tilde => 54,
+        TokenKind::Def => 55,
+        TokenKind::Extend => 56,
+        TokenKind::With => 57,
+        TokenKind::If => 58,
+        TokenKind::Else => 59,
+        TokenKind::For => 60,
+        TokenKind::Struct => 61,
+        TokenKind::Enum => 62,
+        TokenKind::Fn => 63,
+        TokenKind::Let => 64,
+        TokenKind::Mut => 65,
+        TokenKind::Return => 66,
+        TokenKind::While => 67,
+        TokenKind::In => 68,
+        TokenKind::Break => 69,
+        TokenKind::Continue => 70,
+        TokenKind::Match => 71,
+        TokenKind::Import => 72,
+        TokenKind::As => 73,
+        TokenKind::Pub => 74,
+        TokenKind::True => 75,
+        TokenKind::False => 76,
+        TokenKind::None => 77,
+        TokenKind::Trivia => 78,
+        TokenKind::DocComment => 79,
+        TokenKind::Illegal => 80,
+        TokenKind::Eof => 81,
+        TokenKind::Pipe => 82,
+        TokenKind::Const => 83,
+    }
+}
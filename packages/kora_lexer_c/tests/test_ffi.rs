@@ -0,0 +1,115 @@
+use std::{collections::HashMap, ptr};
+
+use kora_lexer::Lexer;
+use kora_lexer_c::{kora_lexer_free, kora_lexer_new, kora_lexer_next_token, KoraToken};
+
+/// Lexes the bytes through the C ABI, returning `None` if no lexer could be created.
+fn tokenize(source: Option<&[u8]>) -> Option<Vec<KoraToken>> {
+    let (pointer, length) =
+        source.map_or((ptr::null(), 0), |source| (source.as_ptr(), source.len()));
+    unsafe {
+        let lexer = kora_lexer_new(pointer, length);
+        if lexer.is_null() {
+            return None;
+        }
+        let mut tokens = Vec::new();
+        let mut token = KoraToken {
+            kind: u32::MAX,
+            start: 0,
+            end: 0,
+        };
+        while kora_lexer_next_token(lexer, &mut token) {
+            tokens.push(token);
+        }
+        // The lexer keeps returning false once the tokens are exhausted
+        assert!(!kora_lexer_next_token(lexer, &mut token));
+        kora_lexer_free(lexer);
+        Some(tokens)
+    }
+}
+
+/// Returns the `KORA_TOKEN_*` constants of the header, by name without the prefix.
+fn header_constants() -> HashMap<String, u32> {
+    let header = include_str!("../include/kora_lexer.h");
+    header
+        .lines()
+        .filter_map(|line| line.strip_prefix("#define KORA_TOKEN_"))
+        .map(|definition| {
+            let (name, value) = definition.split_once(' ').unwrap();
+            (name.to_string(), value.parse().unwrap())
+        })
+        .collect()
+}
+
+/// Returns the name of the header constant of a token kind, such as `LESS_THAN` for `LessThan`.
+fn constant_name(kind: &str) -> String {
+    let mut name = String::new();
+    for (index, c) in kind.char_indices() {
+        if c.is_ascii_uppercase() && index > 0 {
+            name.push('_');
+        }
+        name.push(c.to_ascii_uppercase());
+    }
+    name
+}
+
+#[test]
+fn test_tokenize() {
+    let source_code = "let a = f\"{b}\" + 1 // c\n€";
+    let tokens = tokenize(Some(source_code.as_bytes())).unwrap();
+
+    let constants = header_constants();
+    let expected = Lexer::new(source_code)
+        .map(|token| KoraToken {
+            kind: constants[&constant_name(&format!("{:?}", token.kind))],
+            start: token.span.start,
+            end: token.span.end,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(tokens, expected);
+    assert_eq!(
+        tokens.last(),
+        Some(&KoraToken {
+            kind: constants["ILLEGAL"],
+            start: 24,
+            end: 27,
+        })
+    );
+}
+
+#[test]
+fn test_tokenize_invalid_source() {
+    assert_eq!(tokenize(Some(b"")), Some(Vec::new()));
+    assert_eq!(tokenize(None), None);
+    assert_eq!(tokenize(Some(b"a \xFF b")), None);
+
+    // A null lexer behaves like an exhausted one
+    unsafe {
+        let mut token = KoraToken {
+            kind: u32::MAX,
+            start: 0,
+            end: 0,
+        };
+        assert!(!kora_lexer_next_token(ptr::null_mut(), &mut token));
+        assert_eq!(token.kind, u32::MAX);
+        kora_lexer_free(ptr::null_mut());
+    }
+}
+
+/// Every code of `kind_code` is declared in the header with the same value, so that a new token
+/// kind can't silently change the ABI.
+#[test]
+fn test_header_matches_kind_codes() {
+    let source = include_str!("../src/lib.rs");
+    let kind_codes = source
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("TokenKind::"))
+        .filter_map(|arm| arm.strip_suffix(','))
+        .map(|arm| {
+            let (kind, code) = arm.split_once(" => ").unwrap();
+            (constant_name(kind), code.parse().unwrap())
+        })
+        .collect::<HashMap<String, u32>>();
+
+    assert_eq!(header_constants(), kind_codes);
+}
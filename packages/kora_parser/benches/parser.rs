@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use kora_lexer::Lexer;
+use kora_parser::Parser;
+
+/// Realistic source code mixing items, statements and expressions.
+fn mixed(functions: usize) -> String {
+    r#"/// Adds two numbers.
+def add(a: Int, b: Int) -> Int {
+    let result = a + b * 0x7d1 - 3.14e-2
+    print(f"{a} + {b} = {result}", 'c', [1, 2, 3])
+    if result > 10 && !done { return result }
+    for (i, x) in items { total += x[i].value |> abs }
+    result
+}
+"#
+    .repeat(functions)
+}
+
+/// Source code made of long and deeply nested expressions, where most of the nodes are boxed.
+fn expression_heavy(lines: usize) -> String {
+    "x = a + b * (c - d) / f(g, h[i].j, -k ** 2) || (l < m && n >= o)\n".repeat(lines)
+}
+
+fn bench_corpora(criterion: &mut Criterion) {
+    let corpora = [
+        ("mixed", mixed(2_000)),
+        ("expression_heavy", expression_heavy(10_000)),
+        ("mixed_large", mixed(50_000)),
+    ];
+
+    let mut group = criterion.benchmark_group("parse");
+    for (name, source_code) in &corpora {
+        group.throughput(Throughput::Bytes(source_code.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("tokenize", name),
+            source_code,
+            |b, source| b.iter(|| Lexer::new(source).count()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parse_module", name),
+            source_code,
+            |b, source| b.iter(|| Parser::parse_module(source)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parse_module_in_arena", name),
+            source_code,
+            |b, source| b.iter(|| Parser::parse_module_in_arena(source)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_corpora);
+criterion_main!(benches);
@@ -0,0 +1,154 @@
+//! Feeds arbitrary source code to the parser, checking that it never panics and that the spans of
+//! the AST are within the source code and nested in the spans of their parents.
+//!
+//! Half of the inputs are made of whole tokens, which reach deeper into the parser than arbitrary
+//! bytes do.
+//!
+//! Run with `cargo +nightly fuzz run parse` from `packages/kora_parser`.
+
+#![no_main]
+
+use std::fmt;
+
+use kora_lexer::Span;
+use kora_parser::{
+    ast::{
+        Attribute, BlockExpr, Expr, FieldDecl, FuncDecl, GenericParam, Ident, Item, MatchArm,
+        Module, Param, Pattern, Stmt, Type,
+    },
+    visit::{
+        walk_attribute, walk_block, walk_expr, walk_field_decl, walk_func_decl, walk_generic_param,
+        walk_item, walk_match_arm, walk_param, walk_pattern, walk_stmt, walk_type, Visit,
+    },
+    Parser,
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Texts of tokens that inputs made of whole tokens are built from, one per byte of the input.
+const TOKENS: &[&str] = &[
+    "def", "struct", "extend", "with", "import", "as", "pub", "const", "let", "mut", "if", "else",
+    "for", "in", "while", "match", "fn", "break", "continue", "return", "true", "false", "none",
+    "a", "b", "_", "0", "1.5", "\"s\"", "f\"{a}\"", "f\"{", "'c'", "+", "-", "*", "**", "/", "%",
+    "=", "+=", "==", "!=", "<", ">", "<=", "&&", "||", "!", "&", "|", "^", "<<", "..", "..=", "|>",
+    "(", ")", "[", "]", "{", "}", ",", ".", ";", ":", "::", "->", "=>", "@", " ", "\n", "//",
+];
+
+fuzz_target!(|data: &[u8]| {
+    let source_code = match data.split_first() {
+        Some((mode, rest)) if mode % 2 == 0 => rest
+            .iter()
+            .map(|byte| TOKENS[*byte as usize % TOKENS.len()])
+            .collect(),
+        _ => String::from_utf8_lossy(data).into_owned(),
+    };
+
+    let (module, errors) = Parser::parse_module(&source_code);
+    for error in &errors {
+        let span = error.span();
+        assert!(span.start <= span.end && span.end as usize <= source_code.len());
+    }
+
+    let mut checker = SpanChecker {
+        parents: Vec::new(),
+    };
+    checker.visit_module(&module);
+
+    // The concrete syntax tree keeps every byte of the source code
+    let (_, syntax, _) = Parser::parse_module_with_syntax(&source_code);
+    assert_eq!(syntax.text(), source_code);
+});
+
+/// Checks that the span of every node is nested in the span of its parent, starting with the span
+/// of the module, which is the whole source code.
+struct SpanChecker {
+    parents: Vec<Span>,
+}
+
+impl SpanChecker {
+    fn check<T: fmt::Debug>(&mut self, span: Span, node: &T, walk: impl FnOnce(&mut Self, &T)) {
+        let parent = *self.parents.last().unwrap();
+        assert!(
+            parent.start <= span.start && span.start <= span.end && span.end <= parent.end,
+            "{span:?} is not in {parent:?}: {node:?}"
+        );
+        self.parents.push(span);
+        walk(self, node);
+        self.parents.pop();
+    }
+}
+
+impl<'ast> Visit<'ast> for SpanChecker {
+    fn visit_module(&mut self, module: &'ast Module) {
+        self.parents.push(module.span);
+        for stmt in &module.stmts {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        self.check(stmt.span(), stmt, |checker, stmt| walk_stmt(checker, stmt));
+    }
+
+    fn visit_item(&mut self, item: &'ast Item) {
+        self.check(item.span(), item, |checker, item| walk_item(checker, item));
+    }
+
+    fn visit_func_decl(&mut self, decl: &'ast FuncDecl) {
+        self.check(decl.span, decl, |checker, decl| {
+            walk_func_decl(checker, decl)
+        });
+    }
+
+    fn visit_attribute(&mut self, attribute: &'ast Attribute) {
+        self.check(attribute.span, attribute, |checker, attribute| {
+            walk_attribute(checker, attribute)
+        });
+    }
+
+    fn visit_generic_param(&mut self, param: &'ast GenericParam) {
+        self.check(param.span, param, |checker, param| {
+            walk_generic_param(checker, param)
+        });
+    }
+
+    fn visit_param(&mut self, param: &'ast Param) {
+        self.check(param.span, param, |checker, param| {
+            walk_param(checker, param)
+        });
+    }
+
+    fn visit_field_decl(&mut self, field: &'ast FieldDecl) {
+        self.check(field.span, field, |checker, field| {
+            walk_field_decl(checker, field)
+        });
+    }
+
+    fn visit_type(&mut self, ty: &'ast Type) {
+        let Type::Path(path) = ty;
+        self.check(path.span, ty, |checker, ty| walk_type(checker, ty));
+    }
+
+    fn visit_block(&mut self, block: &'ast BlockExpr) {
+        self.check(block.span, block, |checker, block| {
+            walk_block(checker, block)
+        });
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        self.check(expr.span(), expr, |checker, expr| walk_expr(checker, expr));
+    }
+
+    fn visit_match_arm(&mut self, arm: &'ast MatchArm) {
+        self.check(arm.span, arm, |checker, arm| walk_match_arm(checker, arm));
+    }
+
+    fn visit_pattern(&mut self, pattern: &'ast Pattern) {
+        self.check(pattern.span(), pattern, |checker, pattern| {
+            walk_pattern(checker, pattern)
+        });
+    }
+
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        self.check(ident.span, ident, |_, _| {});
+    }
+}
@@ -0,0 +1,114 @@
+//! Storage of the child expressions of the AST.
+//!
+//! By default, the children of an expression, such as the operands of a [`BinaryExpr`], are boxed,
+//! so building the AST of a big file allocates every node separately. In the arena mode of
+//! [`Parser::parse_module_in_arena`], they are [`ExprId`]s indexing an [`ExprArena`] instead,
+//! which stores the expressions next to each other:
+//!
+//! ```
+//! # use kora_parser::{ast::{Expr, Stmt}, Parser};
+//! let (module, arena, _) = Parser::parse_module_in_arena("a + 1");
+//! let Stmt::Expr(stmt) = &module.stmts[0] else { unreachable!() };
+//! let Expr::Binary(expr) = &stmt.expr else { unreachable!() };
+//! assert!(matches!(arena[expr.lhs], Expr::Path(_)));
+//! ```
+//!
+//! The rest of the crate, such as the visitors and the printer, only handles the boxed AST.
+//!
+//! [`BinaryExpr`]: crate::ast::BinaryExpr
+//! [`Parser::parse_module_in_arena`]: crate::Parser::parse_module_in_arena
+
+use std::{fmt, ops::Index};
+
+use crate::ast::Expr;
+
+/// How the AST refers to the children of expressions, see the [module documentation](self).
+pub trait ExprStorage: Sized + fmt::Debug + Clone + PartialEq + SerdeBounds {
+    /// A child expression, such as the `lhs` of a [`BinaryExpr`](crate::ast::BinaryExpr).
+    type ExprRef: fmt::Debug + Clone + PartialEq + SerdeBounds;
+    /// Where the parser stores the child expressions.
+    type Arena: Default;
+
+    /// Stores the child expression in the arena.
+    fn alloc(arena: &mut Self::Arena, expr: Expr<Self>) -> Self::ExprRef;
+}
+
+/// The bounds needed to serialize the AST, if the `serde` feature is enabled.
+#[cfg(feature = "serde")]
+pub trait SerdeBounds: serde::Serialize + serde::de::DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> SerdeBounds for T {}
+
+/// The bounds needed to serialize the AST, if the `serde` feature is enabled.
+#[cfg(not(feature = "serde"))]
+pub trait SerdeBounds {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> SerdeBounds for T {}
+
+/// The default storage, where child expressions are boxed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Boxed {}
+
+impl ExprStorage for Boxed {
+    type ExprRef = Box<Expr>;
+    type Arena = ();
+
+    fn alloc(_arena: &mut (), expr: Expr) -> Box<Expr> {
+        Box::new(expr)
+    }
+}
+
+/// The storage of the arena mode, where child expressions are in an [`ExprArena`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Arena {}
+
+impl ExprStorage for Arena {
+    type ExprRef = ExprId;
+    type Arena = ExprArena;
+
+    fn alloc(arena: &mut ExprArena, expr: Expr<Arena>) -> ExprId {
+        let id = ExprId(arena.exprs.len() as u32);
+        arena.exprs.push(expr);
+        id
+    }
+}
+
+/// Identifies an expression of an [`ExprArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExprId(u32);
+
+/// The child expressions of a module parsed in the arena mode, in the order they were parsed, so
+/// children come before their parents.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExprArena {
+    exprs: Vec<Expr<Arena>>,
+}
+
+impl ExprArena {
+    pub fn len(&self) -> usize {
+        self.exprs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exprs.is_empty()
+    }
+}
+
+/// Returns the expression with the given id.
+///
+/// # Panics
+///
+/// Panics if the id belongs to another arena.
+impl Index<ExprId> for ExprArena {
+    type Output = Expr<Arena>;
+
+    fn index(&self, id: ExprId) -> &Expr<Arena> {
+        &self.exprs[id.0 as usize]
+    }
+}
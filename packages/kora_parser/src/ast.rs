@@ -0,0 +1,873 @@
+//! The abstract syntax tree built by the [`crate::Parser`].
+//!
+//! Every node carries the byte range of its code in the source code, from its first token to its
+//! last one, so trivia around a node is not part of its span.
+//!
+//! The nodes that contain expressions are generic over how they refer to their child expressions,
+//! which are boxed by default, see [`crate::arena`].
+
+use kora_lexer::Span;
+
+use crate::arena::{Boxed, ExprStorage};
+
+/// A parsed source file, which is a sequence of statements.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct Module<P: ExprStorage = Boxed> {
+    pub stmts: Vec<Stmt<P>>,
+    pub span: Span,
+}
+
+/// A name, such as the name of a function or of a variable.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ident {
+    pub name: String,
+    pub span: Span,
+}
+
+/// A declaration that can appear at the top level of a module or in a block.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub enum Item<P: ExprStorage = Boxed> {
+    /// A function declaration, such as `def add(a, b) { a + b }`.
+    Func(FuncDecl<P>),
+    /// A struct declaration, such as `struct Point { x: Int, y: Int }`.
+    Struct(StructDecl<P>),
+    /// An extension of a type with methods, such as
+    /// `extend Point with Display { def show(self) {} }`.
+    Extend(ExtendDecl<P>),
+    /// An import of a module or of names from it, such as `import std.math`.
+    Import(ImportDecl),
+    /// A constant declaration, such as `const PI = 3.14159`.
+    Const(ConstDecl<P>),
+}
+
+/// A function declaration, such as `def add(a: Int, b: Int) -> Int { a + b }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct FuncDecl<P: ExprStorage = Boxed> {
+    /// Text of the `///` doc comments before the function, if any.
+    pub doc: Option<String>,
+    pub attributes: Vec<Attribute<P>>,
+    /// Whether the function is declared with `pub`, so that it can be used by other modules.
+    pub is_public: bool,
+    pub name: Ident,
+    /// The generic parameters in brackets, such as `T` in `def id[T](x: T) -> T {}`.
+    pub generics: Vec<GenericParam>,
+    pub params: Vec<Param>,
+    /// The type after `->`, if any.
+    pub return_type: Option<Type>,
+    pub body: BlockExpr<P>,
+    pub span: Span,
+}
+
+/// An attribute before an item, such as `@test` or `@inline(always)`, which tools such as the
+/// test runner and the linter read. It has no meaning for the parser.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct Attribute<P: ExprStorage = Boxed> {
+    pub name: Ident,
+    /// The arguments in parentheses after the name, such as `always` in `@inline(always)`.
+    pub args: Vec<Expr<P>>,
+    pub span: Span,
+}
+
+/// A generic parameter of a declaration, such as the `T` in `def id[T](x: T) -> T { x }`, which
+/// stands for any type in the declaration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenericParam {
+    pub name: Ident,
+    pub span: Span,
+}
+
+/// A parameter of a function, such as the `a: Int` in `def add(a: Int, b: Int) {}`. Its type can
+/// be left out, such as in `def add(a, b) {}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Param {
+    pub name: Ident,
+    pub ty: Option<Type>,
+    pub span: Span,
+}
+
+/// A struct declaration, such as `struct Point { x: Int, y: Int }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct StructDecl<P: ExprStorage = Boxed> {
+    /// Text of the `///` doc comments before the struct, if any.
+    pub doc: Option<String>,
+    pub attributes: Vec<Attribute<P>>,
+    /// Whether the struct is declared with `pub`, so that it can be used by other modules.
+    pub is_public: bool,
+    pub name: Ident,
+    /// The generic parameters in brackets, such as `T` in `struct Box[T] { value: T }`.
+    pub generics: Vec<GenericParam>,
+    pub fields: Vec<FieldDecl>,
+    pub span: Span,
+}
+
+/// A field of a struct, such as `x: Int`. Its type can be left out, such as in
+/// `struct Pair { first, second }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldDecl {
+    /// Text of the `///` doc comments before the field, if any.
+    pub doc: Option<String>,
+    /// Whether the field is declared with `pub`, so that it can be used by other modules.
+    pub is_public: bool,
+    pub name: Ident,
+    pub ty: Option<Type>,
+    pub span: Span,
+}
+
+/// A type, such as `Int` or `List[Int]`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Type {
+    /// A named type or a path to one, such as `Int`, `geometry::Point` or `Map[String, Int]`.
+    Path(PathType),
+}
+
+/// A named type or a path to one, such as `Int`, `geometry::Point` or `Map[String, Int]`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathType {
+    /// The names separated by `::`, of which there is at least one.
+    pub segments: Vec<Ident>,
+    /// The generic arguments in brackets, such as `String` and `Int` in `Map[String, Int]`.
+    pub args: Vec<Type>,
+    pub span: Span,
+}
+
+/// An extension of a type with methods, such as `extend Point with Display { def show(self) {} }`.
+///
+/// Without `with`, the methods are added to the type itself. With `with`, they implement the
+/// methods of the type after it for the extended type, like the implementation of a trait.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ExtendDecl<P: ExprStorage = Boxed> {
+    /// The extended type, such as `Point`.
+    pub ty: Type,
+    /// The type after `with`, such as `Display`.
+    pub with_type: Option<Type>,
+    pub methods: Vec<FuncDecl<P>>,
+    pub span: Span,
+}
+
+/// An import of a module or of names from it, such as `import std.math`, `import std.math as m`
+/// or `import util.{a, b as c}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImportDecl {
+    /// The names of the module and of its parents separated by `.`, such as `std` and `math` in
+    /// `import std.math`.
+    pub path: Vec<Ident>,
+    pub kind: ImportKind,
+    pub span: Span,
+}
+
+/// What an [`ImportDecl`] imports from its module.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImportKind {
+    /// The module itself, under its own name or under the name after `as`, such as `m` in
+    /// `import std.math as m`.
+    Module { alias: Option<Ident> },
+    /// The names in braces, such as `a` and `b` in `import util.{a, b}`.
+    Items(Vec<ImportItem>),
+}
+
+/// A name imported from a module in braces, such as `a` or `b as c` in `import util.{a, b as c}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImportItem {
+    pub name: Ident,
+    /// The name after `as` that the item is imported under.
+    pub alias: Option<Ident>,
+    pub span: Span,
+}
+
+/// A constant declaration, such as `const PI = 3.14159` or `const MAX: Int = 10`.
+///
+/// Unlike a variable, the constant is an item, so it is visible in the whole module or block
+/// that declares it, and can't be assigned to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ConstDecl<P: ExprStorage = Boxed> {
+    /// Text of the `///` doc comments before the constant, if any.
+    pub doc: Option<String>,
+    pub attributes: Vec<Attribute<P>>,
+    /// Whether the constant is declared with `pub`, so that it can be used by other modules.
+    pub is_public: bool,
+    pub name: Ident,
+    pub ty: Option<Type>,
+    pub value: Expr<P>,
+    pub span: Span,
+}
+
+/// A statement in a module or in a block.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub enum Stmt<P: ExprStorage = Boxed> {
+    /// An item declared in a module or in a block.
+    Item(Item<P>),
+    /// A variable declaration, such as `let x = 1`.
+    Let(LetStmt<P>),
+    /// An expression evaluated for its value or its side effects.
+    Expr(ExprStmt<P>),
+    /// A statement that couldn't be parsed, in place of which the parser reported an error.
+    Error(ErrorStmt),
+}
+
+/// A variable declaration, such as `let x = 1` or `let mut y: Float = 2.0`.
+///
+/// The names bound by the pattern are visible from the next statement to the end of the
+/// enclosing block, and can only be assigned to if the declaration is `mut`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct LetStmt<P: ExprStorage = Boxed> {
+    pub is_mutable: bool,
+    /// The names that are declared, such as `x` or `(a, b)`.
+    pub pattern: Pattern,
+    pub ty: Option<Type>,
+    pub value: Expr<P>,
+    pub span: Span,
+}
+
+/// An expression statement, such as `print(a)` or `print(a);`.
+///
+/// Statements end at the end of their line or at a `;`. The last expression statement of a block
+/// is the value of the block if it is not followed by a `;`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ExprStmt<P: ExprStorage = Boxed> {
+    pub expr: Expr<P>,
+    pub has_semicolon: bool,
+    pub span: Span,
+}
+
+/// A statement that couldn't be parsed, such as `a b`, skipped up to the start of the next
+/// statement.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorStmt {
+    pub span: Span,
+}
+
+/// An expression.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub enum Expr<P: ExprStorage = Boxed> {
+    /// A literal, such as `1`, `"text"` or `true`.
+    Literal(LiteralExpr),
+    /// A string literal with embedded expressions, such as `f"Hello, {name}!"`.
+    InterpolatedString(InterpolatedStringExpr<P>),
+    /// A name or a path to a name, such as `a` or `std::math::pi`.
+    Path(PathExpr),
+    /// An expression in parentheses, such as `(a)`.
+    Paren(ParenExpr<P>),
+    /// A tuple, such as `(1, "two", 3.0)`.
+    Tuple(TupleExpr<P>),
+    /// A list, such as `[1, 2, 3]`.
+    List(ListExpr<P>),
+    /// A map, such as `{ "a": 1, key: value }`.
+    Map(MapExpr<P>),
+    /// A struct, such as `Point { x: 1, y }`.
+    Struct(StructExpr<P>),
+    /// A block, such as `{ print(a); a }`.
+    Block(BlockExpr<P>),
+    /// A call, such as `add(1, 2)`.
+    Call(CallExpr<P>),
+    /// An access to a field, such as `point.x`.
+    Field(FieldExpr<P>),
+    /// An access to an element, such as `xs[0]` or `m["key"]`.
+    Index(IndexExpr<P>),
+    /// A conditional, such as `if a { b } else { c }`.
+    If(IfExpr<P>),
+    /// A `for` loop, such as `for x in xs { print(x) }`.
+    For(ForExpr<P>),
+    /// A `while` loop, such as `while i < 10 { i += 1 }`.
+    While(WhileExpr<P>),
+    /// A match of a value against patterns, such as `match x { 0 => a, _ => b }`.
+    Match(MatchExpr<P>),
+    /// An anonymous function, such as `fn(x) x * 2`.
+    Closure(ClosureExpr<P>),
+    /// An exit from the enclosing loop, such as `break`.
+    Break(BreakExpr<P>),
+    /// A jump to the next iteration of the enclosing loop, `continue`.
+    Continue(ContinueExpr),
+    /// A return from the enclosing function, such as `return a`.
+    Return(ReturnExpr<P>),
+    /// An operation with a prefix operator, such as `-a` or `!a`.
+    Unary(UnaryExpr<P>),
+    /// An operation with an infix operator, such as `a + b` or `a && b`.
+    Binary(BinaryExpr<P>),
+    /// An assignment, such as `a = b` or `a += b`.
+    Assign(AssignExpr<P>),
+    /// An expression that is missing, such as after `a +` at the end of a line, in place of which
+    /// the parser reported an error.
+    Missing(MissingExpr),
+}
+
+/// A literal, such as `1`, `"text"` or `true`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LiteralExpr {
+    pub kind: LiteralKind,
+    /// Text of the literal as written in the source code, such as `0x7d1` or `"a\n"`.
+    pub text: String,
+    pub span: Span,
+}
+
+/// The kind of a [`LiteralExpr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LiteralKind {
+    Integer,
+    Float,
+    String,
+    Char,
+    True,
+    False,
+    None,
+}
+
+/// A string literal with embedded expressions, such as `f"Hello, {name}!"`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct InterpolatedStringExpr<P: ExprStorage = Boxed> {
+    /// The literal text and the embedded expressions, in order.
+    pub parts: Vec<StringPart<P>>,
+    pub span: Span,
+}
+
+/// A part of an [`InterpolatedStringExpr`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub enum StringPart<P: ExprStorage = Boxed> {
+    /// Literal text as written in the source code, such as `Hello, ` in `f"Hello, {name}!"`.
+    /// Escape sequences and escaped braces (`{{` and `}}`) are left as they are.
+    Fragment { text: String, span: Span },
+    /// An embedded expression, such as `name` in `f"Hello, {name}!"`, whose spans are positions
+    /// in the whole source code like the ones of any other expression.
+    Expr(Expr<P>),
+}
+
+/// A name or a path to a name, such as `a` or `std::math::pi`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathExpr {
+    /// The names separated by `::`, of which there is at least one.
+    pub segments: Vec<Ident>,
+    pub span: Span,
+}
+
+/// An expression in parentheses, such as `(a)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ParenExpr<P: ExprStorage = Boxed> {
+    pub expr: P::ExprRef,
+    pub span: Span,
+}
+
+/// A tuple, such as `(1, "two", 3.0)`.
+///
+/// A tuple of one element needs a trailing comma, such as `(a,)`, since `(a)` is just `a` in
+/// parentheses. `()` is the empty tuple.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct TupleExpr<P: ExprStorage = Boxed> {
+    pub elements: Vec<Expr<P>>,
+    pub span: Span,
+}
+
+/// A list, such as `[1, 2, 3]` or `[]`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ListExpr<P: ExprStorage = Boxed> {
+    pub elements: Vec<Expr<P>>,
+    pub span: Span,
+}
+
+/// A map, such as `{ "a": 1, key: value }`, or the empty map `{:}`.
+///
+/// The keys are expressions, so `key` in `{ key: value }` is the value of the variable `key`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct MapExpr<P: ExprStorage = Boxed> {
+    pub entries: Vec<MapEntry<P>>,
+    pub span: Span,
+}
+
+/// An entry of a map, such as `"a": 1`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct MapEntry<P: ExprStorage = Boxed> {
+    pub key: Expr<P>,
+    pub value: Expr<P>,
+    pub span: Span,
+}
+
+/// A struct, such as `Point { x: 1, y }`.
+///
+/// A struct can't be written in the condition of an `if` or a `while`, the iterable of a `for`
+/// or the value of a `match` outside of parentheses, since its `{` would be taken as the start of
+/// the block that follows, such as in `if a == b { x }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct StructExpr<P: ExprStorage = Boxed> {
+    /// The struct or a path to it, such as `Point` or `geometry::Point`.
+    pub path: Vec<Ident>,
+    pub fields: Vec<FieldInit<P>>,
+    pub span: Span,
+}
+
+/// A field of a struct expression, such as `x: 1`. Without a value, such as `y`, the field is
+/// the value of the variable of the same name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct FieldInit<P: ExprStorage = Boxed> {
+    pub name: Ident,
+    pub value: Option<Expr<P>>,
+    pub span: Span,
+}
+
+/// A block, such as `{ print(a); a }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct BlockExpr<P: ExprStorage = Boxed> {
+    pub stmts: Vec<Stmt<P>>,
+    pub span: Span,
+}
+
+/// A call, such as `add(1, 2)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct CallExpr<P: ExprStorage = Boxed> {
+    pub callee: P::ExprRef,
+    pub args: Vec<Expr<P>>,
+    pub span: Span,
+}
+
+/// A conditional, such as `if a { b } else if c { d } else { e }`.
+///
+/// Its value is the value of the branch that was taken, so it can be used as an expression, such
+/// as in `x = if a { 1 } else { 2 }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct IfExpr<P: ExprStorage = Boxed> {
+    pub condition: P::ExprRef,
+    pub then_branch: BlockExpr<P>,
+    /// The branch after `else`, which is either an [`Expr::Block`] or, for `else if`, an
+    /// [`Expr::If`].
+    pub else_branch: Option<P::ExprRef>,
+    pub span: Span,
+}
+
+/// A `for` loop, such as `for x in xs { print(x) }` or `for i in 0..10 { print(i) }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ForExpr<P: ExprStorage = Boxed> {
+    /// The pattern that every element is bound to, such as `x` or `(key, value)`.
+    pub pattern: Pattern,
+    /// The expression after `in` whose elements are iterated over.
+    pub iterable: P::ExprRef,
+    pub body: BlockExpr<P>,
+    pub span: Span,
+}
+
+/// A `while` loop, such as `while i < 10 { i += 1 }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct WhileExpr<P: ExprStorage = Boxed> {
+    pub condition: P::ExprRef,
+    pub body: BlockExpr<P>,
+    pub span: Span,
+}
+
+/// A match of a value against patterns, such as `match x { 0 => a, _ => b }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct MatchExpr<P: ExprStorage = Boxed> {
+    /// The matched value.
+    pub scrutinee: P::ExprRef,
+    pub arms: Vec<MatchArm<P>>,
+    pub span: Span,
+}
+
+/// An arm of a `match` expression, such as `0 => a`, whose body is evaluated if the value matches
+/// its pattern. The arms are tried in order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct MatchArm<P: ExprStorage = Boxed> {
+    pub pattern: Pattern,
+    pub body: Expr<P>,
+    pub span: Span,
+}
+
+/// An anonymous function, such as `fn(x) x * 2` or `fn(x: Int) -> Int { x * 2 }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ClosureExpr<P: ExprStorage = Boxed> {
+    pub params: Vec<Param>,
+    pub return_type: Option<Type>,
+    pub body: P::ExprRef,
+    /// The variables of the enclosing functions that are used in the body. The parser leaves it
+    /// empty for name resolution to fill in.
+    pub captures: Vec<Ident>,
+    pub span: Span,
+}
+
+/// An exit from the enclosing loop, such as `break` or `break found`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct BreakExpr<P: ExprStorage = Boxed> {
+    pub value: Option<P::ExprRef>,
+    pub span: Span,
+}
+
+/// A jump to the next iteration of the enclosing loop, `continue`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContinueExpr {
+    pub span: Span,
+}
+
+/// An expression that is missing, in place of which the parser reported an error. Its span is
+/// empty, at the end of the token after which the expression was expected, such as the `+` of
+/// `a +`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MissingExpr {
+    pub span: Span,
+}
+
+/// An access to a field, such as `point.x`.
+///
+/// A method call such as `list.map(f)` is a [`CallExpr`] whose callee is a field access.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct FieldExpr<P: ExprStorage = Boxed> {
+    pub expr: P::ExprRef,
+    pub field: Ident,
+    pub span: Span,
+}
+
+/// An access to an element, such as `xs[0]` or `m["key"]`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct IndexExpr<P: ExprStorage = Boxed> {
+    pub expr: P::ExprRef,
+    pub index: P::ExprRef,
+    pub span: Span,
+}
+
+/// A return from the enclosing function, such as `return a`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ReturnExpr<P: ExprStorage = Boxed> {
+    pub value: Option<P::ExprRef>,
+    pub span: Span,
+}
+
+/// An operation with a prefix operator, such as `-a` or `!a`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct UnaryExpr<P: ExprStorage = Boxed> {
+    pub op: UnaryOp,
+    pub op_span: Span,
+    pub expr: P::ExprRef,
+    pub span: Span,
+}
+
+/// A prefix operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnaryOp {
+    /// `-`
+    Negate,
+    /// `!`
+    Not,
+}
+
+/// An operation with an infix operator, such as `a + b` or `a && b`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct BinaryExpr<P: ExprStorage = Boxed> {
+    pub op: BinaryOp,
+    pub op_span: Span,
+    pub lhs: P::ExprRef,
+    pub rhs: P::ExprRef,
+    pub span: Span,
+}
+
+/// An infix operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinaryOp {
+    /// `+`
+    Add,
+    /// `-`
+    Subtract,
+    /// `*`
+    Multiply,
+    /// `/`
+    Divide,
+    /// `%`
+    Modulo,
+    /// `**`
+    Power,
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    LessThan,
+    /// `>`
+    GreaterThan,
+    /// `<=`
+    LessThanEqual,
+    /// `>=`
+    GreaterThanEqual,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `&`
+    BitAnd,
+    /// `|`
+    BitOr,
+    /// `^`
+    BitXor,
+    /// `<<`
+    ShiftLeft,
+    /// `>>`
+    ShiftRight,
+    /// `..`, the range from the left side to the right side, excluded.
+    Range,
+    /// `..=`, the range from the left side to the right side, included.
+    RangeInclusive,
+}
+
+/// An assignment, such as `a = b` or `a += b`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct AssignExpr<P: ExprStorage = Boxed> {
+    /// The operator combined with the assignment, such as [`BinaryOp::Add`] for `+=`, or `None`
+    /// for `=`.
+    pub op: Option<BinaryOp>,
+    pub op_span: Span,
+    pub target: P::ExprRef,
+    pub value: P::ExprRef,
+    pub span: Span,
+}
+
+/// A pattern that a value is matched against, which binds the names in it to the parts of the
+/// value, such as the `(key, value)` in `for (key, value) in pairs {}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pattern {
+    /// A name that is bound to the whole value, such as `x`.
+    Ident(Ident),
+    /// `_`, which matches any value without binding it.
+    Wildcard(Span),
+    /// A literal that the value must be equal to, such as `0`, `-1` or `"text"`.
+    Literal(LiteralExpr),
+    /// A path to a constant value that the value must be equal to, such as `Color::Red`.
+    Path(PathExpr),
+    /// A tuple whose elements are matched against patterns, such as `(a, _)`.
+    Tuple(TuplePattern),
+    /// A struct whose fields are matched against patterns, such as `Point { x, y: 0 }`.
+    Struct(StructPattern),
+}
+
+/// A tuple whose elements are matched against patterns, such as `(a, _)`. Like for tuple
+/// expressions, a tuple of one element needs a trailing comma, such as `(a,)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TuplePattern {
+    pub elements: Vec<Pattern>,
+    pub span: Span,
+}
+
+/// A struct whose fields are matched against patterns, such as `Point { x, y: 0, .. }`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructPattern {
+    /// The struct or a path to it, such as `Point` or `geometry::Point`.
+    pub path: Vec<Ident>,
+    pub fields: Vec<FieldPattern>,
+    /// Whether the fields end with `..`, so that the other fields are ignored.
+    pub has_rest: bool,
+    pub span: Span,
+}
+
+/// A field of a struct pattern, such as `y: 0`. Without a pattern, such as `x`, the field is
+/// bound to a variable of the same name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldPattern {
+    pub name: Ident,
+    pub pattern: Option<Pattern>,
+    pub span: Span,
+}
+
+impl<P: ExprStorage> Item<P> {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Func(func) => func.span,
+            Self::Struct(decl) => decl.span,
+            Self::Extend(decl) => decl.span,
+            Self::Import(decl) => decl.span,
+            Self::Const(decl) => decl.span,
+        }
+    }
+}
+
+impl Type {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Path(ty) => ty.span,
+        }
+    }
+}
+
+impl Pattern {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Ident(name) => name.span,
+            Self::Wildcard(span) => *span,
+            Self::Literal(pattern) => pattern.span,
+            Self::Path(pattern) => pattern.span,
+            Self::Tuple(pattern) => pattern.span,
+            Self::Struct(pattern) => pattern.span,
+        }
+    }
+}
+
+impl<P: ExprStorage> Stmt<P> {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Item(item) => item.span(),
+            Self::Let(stmt) => stmt.span,
+            Self::Expr(stmt) => stmt.span,
+            Self::Error(stmt) => stmt.span,
+        }
+    }
+}
+
+impl<P: ExprStorage> Expr<P> {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Literal(expr) => expr.span,
+            Self::InterpolatedString(expr) => expr.span,
+            Self::Path(expr) => expr.span,
+            Self::Paren(expr) => expr.span,
+            Self::Tuple(expr) => expr.span,
+            Self::List(expr) => expr.span,
+            Self::Map(expr) => expr.span,
+            Self::Struct(expr) => expr.span,
+            Self::Block(expr) => expr.span,
+            Self::Call(expr) => expr.span,
+            Self::Field(expr) => expr.span,
+            Self::Index(expr) => expr.span,
+            Self::If(expr) => expr.span,
+            Self::For(expr) => expr.span,
+            Self::While(expr) => expr.span,
+            Self::Match(expr) => expr.span,
+            Self::Closure(expr) => expr.span,
+            Self::Break(expr) => expr.span,
+            Self::Continue(expr) => expr.span,
+            Self::Return(expr) => expr.span,
+            Self::Unary(expr) => expr.span,
+            Self::Binary(expr) => expr.span,
+            Self::Assign(expr) => expr.span,
+            Self::Missing(expr) => expr.span,
+        }
+    }
+
+    /// Whether the expression ends with a block, so that it doesn't need to be followed by a
+    /// newline or a `;` to end its statement, such as `{ a } b`.
+    pub fn is_block_like(&self) -> bool {
+        matches!(
+            self,
+            Self::Block(_) | Self::If(_) | Self::For(_) | Self::While(_) | Self::Match(_)
+        )
+    }
+}
+
+impl UnaryOp {
+    /// The symbol of the operator, such as `-`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Negate => "-",
+            Self::Not => "!",
+        }
+    }
+}
+
+impl BinaryOp {
+    /// The symbol of the operator, such as `+`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Modulo => "%",
+            Self::Power => "**",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::LessThan => "<",
+            Self::GreaterThan => ">",
+            Self::LessThanEqual => "<=",
+            Self::GreaterThanEqual => ">=",
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "^",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+            Self::Range => "..",
+            Self::RangeInclusive => "..=",
+        }
+    }
+}
@@ -0,0 +1,623 @@
+//! Functions to build AST nodes without parsing source code, such as
+//! `Expr::call(Expr::ident("f"), [Expr::int(1)])`.
+//!
+//! The nodes don't come from source code, so their spans are empty spans at the start of the
+//! source code (`0..0`). Their other fields are the same as the ones the parser would produce for
+//! the code that [`Expr::to_source`] prints, so built nodes can be compared with parsed nodes once
+//! spans are ignored.
+//!
+//! ```
+//! # use kora_parser::ast::{BinaryOp, Expr};
+//! let expr = Expr::ident("f").call([Expr::int(1).binary(BinaryOp::Add, Expr::ident("x"))]);
+//! assert_eq!(expr.to_source(), "f(1 + x)");
+//! ```
+
+use kora_lexer::Span;
+
+use crate::ast::{
+    AssignExpr, BinaryExpr, BinaryOp, BlockExpr, BreakExpr, CallExpr, ClosureExpr, ConstDecl,
+    ContinueExpr, Expr, ExprStmt, FieldDecl, FieldExpr, FieldInit, ForExpr, FuncDecl, Ident,
+    IfExpr, IndexExpr, Item, LetStmt, ListExpr, LiteralExpr, LiteralKind, MapEntry, MapExpr,
+    MatchArm, MatchExpr, Module, Param, ParenExpr, PathExpr, PathType, Pattern, ReturnExpr, Stmt,
+    StructDecl, StructExpr, TupleExpr, TuplePattern, Type, UnaryExpr, UnaryOp, WhileExpr,
+};
+
+impl Ident {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            span: Span::default(),
+        }
+    }
+}
+
+impl Module {
+    pub fn new(stmts: impl IntoIterator<Item = Stmt>) -> Self {
+        Self {
+            stmts: stmts.into_iter().collect(),
+            span: Span::default(),
+        }
+    }
+}
+
+impl FuncDecl {
+    /// A private function without generic parameters or return type, such as `def f(a) {}`.
+    pub fn new(
+        name: impl Into<String>,
+        params: impl IntoIterator<Item = Param>,
+        body: BlockExpr,
+    ) -> Self {
+        Self {
+            doc: None,
+            attributes: Vec::new(),
+            is_public: false,
+            name: Ident::new(name),
+            generics: Vec::new(),
+            params: params.into_iter().collect(),
+            return_type: None,
+            body,
+            span: Span::default(),
+        }
+    }
+
+    /// Declares the function with `pub`.
+    pub fn public(mut self) -> Self {
+        self.is_public = true;
+        self
+    }
+
+    /// Sets the type after `->`.
+    pub fn returns(mut self, ty: Type) -> Self {
+        self.return_type = Some(ty);
+        self
+    }
+}
+
+impl Param {
+    /// A parameter without type, such as `a`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: Ident::new(name),
+            ty: None,
+            span: Span::default(),
+        }
+    }
+
+    /// A parameter with a type, such as `a: Int`.
+    pub fn typed(name: impl Into<String>, ty: Type) -> Self {
+        Self {
+            ty: Some(ty),
+            ..Self::new(name)
+        }
+    }
+}
+
+impl StructDecl {
+    /// A private struct without generic parameters, such as `struct Point { x: Int, y: Int }`.
+    pub fn new(name: impl Into<String>, fields: impl IntoIterator<Item = FieldDecl>) -> Self {
+        Self {
+            doc: None,
+            attributes: Vec::new(),
+            is_public: false,
+            name: Ident::new(name),
+            generics: Vec::new(),
+            fields: fields.into_iter().collect(),
+            span: Span::default(),
+        }
+    }
+
+    /// Declares the struct with `pub`.
+    pub fn public(mut self) -> Self {
+        self.is_public = true;
+        self
+    }
+}
+
+impl FieldDecl {
+    /// A private field without documentation, such as `x: Int`.
+    pub fn new(name: impl Into<String>, ty: Type) -> Self {
+        Self {
+            doc: None,
+            is_public: false,
+            name: Ident::new(name),
+            ty: Some(ty),
+            span: Span::default(),
+        }
+    }
+
+    /// Declares the field with `pub`.
+    pub fn public(mut self) -> Self {
+        self.is_public = true;
+        self
+    }
+}
+
+impl ConstDecl {
+    /// A private constant without type, such as `const PI = 3.14159`.
+    pub fn new(name: impl Into<String>, value: Expr) -> Self {
+        Self {
+            doc: None,
+            attributes: Vec::new(),
+            is_public: false,
+            name: Ident::new(name),
+            ty: None,
+            value,
+            span: Span::default(),
+        }
+    }
+
+    /// Declares the constant with `pub`.
+    pub fn public(mut self) -> Self {
+        self.is_public = true;
+        self
+    }
+}
+
+impl Type {
+    /// A type named by a path without generic arguments, such as `Int` or `std::List`. The
+    /// segments are separated by `::` in `path`.
+    pub fn named(path: &str) -> Self {
+        Self::Path(PathType {
+            segments: path.split("::").map(Ident::new).collect(),
+            args: Vec::new(),
+            span: Span::default(),
+        })
+    }
+
+    /// A generic type with arguments, such as `List[Int]`.
+    pub fn generic(path: &str, args: impl IntoIterator<Item = Type>) -> Self {
+        let Self::Path(mut ty) = Self::named(path);
+        ty.args = args.into_iter().collect();
+        Self::Path(ty)
+    }
+}
+
+impl Stmt {
+    /// A `let` statement without type, such as `let x = 1`.
+    pub fn let_(pattern: Pattern, value: Expr) -> Self {
+        Self::Let(LetStmt {
+            is_mutable: false,
+            pattern,
+            ty: None,
+            value,
+            span: Span::default(),
+        })
+    }
+
+    /// A `let mut` statement without type, such as `let mut x = 1`.
+    pub fn let_mut(pattern: Pattern, value: Expr) -> Self {
+        Self::Let(LetStmt {
+            is_mutable: true,
+            pattern,
+            ty: None,
+            value,
+            span: Span::default(),
+        })
+    }
+
+    /// An expression statement without `;`, such as the value at the end of a block.
+    pub fn expr(expr: Expr) -> Self {
+        Self::Expr(ExprStmt {
+            expr,
+            has_semicolon: false,
+            span: Span::default(),
+        })
+    }
+
+    /// An expression statement followed by `;`.
+    pub fn semi(expr: Expr) -> Self {
+        Self::Expr(ExprStmt {
+            expr,
+            has_semicolon: true,
+            span: Span::default(),
+        })
+    }
+}
+
+impl From<Item> for Stmt {
+    fn from(item: Item) -> Self {
+        Self::Item(item)
+    }
+}
+
+impl From<FuncDecl> for Stmt {
+    fn from(decl: FuncDecl) -> Self {
+        Self::Item(Item::Func(decl))
+    }
+}
+
+impl From<StructDecl> for Stmt {
+    fn from(decl: StructDecl) -> Self {
+        Self::Item(Item::Struct(decl))
+    }
+}
+
+impl From<ConstDecl> for Stmt {
+    fn from(decl: ConstDecl) -> Self {
+        Self::Item(Item::Const(decl))
+    }
+}
+
+impl BlockExpr {
+    pub fn new(stmts: impl IntoIterator<Item = Stmt>) -> Self {
+        Self {
+            stmts: stmts.into_iter().collect(),
+            span: Span::default(),
+        }
+    }
+}
+
+impl MatchArm {
+    pub fn new(pattern: Pattern, body: Expr) -> Self {
+        Self {
+            pattern,
+            body,
+            span: Span::default(),
+        }
+    }
+}
+
+impl Pattern {
+    /// A name bound to the whole value, such as `x`.
+    pub fn ident(name: impl Into<String>) -> Self {
+        Self::Ident(Ident::new(name))
+    }
+
+    /// `_`.
+    pub fn wildcard() -> Self {
+        Self::Wildcard(Span::default())
+    }
+
+    /// A tuple pattern, such as `(a, _)`.
+    pub fn tuple(elements: impl IntoIterator<Item = Pattern>) -> Self {
+        Self::Tuple(TuplePattern {
+            elements: elements.into_iter().collect(),
+            span: Span::default(),
+        })
+    }
+}
+
+impl From<LiteralExpr> for Pattern {
+    fn from(literal: LiteralExpr) -> Self {
+        Self::Literal(literal)
+    }
+}
+
+impl LiteralExpr {
+    fn new(kind: LiteralKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+            span: Span::default(),
+        }
+    }
+
+    /// A decimal integer literal, such as `42`.
+    pub fn int(value: u64) -> Self {
+        Self::new(LiteralKind::Integer, value.to_string())
+    }
+
+    /// A float literal, such as `2.5` or `1e100`.
+    ///
+    /// # Panics
+    ///
+    /// If the value is infinite or NaN, which can't be written as a literal.
+    pub fn float(value: f64) -> Self {
+        assert!(value.is_finite(), "{value} can't be written as a literal");
+        // The `Debug` format always has a fractional part or an exponent, unlike `Display`
+        Self::new(LiteralKind::Float, format!("{value:?}"))
+    }
+
+    /// A string literal with the given content, such as `"a\n"` for `a` and a newline.
+    pub fn string(value: &str) -> Self {
+        Self::new(LiteralKind::String, format!("\"{}\"", escape(value, '"')))
+    }
+
+    /// A character literal, such as `'a'`.
+    pub fn char(value: char) -> Self {
+        Self::new(
+            LiteralKind::Char,
+            format!("'{}'", escape(&value.to_string(), '\'')),
+        )
+    }
+
+    /// `true` or `false`.
+    pub fn bool(value: bool) -> Self {
+        if value {
+            Self::new(LiteralKind::True, "true")
+        } else {
+            Self::new(LiteralKind::False, "false")
+        }
+    }
+
+    /// `none`.
+    pub fn none() -> Self {
+        Self::new(LiteralKind::None, "none")
+    }
+}
+
+/// Escapes the backslashes, the control characters and the `quote` character of `value`, for a
+/// literal delimited by `quote`.
+fn escape(value: &str, quote: char) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => escaped.push_str("\\0"),
+            _ if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Expr {
+    /// A variable or another name, such as `x`. Names are paths with one segment.
+    pub fn ident(name: impl Into<String>) -> Self {
+        Self::Path(PathExpr {
+            segments: vec![Ident::new(name)],
+            span: Span::default(),
+        })
+    }
+
+    /// A path to a name, such as `std::math::pi`, whose segments are separated by `::`.
+    pub fn path(path: &str) -> Self {
+        Self::Path(PathExpr {
+            segments: path.split("::").map(Ident::new).collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// See [`LiteralExpr::int`].
+    pub fn int(value: u64) -> Self {
+        Self::Literal(LiteralExpr::int(value))
+    }
+
+    /// See [`LiteralExpr::float`].
+    pub fn float(value: f64) -> Self {
+        Self::Literal(LiteralExpr::float(value))
+    }
+
+    /// See [`LiteralExpr::string`].
+    pub fn string(value: &str) -> Self {
+        Self::Literal(LiteralExpr::string(value))
+    }
+
+    /// See [`LiteralExpr::char`].
+    pub fn char(value: char) -> Self {
+        Self::Literal(LiteralExpr::char(value))
+    }
+
+    /// See [`LiteralExpr::bool`].
+    pub fn bool(value: bool) -> Self {
+        Self::Literal(LiteralExpr::bool(value))
+    }
+
+    /// See [`LiteralExpr::none`].
+    pub fn none() -> Self {
+        Self::Literal(LiteralExpr::none())
+    }
+
+    /// The expression in parentheses. The printer adds the parentheses that precedence requires
+    /// on its own, so this is only needed to keep the ones of parsed code.
+    pub fn paren(self) -> Self {
+        Self::Paren(ParenExpr {
+            expr: Box::new(self),
+            span: Span::default(),
+        })
+    }
+
+    pub fn tuple(elements: impl IntoIterator<Item = Expr>) -> Self {
+        Self::Tuple(TupleExpr {
+            elements: elements.into_iter().collect(),
+            span: Span::default(),
+        })
+    }
+
+    pub fn list(elements: impl IntoIterator<Item = Expr>) -> Self {
+        Self::List(ListExpr {
+            elements: elements.into_iter().collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// A map with the given keys and values, in order.
+    pub fn map(entries: impl IntoIterator<Item = (Expr, Expr)>) -> Self {
+        Self::Map(MapExpr {
+            entries: entries
+                .into_iter()
+                .map(|(key, value)| MapEntry {
+                    key,
+                    value,
+                    span: Span::default(),
+                })
+                .collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// A struct with the given fields and values, in order, such as `Point { x: 1, y: 2 }`. The
+    /// path is split at `::` like in [`Expr::path`].
+    pub fn struct_<N: Into<String>>(
+        path: &str,
+        fields: impl IntoIterator<Item = (N, Expr)>,
+    ) -> Self {
+        Self::Struct(StructExpr {
+            path: path.split("::").map(Ident::new).collect(),
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| FieldInit {
+                    name: Ident::new(name),
+                    value: Some(value),
+                    span: Span::default(),
+                })
+                .collect(),
+            span: Span::default(),
+        })
+    }
+
+    pub fn block(stmts: impl IntoIterator<Item = Stmt>) -> Self {
+        Self::Block(BlockExpr::new(stmts))
+    }
+
+    /// A call of the expression, such as `f(1)` for `Expr::ident("f").call([Expr::int(1)])`.
+    pub fn call(self, args: impl IntoIterator<Item = Expr>) -> Self {
+        Self::Call(CallExpr {
+            callee: Box::new(self),
+            args: args.into_iter().collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// An access to a field of the expression, such as `point.x`.
+    pub fn field(self, name: impl Into<String>) -> Self {
+        Self::Field(FieldExpr {
+            expr: Box::new(self),
+            field: Ident::new(name),
+            span: Span::default(),
+        })
+    }
+
+    /// An access to an element of the expression, such as `xs[0]`.
+    pub fn index(self, index: Expr) -> Self {
+        Self::Index(IndexExpr {
+            expr: Box::new(self),
+            index: Box::new(index),
+            span: Span::default(),
+        })
+    }
+
+    /// A conditional without `else`, such as `if a { b }`.
+    pub fn if_then(condition: Expr, then_branch: BlockExpr) -> Self {
+        Self::If(IfExpr {
+            condition: Box::new(condition),
+            then_branch,
+            else_branch: None,
+            span: Span::default(),
+        })
+    }
+
+    /// A conditional with an `else` branch, which is a block or another conditional for
+    /// `else if`.
+    pub fn if_else(condition: Expr, then_branch: BlockExpr, else_branch: Expr) -> Self {
+        Self::If(IfExpr {
+            condition: Box::new(condition),
+            then_branch,
+            else_branch: Some(Box::new(else_branch)),
+            span: Span::default(),
+        })
+    }
+
+    /// A `for` loop, such as `for x in xs {}`.
+    pub fn for_in(pattern: Pattern, iterable: Expr, body: BlockExpr) -> Self {
+        Self::For(ForExpr {
+            pattern,
+            iterable: Box::new(iterable),
+            body,
+            span: Span::default(),
+        })
+    }
+
+    /// A `while` loop, such as `while a {}`.
+    pub fn while_loop(condition: Expr, body: BlockExpr) -> Self {
+        Self::While(WhileExpr {
+            condition: Box::new(condition),
+            body,
+            span: Span::default(),
+        })
+    }
+
+    /// A match of the expression against the patterns of `arms`.
+    pub fn match_arms(self, arms: impl IntoIterator<Item = MatchArm>) -> Self {
+        Self::Match(MatchExpr {
+            scrutinee: Box::new(self),
+            arms: arms.into_iter().collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// A closure without return type, such as `fn(x) x * 2`. Its captures are left empty, like
+    /// the parser does.
+    pub fn closure(params: impl IntoIterator<Item = Param>, body: Expr) -> Self {
+        Self::Closure(ClosureExpr {
+            params: params.into_iter().collect(),
+            return_type: None,
+            body: Box::new(body),
+            captures: Vec::new(),
+            span: Span::default(),
+        })
+    }
+
+    /// `break`, or `break value`.
+    pub fn break_(value: Option<Expr>) -> Self {
+        Self::Break(BreakExpr {
+            value: value.map(Box::new),
+            span: Span::default(),
+        })
+    }
+
+    /// `continue`.
+    pub fn continue_() -> Self {
+        Self::Continue(ContinueExpr {
+            span: Span::default(),
+        })
+    }
+
+    /// `return`, or `return value`.
+    pub fn return_(value: Option<Expr>) -> Self {
+        Self::Return(ReturnExpr {
+            value: value.map(Box::new),
+            span: Span::default(),
+        })
+    }
+
+    /// An operation with a prefix operator, such as `-a`.
+    pub fn unary(op: UnaryOp, expr: Expr) -> Self {
+        Self::Unary(UnaryExpr {
+            op,
+            op_span: Span::default(),
+            expr: Box::new(expr),
+            span: Span::default(),
+        })
+    }
+
+    /// An operation with the expression on the left side, such as `a + b` for
+    /// `Expr::ident("a").binary(BinaryOp::Add, Expr::ident("b"))`.
+    pub fn binary(self, op: BinaryOp, rhs: Expr) -> Self {
+        Self::Binary(BinaryExpr {
+            op,
+            op_span: Span::default(),
+            lhs: Box::new(self),
+            rhs: Box::new(rhs),
+            span: Span::default(),
+        })
+    }
+
+    /// An assignment to the expression, such as `a = b`.
+    pub fn assign(self, value: Expr) -> Self {
+        Self::Assign(AssignExpr {
+            op: None,
+            op_span: Span::default(),
+            target: Box::new(self),
+            value: Box::new(value),
+            span: Span::default(),
+        })
+    }
+
+    /// An assignment combined with an operator, such as `a += b`.
+    pub fn compound_assign(self, op: BinaryOp, value: Expr) -> Self {
+        Self::Assign(AssignExpr {
+            op: Some(op),
+            op_span: Span::default(),
+            target: Box::new(self),
+            value: Box::new(value),
+            span: Span::default(),
+        })
+    }
+}
@@ -0,0 +1,418 @@
+//! A lossless concrete syntax tree, which keeps every token of the source code, including trivia,
+//! so that tools like the formatter can edit code without losing comments and whitespace.
+//!
+//! The tree has two layers, like the one of rust-analyzer:
+//! - The green tree is immutable and only stores the kinds and texts, so subtrees can be shared
+//!   between versions of the same file.
+//! - The red tree made of [`SyntaxNode`]s wraps the green tree with the position of every node and
+//!   a pointer to its parent, and is created on demand while walking the tree.
+//!
+//! Trivia between two nodes belongs to their parent, so the span of a node goes from its first
+//! token to its last one, like the span of the matching AST node.
+
+use std::{fmt, rc::Rc, sync::Arc};
+
+use kora_lexer::{Span, TokenKind};
+
+/// The kind of a [`SyntaxNode`], which is named after the AST node that it corresponds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Module,
+    Attribute,
+    FuncDecl,
+    GenericParamList,
+    GenericParam,
+    ParamList,
+    Param,
+    ReturnType,
+    StructDecl,
+    FieldList,
+    FieldDecl,
+    ExtendDecl,
+    ImportDecl,
+    ImportItemList,
+    ImportItem,
+    ConstDecl,
+    MethodList,
+    PathType,
+    GenericArgList,
+    BlockExpr,
+    LetStmt,
+    ExprStmt,
+    LiteralExpr,
+    /// An interpolated string literal. Its embedded expressions are only parsed into the AST, so
+    /// the literal is a single token.
+    InterpolatedStringExpr,
+    PathExpr,
+    ParenExpr,
+    TupleExpr,
+    ListExpr,
+    MapExpr,
+    MapEntry,
+    StructExpr,
+    FieldInitList,
+    FieldInit,
+    CallExpr,
+    ArgList,
+    FieldExpr,
+    IndexExpr,
+    IfExpr,
+    ForExpr,
+    WhileExpr,
+    MatchExpr,
+    MatchArmList,
+    MatchArm,
+    ClosureExpr,
+    BreakExpr,
+    ContinueExpr,
+    IdentPattern,
+    WildcardPattern,
+    LiteralPattern,
+    PathPattern,
+    ParenPattern,
+    TuplePattern,
+    StructPattern,
+    FieldPatternList,
+    FieldPattern,
+    ReturnExpr,
+    UnaryExpr,
+    BinaryExpr,
+    AssignExpr,
+    /// A pipeline such as `x |> f`, which the AST represents as a call.
+    PipeExpr,
+    /// Tokens that couldn't be parsed.
+    Error,
+    /// An empty node in place of a token or an expression that is missing, such as the `)` of
+    /// `f(a` at the end of the file, or the expression after `a +`.
+    Missing,
+}
+
+/// An immutable node of the green tree, see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GreenNode {
+    kind: NodeKind,
+    text_len: u32,
+    children: Vec<GreenElement>,
+}
+
+/// An immutable token of the green tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GreenToken {
+    kind: TokenKind,
+    text: Box<str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GreenElement {
+    Node(Arc<GreenNode>),
+    Token(Arc<GreenToken>),
+}
+
+impl GreenNode {
+    pub fn new(kind: NodeKind, children: Vec<GreenElement>) -> Self {
+        let text_len = children.iter().map(GreenElement::text_len).sum();
+        Self {
+            kind,
+            text_len,
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Length of the text of the node in bytes.
+    pub fn text_len(&self) -> u32 {
+        self.text_len
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// Writes the text of every token of the node, in order.
+    fn write_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => node.write_text(text),
+                GreenElement::Token(token) => text.push_str(&token.text),
+            }
+        }
+    }
+}
+
+impl GreenToken {
+    pub fn new(kind: TokenKind, text: &str) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl GreenElement {
+    /// Length of the text of the element in bytes.
+    pub fn text_len(&self) -> u32 {
+        match self {
+            Self::Node(node) => node.text_len,
+            Self::Token(token) => token.text.len() as u32,
+        }
+    }
+}
+
+/// A marker of a position in a [`GreenNodeBuilder`], used to start a node that wraps the elements
+/// added since then. See [`GreenNodeBuilder::start_node_at`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Checkpoint(usize);
+
+/// Builds a green tree from the top down, out of the tokens and the starts and ends of the nodes
+/// found by the parser.
+#[derive(Debug, Default)]
+pub(crate) struct GreenNodeBuilder {
+    /// Nodes that have been started but not finished, with the index of their first child.
+    parents: Vec<(NodeKind, usize)>,
+    /// Children of the nodes that have been started but not finished.
+    children: Vec<GreenElement>,
+}
+
+impl GreenNodeBuilder {
+    pub(crate) fn start_node(&mut self, kind: NodeKind) {
+        self.parents.push((kind, self.children.len()));
+    }
+
+    /// Starts a node that wraps the elements added since the checkpoint was taken, such as the
+    /// left side of a binary expression, which is parsed before the operator is found.
+    pub(crate) fn start_node_at(&mut self, checkpoint: Checkpoint, kind: NodeKind) {
+        self.parents.push((kind, checkpoint.0));
+    }
+
+    pub(crate) fn finish_node(&mut self) {
+        let (kind, first_child) = self.parents.pop().expect("no node to finish");
+        let children = self.children.drain(first_child..).collect();
+        let node = GreenNode::new(kind, children);
+        self.children.push(GreenElement::Node(Arc::new(node)));
+    }
+
+    pub(crate) fn token(&mut self, kind: TokenKind, text: &str) {
+        let token = GreenToken::new(kind, text);
+        self.children.push(GreenElement::Token(Arc::new(token)));
+    }
+
+    pub(crate) fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.children.len())
+    }
+
+    /// Number of nodes that have been started but not finished.
+    pub(crate) fn depth(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Finishes the nodes that have been started until only `depth` of them are left, such as
+    /// the nodes left unfinished by a parse error.
+    pub(crate) fn finish_nodes_to(&mut self, depth: usize) {
+        while self.parents.len() > depth {
+            self.finish_node();
+        }
+    }
+
+    /// Finishes the nodes that have been started, including the root node, and returns it.
+    pub(crate) fn finish(mut self) -> GreenNode {
+        while !self.parents.is_empty() {
+            self.finish_node();
+        }
+        match self.children.pop() {
+            Some(GreenElement::Node(node)) if self.children.is_empty() => {
+                Arc::unwrap_or_clone(node)
+            }
+            _ => panic!("the tree must have a single root node"),
+        }
+    }
+}
+
+/// A node of the syntax tree, with its position in the source code and its parent.
+/// Cloning it is cheap.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+struct NodeData {
+    green: Arc<GreenNode>,
+    offset: u32,
+    parent: Option<SyntaxNode>,
+}
+
+/// A token of the syntax tree, with its position in the source code and its parent.
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Arc<GreenToken>,
+    offset: u32,
+    parent: SyntaxNode,
+}
+
+/// A child of a [`SyntaxNode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    /// Creates the root of a syntax tree.
+    pub fn new_root(green: Arc<GreenNode>) -> Self {
+        Self(Rc::new(NodeData {
+            green,
+            offset: 0,
+            parent: None,
+        }))
+    }
+
+    pub fn kind(&self) -> NodeKind {
+        self.0.green.kind
+    }
+
+    /// Byte range of the node in the source code.
+    pub fn span(&self) -> Span {
+        Span::new(self.0.offset, self.0.offset + self.0.green.text_len)
+    }
+
+    /// Text of the node, including the trivia inside of it.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.0.green.text_len as usize);
+        self.0.green.write_text(&mut text);
+        text
+    }
+
+    pub fn green(&self) -> &Arc<GreenNode> {
+        &self.0.green
+    }
+
+    pub fn parent(&self) -> Option<&SyntaxNode> {
+        self.0.parent.as_ref()
+    }
+
+    /// Returns the nodes and tokens that are direct children of this node.
+    pub fn children(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.0.offset;
+        self.0.green.children.iter().map(move |child| {
+            let child_offset = offset;
+            offset += child.text_len();
+            match child {
+                GreenElement::Node(node) => SyntaxElement::Node(Self(Rc::new(NodeData {
+                    green: Arc::clone(node),
+                    offset: child_offset,
+                    parent: Some(self.clone()),
+                }))),
+                GreenElement::Token(token) => SyntaxElement::Token(SyntaxToken {
+                    green: Arc::clone(token),
+                    offset: child_offset,
+                    parent: self.clone(),
+                }),
+            }
+        })
+    }
+
+    /// Returns the nodes that are direct children of this node.
+    pub fn child_nodes(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// Returns every token of the node, including the ones of its descendants, in order.
+    pub fn tokens(&self) -> Vec<SyntaxToken> {
+        let mut tokens = Vec::new();
+        for child in self.children() {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> TokenKind {
+        self.green.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    /// Byte range of the token in the source code.
+    pub fn span(&self) -> Span {
+        Span::new(self.offset, self.offset + self.green.text.len() as u32)
+    }
+
+    pub fn parent(&self) -> &SyntaxNode {
+        &self.parent
+    }
+}
+
+/// Prints the tree with one node or token per line, indented by depth, such as:
+///
+/// ```text
+/// BinaryExpr@0..5
+///   PathExpr@0..1
+///     Identifier@0..1 "a"
+///   Trivia@1..2 " "
+/// ```
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_node(f: &mut fmt::Formatter<'_>, node: &SyntaxNode, depth: usize) -> fmt::Result {
+            writeln!(
+                f,
+                "{:indent$}{:?}@{:?}",
+                "",
+                node.kind(),
+                node.span(),
+                indent = depth * 2
+            )?;
+            for child in node.children() {
+                match child {
+                    SyntaxElement::Node(child) => write_node(f, &child, depth + 1)?,
+                    SyntaxElement::Token(token) => {
+                        writeln!(f, "{:indent$}{token:?}", "", indent = (depth + 1) * 2)?
+                    }
+                }
+            }
+            Ok(())
+        }
+        write_node(f, self, 0)
+    }
+}
+
+/// Prints the token as `Identifier@0..1 "a"`.
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}@{:?} {:?}", self.kind(), self.span(), self.text())
+    }
+}
+
+/// Nodes are equal if they are the same node of the same tree.
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+/// Tokens are equal if they are the same token of the same tree.
+impl PartialEq for SyntaxToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl Eq for SyntaxToken {}
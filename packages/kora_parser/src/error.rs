@@ -0,0 +1,291 @@
+use std::fmt;
+
+use kora_diagnostics::{Applicability, Diagnostic, ErrorCode, Suggestion};
+use kora_lexer::{Span, SyntaxError, TokenKind};
+
+/// An error found while parsing the source code, including the errors found while tokenizing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParseError {
+    /// An error found by the lexer, such as an unterminated string literal.
+    Syntax(SyntaxError),
+    /// A specific token was expected, such as the `)` closing the arguments of a call.
+    ExpectedToken {
+        span: Span,
+        expected: TokenKind,
+        found: TokenKind,
+    },
+    /// An expression was expected, such as after `return (`.
+    ExpectedExpression { span: Span, found: TokenKind },
+    /// A pattern was expected, such as after `for`.
+    ExpectedPattern { span: Span, found: TokenKind },
+    /// Attributes or `pub` are followed by something else than an item that can have them, such
+    /// as `@test let a = 1`.
+    ExpectedItem { span: Span, found: TokenKind },
+    /// A type was expected, such as after `x:` in a struct field.
+    ExpectedType { span: Span, found: TokenKind },
+    /// The body of an `extend` block contains something else than a method, such as `a + b`.
+    ExpectedMethod { span: Span },
+    /// A statement is followed by more code on the same line, such as `a b`.
+    ExpectedStatementEnd { span: Span, found: TokenKind },
+    /// An embedded expression of an interpolated string literal is followed by more code, such as
+    /// `f"{a b}"`.
+    ExpectedInterpolationEnd { span: Span, found: TokenKind },
+    /// The left side of an assignment is not a variable, a field or an element, such as `1 = a`.
+    InvalidAssignmentTarget { span: Span },
+    /// The condition of an `if` or a `while` is an assignment, such as `if a = b {}`, which is
+    /// most likely a comparison. The span is the span of the `=`.
+    AssignmentInCondition { span: Span },
+}
+
+impl ParseError {
+    /// Stable numeric code of the error. Errors of the lexer keep their codes, and errors of the
+    /// parser start at `100`.
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::Syntax(error) => error.code(),
+            Self::ExpectedToken { .. } => 100,
+            Self::ExpectedExpression { .. } => 101,
+            Self::ExpectedStatementEnd { .. } => 102,
+            Self::InvalidAssignmentTarget { .. } => 103,
+            Self::ExpectedType { .. } => 104,
+            Self::ExpectedMethod { .. } => 105,
+            Self::ExpectedPattern { .. } => 106,
+            Self::ExpectedItem { .. } => 107,
+            Self::ExpectedInterpolationEnd { .. } => 108,
+            Self::AssignmentInCondition { .. } => 109,
+        }
+    }
+
+    /// Human-readable description of the error, same as its [`fmt::Display`] output.
+    pub fn message(&self) -> String {
+        self.to_string()
+    }
+
+    /// Byte range of the offending code in the source code.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Syntax(error) => error.span(),
+            Self::ExpectedToken { span, .. }
+            | Self::ExpectedExpression { span, .. }
+            | Self::ExpectedType { span, .. }
+            | Self::ExpectedPattern { span, .. }
+            | Self::ExpectedItem { span, .. }
+            | Self::ExpectedStatementEnd { span, .. }
+            | Self::ExpectedInterpolationEnd { span, .. }
+            | Self::InvalidAssignmentTarget { span }
+            | Self::AssignmentInCondition { span }
+            | Self::ExpectedMethod { span } => *span,
+        }
+    }
+
+    /// Returns the same error with its span moved by `delta` bytes.
+    pub(crate) fn moved_by(&self, delta: i64) -> Self {
+        let mut error = self.clone();
+        let span = match &mut error {
+            Self::Syntax(error) => return Self::Syntax(error.moved_by(delta)),
+            Self::ExpectedToken { span, .. }
+            | Self::ExpectedExpression { span, .. }
+            | Self::ExpectedType { span, .. }
+            | Self::ExpectedPattern { span, .. }
+            | Self::ExpectedItem { span, .. }
+            | Self::ExpectedStatementEnd { span, .. }
+            | Self::ExpectedInterpolationEnd { span, .. }
+            | Self::InvalidAssignmentTarget { span }
+            | Self::AssignmentInCondition { span }
+            | Self::ExpectedMethod { span } => span,
+        };
+        *span = moved_span(*span, delta);
+        error
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        let diagnostic = match error {
+            ParseError::Syntax(error) => return error.into(),
+            _ => Diagnostic::error(error.message(), error.span())
+                .with_code(ErrorCode(error.code() as u32)),
+        };
+
+        match error {
+            ParseError::ExpectedToken { expected, .. } => diagnostic
+                .with_primary_message(format!("expected {}", token_description(*expected))),
+            ParseError::ExpectedStatementEnd { .. } => diagnostic
+                .with_help("put the statements on separate lines or separate them with `;`"),
+            ParseError::InvalidAssignmentTarget { .. } => {
+                diagnostic.with_note("only variables, fields and elements can be assigned to")
+            }
+            ParseError::AssignmentInCondition { span } => {
+                diagnostic.with_suggestion(Suggestion::new(
+                    "did you mean `==`?",
+                    *span,
+                    "==",
+                    Applicability::MaybeIncorrect,
+                ))
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Self {
+        Self::from(&error)
+    }
+}
+
+/// Returns the span moved by `delta` bytes.
+pub(crate) fn moved_span(span: Span, delta: i64) -> Span {
+    Span::new(
+        (span.start as i64 + delta) as u32,
+        (span.end as i64 + delta) as u32,
+    )
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(error) => error.fmt(f),
+            Self::ExpectedToken {
+                expected, found, ..
+            } => write!(
+                f,
+                "expected {}, found {}",
+                token_description(*expected),
+                token_description(*found)
+            ),
+            Self::ExpectedExpression { found, .. } => {
+                write!(
+                    f,
+                    "expected an expression, found {}",
+                    token_description(*found)
+                )
+            }
+            Self::ExpectedType { found, .. } => {
+                write!(f, "expected a type, found {}", token_description(*found))
+            }
+            Self::ExpectedPattern { found, .. } => {
+                write!(f, "expected a pattern, found {}", token_description(*found))
+            }
+            Self::ExpectedItem { found, .. } => write!(
+                f,
+                "expected `def` or `struct`, found {}",
+                token_description(*found)
+            ),
+            Self::ExpectedMethod { .. } => {
+                write!(f, "expected a method declaration in `extend` block")
+            }
+            Self::ExpectedStatementEnd { found, .. } => write!(
+                f,
+                "expected a newline or `;` after the statement, found {}",
+                token_description(*found)
+            ),
+            Self::ExpectedInterpolationEnd { found, .. } => write!(
+                f,
+                "expected `}}` after the interpolated expression, found {}",
+                token_description(*found)
+            ),
+            Self::InvalidAssignmentTarget { .. } => write!(f, "invalid left side of assignment"),
+            Self::AssignmentInCondition { .. } => write!(f, "assignment used as a condition"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<SyntaxError> for ParseError {
+    fn from(error: SyntaxError) -> Self {
+        Self::Syntax(error)
+    }
+}
+
+/// Describes a token of the given kind in messages, such as `` `+=` `` or `an identifier`.
+pub(crate) fn token_description(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Identifier => "an identifier",
+        TokenKind::IntegerLiteral => "an integer literal",
+        TokenKind::FloatLiteral => "a float literal",
+        TokenKind::StringLiteral => "a string literal",
+        TokenKind::CharLiteral => "a char literal",
+        TokenKind::Equal => "`=`",
+        TokenKind::EqualEqual => "`==`",
+        TokenKind::NotEqual => "`!=`",
+        TokenKind::Plus => "`+`",
+        TokenKind::Minus => "`-`",
+        TokenKind::Multiply => "`*`",
+        TokenKind::Divide => "`/`",
+        TokenKind::Modulo => "`%`",
+        TokenKind::Power => "`**`",
+        TokenKind::PlusEqual => "`+=`",
+        TokenKind::MinusEqual => "`-=`",
+        TokenKind::MultiplyEqual => "`*=`",
+        TokenKind::DivideEqual => "`/=`",
+        TokenKind::ModuloEqual => "`%=`",
+        TokenKind::PowerEqual => "`**=`",
+        TokenKind::Not => "`!`",
+        TokenKind::OrOr => "`||`",
+        TokenKind::AndAnd => "`&&`",
+        TokenKind::LessThan => "`<`",
+        TokenKind::GreaterThan => "`>`",
+        TokenKind::LessThanEqual => "`<=`",
+        TokenKind::GreaterThanEqual => "`>=`",
+        TokenKind::And => "`&`",
+        TokenKind::Or => "`|`",
+        TokenKind::Caret => "`^`",
+        TokenKind::LessThanLessThan => "`<<`",
+        TokenKind::GreaterThanGreaterThan => "`>>`",
+        TokenKind::LessThanLessThanEqual => "`<<=`",
+        TokenKind::GreaterThanGreaterThanEqual => "`>>=`",
+        TokenKind::LeftParenthesis => "`(`",
+        TokenKind::RightParenthesis => "`)`",
+        TokenKind::LeftBracket => "`[`",
+        TokenKind::RightBracket => "`]`",
+        TokenKind::LeftBrace => "`{`",
+        TokenKind::RightBrace => "`}`",
+        TokenKind::Comma => "`,`",
+        TokenKind::Dot => "`.`",
+        TokenKind::Semicolon => "`;`",
+        TokenKind::Colon => "`:`",
+        TokenKind::ColonColon => "`::`",
+        TokenKind::Arrow => "`->`",
+        TokenKind::FatArrow => "`=>`",
+        TokenKind::Pipe => "`|>`",
+        TokenKind::DotDot => "`..`",
+        TokenKind::DotDotEqual => "`..=`",
+        TokenKind::DotDotDot => "`...`",
+        TokenKind::Question => "`?`",
+        TokenKind::At => "`@`",
+        TokenKind::Hash => "`#`",
+        TokenKind::Dollar => "`$`",
+        TokenKind::Tilde => "`~`",
+        TokenKind::Def => "`def`",
+        TokenKind::Extend => "`extend`",
+        TokenKind::With => "`with`",
+        TokenKind::If => "`if`",
+        TokenKind::Else => "`else`",
+        TokenKind::For => "`for`",
+        TokenKind::Struct => "`struct`",
+        TokenKind::Enum => "`enum`",
+        TokenKind::Fn => "`fn`",
+        TokenKind::Let => "`let`",
+        TokenKind::Mut => "`mut`",
+        TokenKind::Return => "`return`",
+        TokenKind::While => "`while`",
+        TokenKind::In => "`in`",
+        TokenKind::Break => "`break`",
+        TokenKind::Continue => "`continue`",
+        TokenKind::Match => "`match`",
+        TokenKind::Import => "`import`",
+        TokenKind::As => "`as`",
+        TokenKind::Pub => "`pub`",
+        TokenKind::Const => "`const`",
+        TokenKind::True => "`true`",
+        TokenKind::False => "`false`",
+        TokenKind::None => "`none`",
+        TokenKind::Trivia => "whitespace",
+        TokenKind::DocComment => "a doc comment",
+        TokenKind::Illegal => "an illegal character",
+        TokenKind::Eof => "the end of the file",
+    }
+}
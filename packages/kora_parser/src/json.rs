@@ -0,0 +1,29 @@
+//! Serialization of the AST to JSON.
+
+use crate::ast::Module;
+
+/// Serializes the module to JSON, for tools that aren't written in Rust, such as editor plugins.
+///
+/// The schema follows the types of the [`ast`](crate::ast) module, so it only changes when the AST
+/// does:
+/// - A struct is an object with a key for every field, such as `{"name":"a","span":{...}}`.
+/// - A variant of an enum with data is an object with the name of the variant as its only key,
+///   such as `{"Path":{...}}`, and a variant without data is its name, such as `"Add"`.
+/// - A [`Span`](kora_lexer::Span) is an object with the `start` and `end` byte offsets in the
+///   source code, such as `{"start":0,"end":1}`.
+/// - A missing optional value is `null`, and a list is an array.
+///
+/// For example, `a + 1` is the statement:
+///
+/// ```json
+/// {"Expr":{"expr":{"Binary":{
+///   "op":"Add",
+///   "op_span":{"start":2,"end":3},
+///   "lhs":{"Path":{"segments":[{"name":"a","span":{"start":0,"end":1}}],"span":{"start":0,"end":1}}},
+///   "rhs":{"Literal":{"kind":"Integer","text":"1","span":{"start":4,"end":5}}},
+///   "span":{"start":0,"end":5}
+/// }},"has_semicolon":false,"span":{"start":0,"end":5}}}
+/// ```
+pub fn to_json(module: &Module) -> String {
+    serde_json::to_string(module).expect("the AST can always be serialized")
+}
@@ -0,0 +1,19 @@
+//! Parser that builds an abstract syntax tree out of the tokens of [`kora_lexer`].
+
+pub mod arena;
+pub mod ast;
+mod builder;
+pub mod cst;
+mod error;
+#[cfg(feature = "serde")]
+mod json;
+mod parser;
+pub mod precedence;
+mod printer;
+mod reparse;
+pub mod visit;
+
+pub use error::ParseError;
+#[cfg(feature = "serde")]
+pub use json::to_json;
+pub use parser::Parser;
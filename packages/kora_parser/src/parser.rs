@@ -0,0 +1,1809 @@
+use std::{mem, sync::Arc};
+
+use kora_lexer::{interpolation_parts, InterpolationPart, Lexer, Span, Token, TokenKind};
+
+use crate::{
+    arena::{Arena, Boxed, ExprArena, ExprStorage},
+    ast::{
+        AssignExpr, Attribute, BinaryExpr, BlockExpr, BreakExpr, CallExpr, ClosureExpr, ConstDecl,
+        ContinueExpr, ErrorStmt, Expr, ExprStmt, ExtendDecl, FieldDecl, FieldExpr, FieldInit,
+        FieldPattern, ForExpr, FuncDecl, GenericParam, Ident, IfExpr, ImportDecl, ImportItem,
+        ImportKind, IndexExpr, InterpolatedStringExpr, Item, LetStmt, ListExpr, LiteralExpr,
+        LiteralKind, MapEntry, MapExpr, MatchArm, MatchExpr, MissingExpr, Module, Param, ParenExpr,
+        PathExpr, PathType, Pattern, ReturnExpr, Stmt, StringPart, StructDecl, StructExpr,
+        StructPattern, TupleExpr, TuplePattern, Type, UnaryExpr, UnaryOp, WhileExpr,
+    },
+    cst::{Checkpoint, GreenNodeBuilder, NodeKind, SyntaxNode},
+    error::ParseError,
+    precedence::{
+        infix_operator, Associativity, InfixOperator, InfixOperatorInfo, PREFIX_PRECEDENCE,
+    },
+};
+
+type ParseResult<T> = Result<T, ParseError>;
+
+/// A recursive descent parser that builds an [`Module`] out of the tokens of the source code.
+///
+/// Statements are separated by newlines or `;`. Inside parentheses, brackets and maps, newlines
+/// are insignificant, so long expressions can be split across lines.
+pub struct Parser<'source, P: ExprStorage = Boxed> {
+    /// Every token of the source code, including trivia, ending with a [`TokenKind::Eof`].
+    tokens: Vec<Token<'source>>,
+
+    /// Index of the next token to be parsed, which is never trivia.
+    position: usize,
+
+    /// Index of the token parsed last, if any.
+    previous_position: Option<usize>,
+
+    /// Errors found by the lexer and the parser.
+    errors: Vec<ParseError>,
+
+    /// Whether newlines are currently insignificant, because the parser is inside parentheses.
+    ignores_newlines: bool,
+
+    /// Whether a `{` after a path starts a block rather than the fields of a struct, because the
+    /// parser is in the condition of an `if` or a similar expression followed by a block.
+    forbids_struct_literals: bool,
+
+    /// Builder of the concrete syntax tree, if it was requested.
+    builder: Option<GreenNodeBuilder>,
+
+    /// Index of the first token that hasn't been added to the concrete syntax tree yet.
+    next_tree_token: usize,
+
+    /// Index of the first token of the statement being parsed.
+    statement_start: usize,
+
+    /// Where the child expressions are stored, see [`ExprStorage`].
+    arena: P::Arena,
+}
+
+impl<'source> Parser<'source> {
+    pub fn new(source_code: &'source str) -> Self {
+        Self::with_offset(source_code, 0)
+    }
+
+    /// Parses the whole source code as a module, returning it and the errors found, including
+    /// the syntax errors of the lexer.
+    ///
+    /// After a parse error, the parser skips to the start of the next statement and continues,
+    /// so every statement that can't be parsed is replaced by a [`Stmt::Error`] and reported.
+    pub fn parse_module(source_code: &'source str) -> (Module, Vec<ParseError>) {
+        let mut parser = Self::new(source_code);
+        let module = parser.parse_module_statements();
+        (module, parser.errors)
+    }
+
+    /// Same as [`Parser::parse_module`], but also returns the lossless concrete syntax tree of the
+    /// source code, which keeps every token, including trivia.
+    pub fn parse_module_with_syntax(
+        source_code: &'source str,
+    ) -> (Module, SyntaxNode, Vec<ParseError>) {
+        let mut parser = Self::new(source_code);
+        parser.builder = Some(GreenNodeBuilder::default());
+        let module = parser.parse_module_statements();
+        let green = parser.builder.take().unwrap().finish();
+        (module, SyntaxNode::new_root(Arc::new(green)), parser.errors)
+    }
+
+    /// Parses the whole source code as a single expression, such as an input of the REPL,
+    /// returning it if it could be parsed, and the errors found, including the syntax errors of
+    /// the lexer.
+    ///
+    /// Like inside parentheses, newlines are insignificant, so `1 +\n2` is one expression. Code
+    /// after the expression is an error, since there is no statement to recover at.
+    pub fn parse_expr(source_code: &'source str) -> (Option<Expr>, Vec<ParseError>) {
+        let mut parser = Self::new(source_code);
+        parser.ignores_newlines = true;
+        let result = parser.parse_expression().and_then(|expr| {
+            parser.expect(TokenKind::Eof)?;
+            Ok(expr)
+        });
+        match result {
+            Ok(expr) => (Some(expr), parser.errors),
+            Err(error) => {
+                parser.errors.push(error);
+                (None, parser.errors)
+            }
+        }
+    }
+
+    /// Parses the statements of `source_code`, which is a part of the source code of a module that
+    /// starts at `offset` in it, such as the statements touched by an edit. See
+    /// [`Parser::reparse`].
+    pub(crate) fn parse_statements_at(
+        source_code: &'source str,
+        offset: u32,
+    ) -> (Vec<Stmt>, Vec<ParseError>) {
+        let mut parser = Self::with_offset(source_code, offset);
+        let stmts = parser.parse_statements(TokenKind::Eof);
+        (stmts, parser.errors)
+    }
+}
+
+impl<'source> Parser<'source, Arena> {
+    /// Same as [`Parser::parse_module`], but in the arena mode, where the child expressions of the
+    /// module are stored in the returned [`ExprArena`] rather than boxed, see
+    /// [`arena`](crate::arena).
+    ///
+    /// The arena AST is parse-only: [`Visit`](crate::visit::Visit), [`Module::to_source`] and the
+    /// JSON output only accept the default boxed AST, so it has to be walked by hand by indexing
+    /// the arena.
+    pub fn parse_module_in_arena(
+        source_code: &'source str,
+    ) -> (Module<Arena>, ExprArena, Vec<ParseError>) {
+        let mut parser = Self::with_offset(source_code, 0);
+        let module = parser.parse_module_statements();
+        (module, parser.arena, parser.errors)
+    }
+}
+
+impl<'source, P: ExprStorage> Parser<'source, P> {
+    /// Creates a parser of `source_code`, which is a part of a bigger source code that starts at
+    /// `offset` in it, such as the source code of an interpolation, so that spans are positions in
+    /// the bigger source code.
+    fn with_offset(source_code: &'source str, offset: u32) -> Self {
+        let mut lexer = Lexer::builder().emit_eof(true).build(source_code);
+        let tokens = lexer
+            .by_ref()
+            .map(|token| Token {
+                span: Span::new(token.span.start + offset, token.span.end + offset),
+                ..token
+            })
+            .collect();
+        let errors = lexer
+            .finish()
+            .into_iter()
+            .map(|error| ParseError::from(error.moved_by(offset.into())))
+            .collect();
+
+        let mut parser = Self {
+            tokens,
+            position: 0,
+            previous_position: None,
+            errors,
+            ignores_newlines: false,
+            forbids_struct_literals: false,
+            builder: None,
+            next_tree_token: 0,
+            statement_start: 0,
+            arena: P::Arena::default(),
+        };
+        parser.skip_trivia();
+        parser
+    }
+
+    fn parse_module_statements(&mut self) -> Module<P> {
+        if let Some(builder) = &mut self.builder {
+            // The module includes the trivia before its first statement
+            builder.start_node(NodeKind::Module);
+        }
+
+        let stmts = self.parse_statements(TokenKind::Eof);
+        self.add_trivia_to_tree();
+        self.finish_node();
+
+        let span = Span::new(0, self.tokens.last().map_or(0, |token| token.span.end));
+        Module { stmts, span }
+    }
+
+    /// Stores a child expression, see [`ExprStorage`].
+    fn alloc(&mut self, expr: Expr<P>) -> P::ExprRef {
+        P::alloc(&mut self.arena, expr)
+    }
+
+    /// Parses statements up to the `closing` token, which is not consumed, recovering from the
+    /// errors in them.
+    fn parse_statements(&mut self, closing: TokenKind) -> Vec<Stmt<P>> {
+        let mut stmts = Vec::new();
+        while !self.at(closing) && !self.at(TokenKind::Eof) {
+            stmts.push(self.parse_statement_or_recover(closing));
+        }
+        stmts
+    }
+
+    /// Parses a statement. If it contains an error, the error is recorded and the statement is
+    /// skipped up to a synchronization point, see [`Parser::skip_to_statement_start`].
+    fn parse_statement_or_recover(&mut self, closing: TokenKind) -> Stmt<P> {
+        let start = self.position;
+        self.statement_start = start;
+        let checkpoint = self.checkpoint();
+        let tree_depth = self.tree_depth();
+        let ignored_newlines = self.ignores_newlines;
+        let forbade_struct_literals = self.forbids_struct_literals;
+
+        let error = match self.parse_statement() {
+            Ok(stmt) => return stmt,
+            Err(error) => error,
+        };
+        self.errors.push(error);
+        self.ignores_newlines = ignored_newlines;
+        self.forbids_struct_literals = forbade_struct_literals;
+
+        // The error node wraps the nodes left unfinished by the error too
+        if let Some(builder) = &mut self.builder {
+            builder.finish_nodes_to(tree_depth);
+        }
+        self.start_node_at(checkpoint, NodeKind::Error);
+        self.skip_to_statement_start(start, closing);
+        self.finish_node();
+
+        let end = self
+            .previous_position
+            .map_or(start, |position| position.max(start));
+        Stmt::Error(ErrorStmt {
+            span: self.tokens[start].span.to(self.tokens[end].span),
+        })
+    }
+
+    /// Skips the tokens of a statement that contains an error, starting at the token at `start`,
+    /// up to a point where another statement can start:
+    /// - after a `;`,
+    /// - before a token at the start of a line,
+    /// - before a keyword that starts an item, such as `def`,
+    /// - before the `closing` token of the enclosing block.
+    ///
+    /// Delimited groups such as `(...)` are skipped as a whole, so a `;` or a newline in them
+    /// doesn't stop the skipping. At least one token is skipped if the statement is empty, so
+    /// that the parser always makes progress.
+    fn skip_to_statement_start(&mut self, start: usize, closing: TokenKind) {
+        let mut depth = 0_usize;
+        loop {
+            let kind = self.peek();
+            let has_skipped_tokens = self.position > start;
+            if kind == TokenKind::Eof || (depth == 0 && kind == closing) {
+                return;
+            }
+            if depth == 0
+                && has_skipped_tokens
+                && (self.has_newline_before() || is_item_start(kind))
+            {
+                return;
+            }
+
+            self.bump();
+            if kind.is_opening_delimiter() {
+                depth += 1;
+            } else if kind.is_closing_delimiter() {
+                depth = depth.saturating_sub(1);
+            } else if kind == TokenKind::Semicolon && depth == 0 {
+                return;
+            }
+        }
+    }
+
+    fn parse_statement(&mut self) -> ParseResult<Stmt<P>> {
+        let prefix = self.parse_item_prefix()?;
+        match self.peek() {
+            TokenKind::Def => {
+                let decl = self.parse_func_decl(prefix)?;
+                return Ok(Stmt::Item(Item::Func(decl)));
+            }
+            TokenKind::Struct => {
+                let decl = self.parse_struct_decl(prefix)?;
+                return Ok(Stmt::Item(Item::Struct(decl)));
+            }
+            TokenKind::Const => {
+                let decl = self.parse_const_decl(prefix)?;
+                return Ok(Stmt::Item(Item::Const(decl)));
+            }
+            found if prefix.span.is_some() => {
+                return Err(ParseError::ExpectedItem {
+                    span: self.peek_token().span,
+                    found,
+                })
+            }
+            TokenKind::Extend => return Ok(Stmt::Item(Item::Extend(self.parse_extend_decl()?))),
+            TokenKind::Import => return Ok(Stmt::Item(Item::Import(self.parse_import_decl()?))),
+            TokenKind::Let => return self.parse_let_statement().map(Stmt::Let),
+            _ => {}
+        }
+
+        self.start_node(NodeKind::ExprStmt);
+        let expr = self.parse_expression()?;
+        let semicolon = self.eat(TokenKind::Semicolon);
+        if semicolon.is_none() && !expr.is_block_like() {
+            self.expect_statement_end()?;
+        }
+        self.finish_node();
+        let span = semicolon
+            .as_ref()
+            .map_or(expr.span(), |semicolon| expr.span().to(semicolon.span));
+        Ok(Stmt::Expr(ExprStmt {
+            expr,
+            has_semicolon: semicolon.is_some(),
+            span,
+        }))
+    }
+
+    /// Parses a variable declaration, such as `let x = 1` or `let mut y: Float = 2.0`.
+    fn parse_let_statement(&mut self) -> ParseResult<LetStmt<P>> {
+        self.start_node(NodeKind::LetStmt);
+        let let_token = self.expect(TokenKind::Let)?;
+        let is_mutable = self.eat(TokenKind::Mut).is_some();
+        let pattern = self.parse_pattern()?;
+        let ty = self.parse_type_annotation()?;
+        self.expect(TokenKind::Equal)?;
+        let value = self.parse_expression()?;
+        let semicolon = self.eat(TokenKind::Semicolon);
+        if semicolon.is_none() && !value.is_block_like() {
+            self.expect_statement_end()?;
+        }
+        self.finish_node();
+        Ok(LetStmt {
+            span: let_token.span.to(semicolon
+                .as_ref()
+                .map_or(value.span(), |semicolon| semicolon.span)),
+            is_mutable,
+            pattern,
+            ty,
+            value,
+        })
+    }
+
+    /// Checks that the statement that was just parsed is followed by a newline, or by the end of
+    /// its block or of the file.
+    fn expect_statement_end(&mut self) -> ParseResult<()> {
+        if self.has_newline_before() || self.at(TokenKind::RightBrace) || self.at(TokenKind::Eof) {
+            return Ok(());
+        }
+        Err(ParseError::ExpectedStatementEnd {
+            span: self.peek_token().span,
+            found: self.peek(),
+        })
+    }
+
+    /// Parses what can come before the keyword of an item: its doc comments, its attributes and
+    /// `pub`.
+    fn parse_item_prefix(&mut self) -> ParseResult<ItemPrefix<P>> {
+        let doc = self.doc_comment_before();
+        // The prefix is part of the node of the item that it is attached to
+        let checkpoint = self.checkpoint();
+        let attributes = self.parse_attributes()?;
+        let pub_token = self.eat(TokenKind::Pub);
+        let span = match (attributes.first(), &pub_token) {
+            (Some(attribute), _) => Some(attribute.span),
+            (None, Some(pub_token)) => Some(pub_token.span),
+            (None, None) => None,
+        };
+        Ok(ItemPrefix {
+            checkpoint,
+            doc,
+            attributes,
+            is_public: pub_token.is_some(),
+            span,
+        })
+    }
+
+    /// Parses attributes before an item, such as `@test` or `@inline(always)`.
+    fn parse_attributes(&mut self) -> ParseResult<Vec<Attribute<P>>> {
+        let mut attributes = Vec::new();
+        while self.at(TokenKind::At) {
+            self.start_node(NodeKind::Attribute);
+            let at = self.bump();
+            let name = self.parse_ident()?;
+            let (args, end) = if self.at(TokenKind::LeftParenthesis) && !self.has_newline_before() {
+                self.start_node(NodeKind::ArgList);
+                self.bump();
+                let args = self.parse_in_parentheses(|parser| {
+                    parser
+                        .parse_comma_separated(TokenKind::RightParenthesis, Self::parse_expression)
+                })?;
+                let right_parenthesis = self.expect_closing(TokenKind::RightParenthesis)?;
+                self.finish_node();
+                (args, right_parenthesis)
+            } else {
+                (Vec::new(), name.span)
+            };
+            self.finish_node();
+            attributes.push(Attribute {
+                name,
+                args,
+                span: at.span.to(end),
+            });
+        }
+        Ok(attributes)
+    }
+
+    /// Parses a function declaration, after its attributes which were parsed since the
+    /// checkpoint.
+    fn parse_func_decl(&mut self, prefix: ItemPrefix<P>) -> ParseResult<FuncDecl<P>> {
+        self.start_node_at(prefix.checkpoint, NodeKind::FuncDecl);
+        let def = self.expect(TokenKind::Def)?;
+        let name = self.parse_ident()?;
+        let generics = self.parse_generic_params()?;
+        let params = self.parse_param_list()?;
+        let return_type = self.parse_return_type()?;
+        let body = self.parse_block()?;
+        self.finish_node();
+        Ok(FuncDecl {
+            span: prefix.span.unwrap_or(def.span).to(body.span),
+            doc: prefix.doc,
+            attributes: prefix.attributes,
+            is_public: prefix.is_public,
+            name,
+            generics,
+            params,
+            return_type,
+            body,
+        })
+    }
+
+    /// Parses a constant declaration, such as `const PI = 3.14159`, after its attributes which
+    /// were parsed since the checkpoint.
+    fn parse_const_decl(&mut self, prefix: ItemPrefix<P>) -> ParseResult<ConstDecl<P>> {
+        self.start_node_at(prefix.checkpoint, NodeKind::ConstDecl);
+        let const_token = self.expect(TokenKind::Const)?;
+        let name = self.parse_ident()?;
+        let ty = self.parse_type_annotation()?;
+        self.expect(TokenKind::Equal)?;
+        let value = self.parse_expression()?;
+        let semicolon = self.eat(TokenKind::Semicolon);
+        if semicolon.is_none() && !value.is_block_like() {
+            self.expect_statement_end()?;
+        }
+        self.finish_node();
+        let end = semicolon
+            .as_ref()
+            .map_or(value.span(), |semicolon| semicolon.span);
+        Ok(ConstDecl {
+            span: prefix.span.unwrap_or(const_token.span).to(end),
+            doc: prefix.doc,
+            attributes: prefix.attributes,
+            is_public: prefix.is_public,
+            name,
+            ty,
+            value,
+        })
+    }
+
+    /// Parses the optional generic parameters of a declaration in brackets, such as `[K, V]`.
+    fn parse_generic_params(&mut self) -> ParseResult<Vec<GenericParam>> {
+        if !self.at(TokenKind::LeftBracket) {
+            return Ok(Vec::new());
+        }
+        self.start_node(NodeKind::GenericParamList);
+        self.bump();
+        let generics = self.parse_comma_separated(TokenKind::RightBracket, |parser| {
+            parser.start_node(NodeKind::GenericParam);
+            let name = parser.parse_ident()?;
+            parser.finish_node();
+            Ok(GenericParam {
+                span: name.span,
+                name,
+            })
+        })?;
+        self.expect_closing(TokenKind::RightBracket)?;
+        self.finish_node();
+        Ok(generics)
+    }
+
+    /// Parses the parameters of a function in parentheses, such as `(a: Int, b)`.
+    fn parse_param_list(&mut self) -> ParseResult<Vec<Param>> {
+        self.start_node(NodeKind::ParamList);
+        self.expect(TokenKind::LeftParenthesis)?;
+        let params = self.parse_comma_separated(TokenKind::RightParenthesis, |parser| {
+            parser.start_node(NodeKind::Param);
+            let name = parser.parse_ident()?;
+            let ty = parser.parse_type_annotation()?;
+            parser.finish_node();
+            Ok(Param {
+                span: ty.as_ref().map_or(name.span, |ty| name.span.to(ty.span())),
+                name,
+                ty,
+            })
+        })?;
+        self.expect_closing(TokenKind::RightParenthesis)?;
+        self.finish_node();
+        Ok(params)
+    }
+
+    /// Parses the optional `-> Type` after the parameters of a function.
+    fn parse_return_type(&mut self) -> ParseResult<Option<Type>> {
+        if !self.at(TokenKind::Arrow) {
+            return Ok(None);
+        }
+        self.start_node(NodeKind::ReturnType);
+        self.bump();
+        let return_type = self.parse_type()?;
+        self.finish_node();
+        Ok(Some(return_type))
+    }
+
+    /// Parses a struct declaration, after its attributes which were parsed since the checkpoint.
+    fn parse_struct_decl(&mut self, prefix: ItemPrefix<P>) -> ParseResult<StructDecl<P>> {
+        self.start_node_at(prefix.checkpoint, NodeKind::StructDecl);
+        let struct_token = self.expect(TokenKind::Struct)?;
+        let name = self.parse_ident()?;
+        let generics = self.parse_generic_params()?;
+
+        self.start_node(NodeKind::FieldList);
+        self.expect(TokenKind::LeftBrace)?;
+        let fields = self.parse_comma_separated(TokenKind::RightBrace, Self::parse_field_decl)?;
+        let right_brace = self.expect_closing(TokenKind::RightBrace)?;
+        self.finish_node();
+
+        self.finish_node();
+        Ok(StructDecl {
+            span: prefix.span.unwrap_or(struct_token.span).to(right_brace),
+            doc: prefix.doc,
+            attributes: prefix.attributes,
+            is_public: prefix.is_public,
+            name,
+            generics,
+            fields,
+        })
+    }
+
+    /// Parses an extension of a type with methods, such as
+    /// `extend Point with Display { def show(self) {} }`.
+    fn parse_extend_decl(&mut self) -> ParseResult<ExtendDecl<P>> {
+        self.start_node(NodeKind::ExtendDecl);
+        let extend = self.expect(TokenKind::Extend)?;
+        let ty = self.parse_type()?;
+        let with_type = if self.eat(TokenKind::With).is_some() {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        self.start_node(NodeKind::MethodList);
+        self.expect(TokenKind::LeftBrace)?;
+        let ignored_newlines = std::mem::replace(&mut self.ignores_newlines, false);
+        let mut methods = Vec::new();
+        for stmt in self.parse_statements(TokenKind::RightBrace) {
+            match stmt {
+                Stmt::Item(Item::Func(method)) => methods.push(method),
+                // The error has already been reported
+                Stmt::Error(_) => {}
+                stmt => self
+                    .errors
+                    .push(ParseError::ExpectedMethod { span: stmt.span() }),
+            }
+        }
+        let right_brace = self.expect_closing(TokenKind::RightBrace)?;
+        self.ignores_newlines = ignored_newlines;
+        self.finish_node();
+
+        self.finish_node();
+        Ok(ExtendDecl {
+            ty,
+            with_type,
+            methods,
+            span: extend.span.to(right_brace),
+        })
+    }
+
+    /// Parses an import, such as `import std.math`, `import std.math as m` or
+    /// `import util.{a, b as c}`.
+    fn parse_import_decl(&mut self) -> ParseResult<ImportDecl> {
+        self.start_node(NodeKind::ImportDecl);
+        let import = self.expect(TokenKind::Import)?;
+        let mut path = vec![self.parse_ident()?];
+        let mut kind = None;
+        while self.eat(TokenKind::Dot).is_some() {
+            if self.at(TokenKind::LeftBrace) {
+                kind = Some(ImportKind::Items(self.parse_import_items()?));
+                break;
+            }
+            path.push(self.parse_ident()?);
+        }
+        let kind = match kind {
+            Some(kind) => kind,
+            None => ImportKind::Module {
+                alias: self.parse_import_alias()?,
+            },
+        };
+        if self.eat(TokenKind::Semicolon).is_none() {
+            self.expect_statement_end()?;
+        }
+        self.finish_node();
+
+        let end = self
+            .previous_position
+            .map_or(import.span, |position| self.tokens[position].span);
+        Ok(ImportDecl {
+            path,
+            kind,
+            span: import.span.to(end),
+        })
+    }
+
+    /// Parses the names imported from a module in braces, such as `{a, b as c}`.
+    fn parse_import_items(&mut self) -> ParseResult<Vec<ImportItem>> {
+        self.start_node(NodeKind::ImportItemList);
+        self.expect(TokenKind::LeftBrace)?;
+        let items = self.parse_in_parentheses(|parser| {
+            parser.parse_comma_separated(TokenKind::RightBrace, |parser| {
+                parser.start_node(NodeKind::ImportItem);
+                let name = parser.parse_ident()?;
+                let alias = parser.parse_import_alias()?;
+                parser.finish_node();
+                Ok(ImportItem {
+                    span: alias
+                        .as_ref()
+                        .map_or(name.span, |alias| name.span.to(alias.span)),
+                    name,
+                    alias,
+                })
+            })
+        })?;
+        self.expect_closing(TokenKind::RightBrace)?;
+        self.finish_node();
+        Ok(items)
+    }
+
+    /// Parses the optional `as name` after an imported name.
+    fn parse_import_alias(&mut self) -> ParseResult<Option<Ident>> {
+        if self.eat(TokenKind::As).is_none() {
+            return Ok(None);
+        }
+        self.parse_ident().map(Some)
+    }
+
+    /// Parses a field of a struct, such as `x: Int`, with the doc comments before it.
+    fn parse_field_decl(&mut self) -> ParseResult<FieldDecl> {
+        let doc = self.doc_comment_before();
+        self.start_node(NodeKind::FieldDecl);
+        let pub_token = self.eat(TokenKind::Pub);
+        let name = self.parse_ident()?;
+        let ty = self.parse_type_annotation()?;
+        self.finish_node();
+        let start = pub_token
+            .as_ref()
+            .map_or(name.span, |pub_token| pub_token.span);
+        Ok(FieldDecl {
+            doc,
+            is_public: pub_token.is_some(),
+            span: ty
+                .as_ref()
+                .map_or(start.to(name.span), |ty| start.to(ty.span())),
+            name,
+            ty,
+        })
+    }
+
+    /// Parses the optional `: Type` after a name.
+    fn parse_type_annotation(&mut self) -> ParseResult<Option<Type>> {
+        if self.eat(TokenKind::Colon).is_none() {
+            return Ok(None);
+        }
+        self.parse_type().map(Some)
+    }
+
+    fn parse_type(&mut self) -> ParseResult<Type> {
+        match self.peek() {
+            TokenKind::Identifier => {
+                self.start_node(NodeKind::PathType);
+                let segments = self.parse_path_segments()?;
+                let mut end = segments[segments.len() - 1].span;
+                let args = if self.at(TokenKind::LeftBracket) {
+                    self.start_node(NodeKind::GenericArgList);
+                    self.bump();
+                    let args =
+                        self.parse_comma_separated(TokenKind::RightBracket, Self::parse_type)?;
+                    end = self.expect_closing(TokenKind::RightBracket)?;
+                    self.finish_node();
+                    args
+                } else {
+                    Vec::new()
+                };
+                self.finish_node();
+                Ok(Type::Path(PathType {
+                    span: segments[0].span.to(end),
+                    segments,
+                    args,
+                }))
+            }
+            found => Err(ParseError::ExpectedType {
+                span: self.peek_token().span,
+                found,
+            }),
+        }
+    }
+
+    fn parse_block(&mut self) -> ParseResult<BlockExpr<P>> {
+        self.start_node(NodeKind::BlockExpr);
+        let left_brace = self.expect(TokenKind::LeftBrace)?;
+        let ignored_newlines = std::mem::replace(&mut self.ignores_newlines, false);
+        let forbade_struct_literals = std::mem::replace(&mut self.forbids_struct_literals, false);
+
+        let stmts = self.parse_statements(TokenKind::RightBrace);
+
+        let right_brace = self.expect_closing(TokenKind::RightBrace)?;
+        self.ignores_newlines = ignored_newlines;
+        self.forbids_struct_literals = forbade_struct_literals;
+        self.finish_node();
+        Ok(BlockExpr {
+            stmts,
+            span: left_brace.span.to(right_brace),
+        })
+    }
+
+    fn parse_expression(&mut self) -> ParseResult<Expr<P>> {
+        self.parse_binary_expression(0)
+    }
+
+    /// Parses an expression whose infix operators all have at least the given precedence, using
+    /// precedence climbing. See [`crate::precedence`] for the precedence of every operator.
+    fn parse_binary_expression(&mut self, min_precedence: u8) -> ParseResult<Expr<P>> {
+        let checkpoint = self.checkpoint();
+        let mut lhs = self.parse_unary_expression()?;
+
+        loop {
+            // An operator on the next line starts a new statement instead of continuing this one,
+            // except for `|>` which can start a line to continue a pipeline
+            if self.has_significant_newline_before() && !self.at(TokenKind::Pipe) {
+                break;
+            }
+            let Some(&InfixOperatorInfo {
+                operator,
+                precedence,
+                associativity,
+                ..
+            }) = infix_operator(self.peek())
+            else {
+                break;
+            };
+            if precedence < min_precedence {
+                break;
+            }
+
+            let node_kind = match operator {
+                InfixOperator::Binary(_) => NodeKind::BinaryExpr,
+                InfixOperator::Assign(_) => NodeKind::AssignExpr,
+                InfixOperator::Pipe => NodeKind::PipeExpr,
+            };
+            self.start_node_at(checkpoint, node_kind);
+            let op_span = self.bump().span;
+            let rhs = match associativity {
+                Associativity::Left => self.parse_binary_expression(precedence + 1)?,
+                Associativity::Right => self.parse_binary_expression(precedence)?,
+            };
+            self.finish_node();
+            let span = lhs.span().to(rhs.span());
+
+            lhs = match operator {
+                InfixOperator::Binary(op) => Expr::Binary(BinaryExpr {
+                    op,
+                    op_span,
+                    lhs: self.alloc(lhs),
+                    rhs: self.alloc(rhs),
+                    span,
+                }),
+                InfixOperator::Assign(op) => {
+                    if !matches!(lhs, Expr::Path(_) | Expr::Field(_) | Expr::Index(_)) {
+                        return Err(ParseError::InvalidAssignmentTarget { span: lhs.span() });
+                    }
+                    Expr::Assign(AssignExpr {
+                        op,
+                        op_span,
+                        target: self.alloc(lhs),
+                        value: self.alloc(rhs),
+                        span,
+                    })
+                }
+                InfixOperator::Pipe => desugar_pipe(&mut self.arena, lhs, rhs, span),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    /// Parses an expression with any number of prefix operators, such as `-a` or `!!a`.
+    /// Prefix operators bind tighter than every infix operator except `**`, so `-a ** 2` is
+    /// `-(a ** 2)`.
+    fn parse_unary_expression(&mut self) -> ParseResult<Expr<P>> {
+        let op = match self.peek() {
+            TokenKind::Minus => UnaryOp::Negate,
+            TokenKind::Not => UnaryOp::Not,
+            _ => return self.parse_postfix_expression(),
+        };
+
+        self.start_node(NodeKind::UnaryExpr);
+        let op_span = self.bump().span;
+        let expr = self.parse_binary_expression(PREFIX_PRECEDENCE + 1)?;
+        self.finish_node();
+        Ok(Expr::Unary(UnaryExpr {
+            op,
+            op_span,
+            span: op_span.to(expr.span()),
+            expr: self.alloc(expr),
+        }))
+    }
+
+    /// Parses an expression followed by any number of calls, field accesses and indexes, such as
+    /// `make_adder(1)(2)`, `list.map(f).filter(g)` or `grid[0][1]`, which apply from left to
+    /// right.
+    fn parse_postfix_expression(&mut self) -> ParseResult<Expr<P>> {
+        let checkpoint = self.checkpoint();
+        let mut expr = self.parse_primary_expression()?;
+
+        loop {
+            // A `.` can start a line to continue a chain, since no statement starts with one
+            if self.at(TokenKind::Dot) {
+                self.start_node_at(checkpoint, NodeKind::FieldExpr);
+                self.bump();
+                let field = self.parse_ident()?;
+                self.finish_node();
+                expr = Expr::Field(FieldExpr {
+                    span: expr.span().to(field.span),
+                    expr: self.alloc(expr),
+                    field,
+                });
+                continue;
+            }
+
+            // A `(` or a `[` on the next line starts a new statement instead of calling or
+            // indexing the expression
+            if self.has_significant_newline_before() {
+                break;
+            }
+            if self.at(TokenKind::LeftBracket) {
+                self.start_node_at(checkpoint, NodeKind::IndexExpr);
+                self.bump();
+                let index = self.parse_in_parentheses(Self::parse_expression)?;
+                let right_bracket = self.expect_closing(TokenKind::RightBracket)?;
+                self.finish_node();
+                expr = Expr::Index(IndexExpr {
+                    span: expr.span().to(right_bracket),
+                    expr: self.alloc(expr),
+                    index: self.alloc(index),
+                });
+                continue;
+            }
+            if !self.at(TokenKind::LeftParenthesis) {
+                break;
+            }
+            self.start_node_at(checkpoint, NodeKind::CallExpr);
+            self.start_node(NodeKind::ArgList);
+            self.bump();
+            let args = self.parse_in_parentheses(|parser| {
+                parser.parse_comma_separated(TokenKind::RightParenthesis, Self::parse_expression)
+            })?;
+            let right_parenthesis = self.expect_closing(TokenKind::RightParenthesis)?;
+            self.finish_node();
+            self.finish_node();
+            expr = Expr::Call(CallExpr {
+                span: expr.span().to(right_parenthesis),
+                callee: self.alloc(expr),
+                args,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_primary_expression(&mut self) -> ParseResult<Expr<P>> {
+        if self.at(TokenKind::StringLiteral) && self.peek_token().text.starts_with('f') {
+            return self
+                .parse_interpolated_string()
+                .map(Expr::InterpolatedString);
+        }
+
+        if let Some(kind) = literal_kind(self.peek()) {
+            self.start_node(NodeKind::LiteralExpr);
+            let token = self.bump();
+            self.finish_node();
+            return Ok(Expr::Literal(LiteralExpr {
+                kind,
+                text: token.text.to_string(),
+                span: token.span,
+            }));
+        }
+
+        match self.peek() {
+            TokenKind::Identifier => self.parse_path_or_struct_expression(),
+            TokenKind::LeftParenthesis => {
+                let checkpoint = self.checkpoint();
+                let left_parenthesis = self.bump();
+                let (mut elements, is_tuple) =
+                    self.parse_parenthesized_elements(Self::parse_expression)?;
+                let right_parenthesis = self.expect_closing(TokenKind::RightParenthesis)?;
+                let span = left_parenthesis.span.to(right_parenthesis);
+                if is_tuple {
+                    self.start_node_at(checkpoint, NodeKind::TupleExpr);
+                    self.finish_node();
+                    Ok(Expr::Tuple(TupleExpr { elements, span }))
+                } else {
+                    self.start_node_at(checkpoint, NodeKind::ParenExpr);
+                    self.finish_node();
+                    Ok(Expr::Paren(ParenExpr {
+                        expr: self.alloc(elements.remove(0)),
+                        span,
+                    }))
+                }
+            }
+            TokenKind::LeftBracket => {
+                self.start_node(NodeKind::ListExpr);
+                let left_bracket = self.bump();
+                let elements = self.parse_in_parentheses(|parser| {
+                    parser.parse_comma_separated(TokenKind::RightBracket, Self::parse_expression)
+                })?;
+                let right_bracket = self.expect_closing(TokenKind::RightBracket)?;
+                self.finish_node();
+                Ok(Expr::List(ListExpr {
+                    elements,
+                    span: left_bracket.span.to(right_bracket),
+                }))
+            }
+            TokenKind::LeftBrace if self.at_map_start() => {
+                self.parse_map_expression().map(Expr::Map)
+            }
+            TokenKind::LeftBrace => self.parse_block().map(Expr::Block),
+            TokenKind::If => self.parse_if_expression().map(Expr::If),
+            TokenKind::For => self.parse_for_expression().map(Expr::For),
+            TokenKind::While => self.parse_while_expression().map(Expr::While),
+            TokenKind::Match => self.parse_match_expression().map(Expr::Match),
+            TokenKind::Fn => self.parse_closure_expression().map(Expr::Closure),
+            TokenKind::Break => {
+                self.start_node(NodeKind::BreakExpr);
+                let break_token = self.bump();
+                let value = if self.at_expression_end() {
+                    None
+                } else {
+                    Some(self.parse_expression()?)
+                };
+                self.finish_node();
+                Ok(Expr::Break(BreakExpr {
+                    span: value
+                        .as_ref()
+                        .map_or(break_token.span, |value| break_token.span.to(value.span())),
+                    value: value.map(|value| self.alloc(value)),
+                }))
+            }
+            TokenKind::Continue => {
+                self.start_node(NodeKind::ContinueExpr);
+                let continue_token = self.bump();
+                self.finish_node();
+                Ok(Expr::Continue(ContinueExpr {
+                    span: continue_token.span,
+                }))
+            }
+            TokenKind::Return => {
+                self.start_node(NodeKind::ReturnExpr);
+                let return_token = self.bump();
+                let value = if self.at_expression_end() {
+                    None
+                } else {
+                    Some(self.parse_expression()?)
+                };
+                self.finish_node();
+                Ok(Expr::Return(ReturnExpr {
+                    span: value.as_ref().map_or(return_token.span, |value| {
+                        return_token.span.to(value.span())
+                    }),
+                    value: value.map(|value| self.alloc(value)),
+                }))
+            }
+            found => {
+                let error = ParseError::ExpectedExpression {
+                    span: self.peek_token().span,
+                    found,
+                };
+                // An operand that is missing at the end of its statement or of its group, such as
+                // in `a +` or `f(a, )`, keeps the expression around it, which is what the user is
+                // likely typing. A statement that doesn't start with an expression is skipped
+                // like any other error.
+                let is_missing = self.position > self.statement_start
+                    && (self.at_expression_end() || found == TokenKind::RightBracket);
+                if !is_missing {
+                    return Err(error);
+                }
+                self.errors.push(error);
+                self.add_missing_node();
+                Ok(Expr::Missing(MissingExpr {
+                    span: self.missing_span(),
+                }))
+            }
+        }
+    }
+
+    /// Whether the `{` at the current position starts a map rather than a block.
+    ///
+    /// A `{` starts a map if it's followed by a `:`, which is the empty map `{:}`, or by an
+    /// identifier or a literal and a `:`, such as `{ "a": 1 }` or `{ key: value }`. A statement
+    /// can't start that way, and `{}` is an empty block. A map whose first key is any other
+    /// expression must have it in parentheses, such as `{ (a + b): 1 }`.
+    fn at_map_start(&self) -> bool {
+        match self.nth(1) {
+            TokenKind::Colon => true,
+            kind => {
+                (kind == TokenKind::Identifier || literal_kind(kind).is_some())
+                    && self.nth(2) == TokenKind::Colon
+            }
+        }
+    }
+
+    /// Parses an interpolated string literal, such as `f"Hello, {name}!"`, and the expressions
+    /// embedded in it.
+    fn parse_interpolated_string(&mut self) -> ParseResult<InterpolatedStringExpr<P>> {
+        self.start_node(NodeKind::InterpolatedStringExpr);
+        let token = self.bump();
+        self.finish_node();
+
+        let mut parts = Vec::new();
+        for part in interpolation_parts(token.text) {
+            match part {
+                InterpolationPart::Fragment(range) => parts.push(StringPart::Fragment {
+                    text: token.text[range.clone()].to_string(),
+                    span: Span::new(
+                        token.span.start + range.start as u32,
+                        token.span.start + range.end as u32,
+                    ),
+                }),
+                InterpolationPart::Expression(range) => {
+                    let offset = token.span.start + range.start as u32;
+                    let expr = self.parse_interpolation(&token.text[range], offset)?;
+                    parts.push(StringPart::Expr(expr));
+                }
+            }
+        }
+
+        Ok(InterpolatedStringExpr {
+            parts,
+            span: token.span,
+        })
+    }
+
+    /// Parses an expression embedded in an interpolated string literal, such as `a + b` in
+    /// `f"{a + b}"`, whose source code starts at `offset` in the whole source code. Like inside
+    /// parentheses, newlines are insignificant.
+    fn parse_interpolation(
+        &mut self,
+        source_code: &'source str,
+        offset: u32,
+    ) -> ParseResult<Expr<P>> {
+        let mut parser = Self::with_offset(source_code, offset);
+        parser.ignores_newlines = true;
+        // The child expressions of the interpolation are stored with the others
+        parser.arena = mem::take(&mut self.arena);
+        let result = parser
+            .parse_expression()
+            .and_then(|expr| match parser.peek() {
+                TokenKind::Eof => Ok(expr),
+                found => Err(ParseError::ExpectedInterpolationEnd {
+                    span: parser.peek_token().span,
+                    found,
+                }),
+            });
+        self.errors.append(&mut parser.errors);
+        self.arena = parser.arena;
+        result
+    }
+
+    /// Parses a map, such as `{ "a": 1, key: value }`, or the empty map `{:}`.
+    fn parse_map_expression(&mut self) -> ParseResult<MapExpr<P>> {
+        self.start_node(NodeKind::MapExpr);
+        let left_brace = self.expect(TokenKind::LeftBrace)?;
+        let entries = if self.eat(TokenKind::Colon).is_some() {
+            Vec::new()
+        } else {
+            self.parse_in_parentheses(|parser| {
+                parser.parse_comma_separated(TokenKind::RightBrace, |parser| {
+                    parser.start_node(NodeKind::MapEntry);
+                    let key = parser.parse_expression()?;
+                    parser.expect(TokenKind::Colon)?;
+                    let value = parser.parse_expression()?;
+                    parser.finish_node();
+                    Ok(MapEntry {
+                        span: key.span().to(value.span()),
+                        key,
+                        value,
+                    })
+                })
+            })?
+        };
+        let right_brace = self.expect_closing(TokenKind::RightBrace)?;
+        self.finish_node();
+        Ok(MapExpr {
+            entries,
+            span: left_brace.span.to(right_brace),
+        })
+    }
+
+    /// Parses an `if` expression with its `else if` and `else` branches, such as
+    /// `if a { b } else if c { d } else { e }`. An `else` always belongs to the nearest `if`,
+    /// since the branches are blocks.
+    fn parse_if_expression(&mut self) -> ParseResult<IfExpr<P>> {
+        self.start_node(NodeKind::IfExpr);
+        let if_token = self.expect(TokenKind::If)?;
+        let condition = self.parse_condition()?;
+        let then_branch = self.parse_block()?;
+        let else_branch = if self.eat(TokenKind::Else).is_some() {
+            let else_branch = match self.peek() {
+                TokenKind::If => Expr::If(self.parse_if_expression()?),
+                _ => Expr::Block(self.parse_block()?),
+            };
+            Some(else_branch)
+        } else {
+            None
+        };
+        self.finish_node();
+        Ok(IfExpr {
+            span: if_token.span.to(else_branch
+                .as_ref()
+                .map_or(then_branch.span, |else_branch| else_branch.span())),
+            condition: self.alloc(condition),
+            then_branch,
+            else_branch: else_branch.map(|else_branch| self.alloc(else_branch)),
+        })
+    }
+
+    /// Parses the condition of an `if` or a `while`. An assignment such as `if a = b` is kept but
+    /// reported, as it is most likely a comparison missing an `=`.
+    fn parse_condition(&mut self) -> ParseResult<Expr<P>> {
+        let condition = self.parse_expression_before_block()?;
+        if let Expr::Assign(AssignExpr {
+            op: None, op_span, ..
+        }) = &condition
+        {
+            self.errors
+                .push(ParseError::AssignmentInCondition { span: *op_span });
+        }
+        Ok(condition)
+    }
+
+    /// Parses a `for` loop, such as `for (i, x) in enumerate(xs) { print(x) }`.
+    fn parse_for_expression(&mut self) -> ParseResult<ForExpr<P>> {
+        self.start_node(NodeKind::ForExpr);
+        let for_token = self.expect(TokenKind::For)?;
+        let pattern = self.parse_pattern()?;
+        self.expect(TokenKind::In)?;
+        let iterable = self.parse_expression_before_block()?;
+        let body = self.parse_block()?;
+        self.finish_node();
+        Ok(ForExpr {
+            span: for_token.span.to(body.span),
+            pattern,
+            iterable: self.alloc(iterable),
+            body,
+        })
+    }
+
+    /// Parses an anonymous function, such as `fn(x) x * 2` or `fn(x: Int) -> Int { x * 2 }`.
+    /// The body is a block if there is a return type.
+    fn parse_closure_expression(&mut self) -> ParseResult<ClosureExpr<P>> {
+        self.start_node(NodeKind::ClosureExpr);
+        let fn_token = self.expect(TokenKind::Fn)?;
+        let params = self.parse_param_list()?;
+        let return_type = self.parse_return_type()?;
+        let body = if return_type.is_some() {
+            Expr::Block(self.parse_block()?)
+        } else {
+            self.parse_expression()?
+        };
+        self.finish_node();
+        Ok(ClosureExpr {
+            span: fn_token.span.to(body.span()),
+            params,
+            return_type,
+            body: self.alloc(body),
+            captures: Vec::new(),
+        })
+    }
+
+    /// Parses a `while` loop, such as `while i < 10 { i += 1 }`.
+    fn parse_while_expression(&mut self) -> ParseResult<WhileExpr<P>> {
+        self.start_node(NodeKind::WhileExpr);
+        let while_token = self.expect(TokenKind::While)?;
+        let condition = self.parse_condition()?;
+        let body = self.parse_block()?;
+        self.finish_node();
+        Ok(WhileExpr {
+            span: while_token.span.to(body.span),
+            condition: self.alloc(condition),
+            body,
+        })
+    }
+
+    /// Parses a `match` expression, such as `match x { 0 => a, _ => b }`.
+    ///
+    /// Arms are separated by commas, which can be left out after an arm that ends with a block or
+    /// at the end of a line.
+    fn parse_match_expression(&mut self) -> ParseResult<MatchExpr<P>> {
+        self.start_node(NodeKind::MatchExpr);
+        let match_token = self.expect(TokenKind::Match)?;
+        let scrutinee = self.parse_expression_before_block()?;
+
+        self.start_node(NodeKind::MatchArmList);
+        self.expect(TokenKind::LeftBrace)?;
+        let ignored_newlines = std::mem::replace(&mut self.ignores_newlines, false);
+        let mut arms = Vec::new();
+        while !self.at(TokenKind::RightBrace) {
+            let arm = self.parse_match_arm()?;
+            let has_comma = self.eat(TokenKind::Comma).is_some();
+            let is_last = self.at(TokenKind::RightBrace);
+            if !has_comma && !is_last && !arm.body.is_block_like() && !self.has_newline_before() {
+                return Err(ParseError::ExpectedToken {
+                    span: self.peek_token().span,
+                    expected: TokenKind::Comma,
+                    found: self.peek(),
+                });
+            }
+            arms.push(arm);
+        }
+        let right_brace = self.expect_closing(TokenKind::RightBrace)?;
+        self.ignores_newlines = ignored_newlines;
+        self.finish_node();
+
+        self.finish_node();
+        Ok(MatchExpr {
+            scrutinee: self.alloc(scrutinee),
+            arms,
+            span: match_token.span.to(right_brace),
+        })
+    }
+
+    /// Parses an arm of a `match` expression, such as `0 => a`.
+    fn parse_match_arm(&mut self) -> ParseResult<MatchArm<P>> {
+        self.start_node(NodeKind::MatchArm);
+        let pattern = self.parse_pattern()?;
+        self.expect(TokenKind::FatArrow)?;
+        let body = self.parse_expression()?;
+        self.finish_node();
+        Ok(MatchArm {
+            span: pattern.span().to(body.span()),
+            pattern,
+            body,
+        })
+    }
+
+    /// Parses a pattern that a value is matched against, such as `x`, `_`, `(a, b)`, `0` or
+    /// `Point { x, y: 0 }`.
+    fn parse_pattern(&mut self) -> ParseResult<Pattern> {
+        if let Some(kind) = literal_kind(self.peek()) {
+            self.start_node(NodeKind::LiteralPattern);
+            let token = self.bump();
+            self.finish_node();
+            return Ok(Pattern::Literal(LiteralExpr {
+                kind,
+                text: token.text.to_string(),
+                span: token.span,
+            }));
+        }
+
+        match self.peek() {
+            // Negative numbers, such as `-1`
+            TokenKind::Minus => {
+                self.start_node(NodeKind::LiteralPattern);
+                let minus = self.bump();
+                let (kind, number) = match self.peek() {
+                    TokenKind::IntegerLiteral => (LiteralKind::Integer, self.bump()),
+                    TokenKind::FloatLiteral => (LiteralKind::Float, self.bump()),
+                    found => {
+                        return Err(ParseError::ExpectedPattern {
+                            span: self.peek_token().span,
+                            found,
+                        })
+                    }
+                };
+                self.finish_node();
+                Ok(Pattern::Literal(LiteralExpr {
+                    kind,
+                    text: format!("-{}", number.text),
+                    span: minus.span.to(number.span),
+                }))
+            }
+            TokenKind::Identifier if self.peek_token().text == "_" => {
+                self.start_node(NodeKind::WildcardPattern);
+                let underscore = self.bump();
+                self.finish_node();
+                Ok(Pattern::Wildcard(underscore.span))
+            }
+            TokenKind::Identifier => {
+                let checkpoint = self.checkpoint();
+                let mut segments = self.parse_path_segments()?;
+                if self.at(TokenKind::LeftBrace) {
+                    self.start_node_at(checkpoint, NodeKind::StructPattern);
+                    let pattern = self.parse_struct_pattern_fields(segments)?;
+                    self.finish_node();
+                    Ok(Pattern::Struct(pattern))
+                } else if segments.len() == 1 {
+                    self.start_node_at(checkpoint, NodeKind::IdentPattern);
+                    self.finish_node();
+                    Ok(Pattern::Ident(segments.remove(0)))
+                } else {
+                    self.start_node_at(checkpoint, NodeKind::PathPattern);
+                    self.finish_node();
+                    Ok(Pattern::Path(PathExpr {
+                        span: segments[0].span.to(segments[segments.len() - 1].span),
+                        segments,
+                    }))
+                }
+            }
+            TokenKind::LeftParenthesis => {
+                let checkpoint = self.checkpoint();
+                let left_parenthesis = self.bump();
+                let (mut elements, is_tuple) =
+                    self.parse_parenthesized_elements(Self::parse_pattern)?;
+                let right_parenthesis = self.expect_closing(TokenKind::RightParenthesis)?;
+                if is_tuple {
+                    self.start_node_at(checkpoint, NodeKind::TuplePattern);
+                    self.finish_node();
+                    Ok(Pattern::Tuple(TuplePattern {
+                        elements,
+                        span: left_parenthesis.span.to(right_parenthesis),
+                    }))
+                } else {
+                    self.start_node_at(checkpoint, NodeKind::ParenPattern);
+                    self.finish_node();
+                    Ok(elements.remove(0))
+                }
+            }
+            found => Err(ParseError::ExpectedPattern {
+                span: self.peek_token().span,
+                found,
+            }),
+        }
+    }
+
+    /// Parses the fields of a struct pattern after its path, such as the `{ x, y: 0, .. }` in
+    /// `Point { x, y: 0, .. }`.
+    fn parse_struct_pattern_fields(&mut self, path: Vec<Ident>) -> ParseResult<StructPattern> {
+        self.start_node(NodeKind::FieldPatternList);
+        self.expect(TokenKind::LeftBrace)?;
+        let mut fields = Vec::new();
+        let mut has_rest = false;
+        while !self.at(TokenKind::RightBrace) {
+            if self.eat(TokenKind::DotDot).is_some() {
+                has_rest = true;
+                break;
+            }
+
+            self.start_node(NodeKind::FieldPattern);
+            let name = self.parse_ident()?;
+            let pattern = if self.eat(TokenKind::Colon).is_some() {
+                Some(self.parse_pattern()?)
+            } else {
+                None
+            };
+            self.finish_node();
+            fields.push(FieldPattern {
+                span: pattern
+                    .as_ref()
+                    .map_or(name.span, |pattern| name.span.to(pattern.span())),
+                name,
+                pattern,
+            });
+
+            if self.eat(TokenKind::Comma).is_none() {
+                break;
+            }
+        }
+        let right_brace = self.expect_closing(TokenKind::RightBrace)?;
+        self.finish_node();
+        Ok(StructPattern {
+            span: path[0].span.to(right_brace),
+            path,
+            fields,
+            has_rest,
+        })
+    }
+
+    /// Parses an expression that is followed by a block, such as the condition of an `if`, in
+    /// which a `{` after a path starts the block, so that `if a { b }` isn't the struct `a { b }`.
+    fn parse_expression_before_block(&mut self) -> ParseResult<Expr<P>> {
+        let forbade_struct_literals = std::mem::replace(&mut self.forbids_struct_literals, true);
+        let result = self.parse_expression();
+        self.forbids_struct_literals = forbade_struct_literals;
+        result
+    }
+
+    /// Parses a path, such as `std::math::pi`, or a struct, such as `Point { x: 1, y }`.
+    fn parse_path_or_struct_expression(&mut self) -> ParseResult<Expr<P>> {
+        let checkpoint = self.checkpoint();
+        let segments = self.parse_path_segments()?;
+        if self.at_struct_fields_start() {
+            self.start_node_at(checkpoint, NodeKind::StructExpr);
+            let expr = self.parse_struct_expression_fields(segments)?;
+            self.finish_node();
+            Ok(Expr::Struct(expr))
+        } else {
+            self.start_node_at(checkpoint, NodeKind::PathExpr);
+            self.finish_node();
+            Ok(Expr::Path(PathExpr {
+                span: segments[0].span.to(segments[segments.len() - 1].span),
+                segments,
+            }))
+        }
+    }
+
+    /// Whether the `{` at the current position, after a path, starts the fields of a struct.
+    ///
+    /// It does if it's on the same line and followed by a `}`, or by a name and a `:`, a `,` or a
+    /// `}`, such as in `Point {}`, `Point { x: 1 }` or `Point { x }`, unless structs are forbidden
+    /// because a block follows the expression, see [`Parser::parse_expression_before_block`].
+    fn at_struct_fields_start(&self) -> bool {
+        if self.forbids_struct_literals
+            || !self.at(TokenKind::LeftBrace)
+            || self.has_newline_before()
+        {
+            return false;
+        }
+        match self.nth(1) {
+            TokenKind::RightBrace => true,
+            TokenKind::Identifier => matches!(
+                self.nth(2),
+                TokenKind::Colon | TokenKind::Comma | TokenKind::RightBrace
+            ),
+            _ => false,
+        }
+    }
+
+    /// Parses the fields of a struct after its path, such as the `{ x: 1, y }` in
+    /// `Point { x: 1, y }`. Like inside parentheses, newlines are insignificant.
+    fn parse_struct_expression_fields(&mut self, path: Vec<Ident>) -> ParseResult<StructExpr<P>> {
+        self.start_node(NodeKind::FieldInitList);
+        self.expect(TokenKind::LeftBrace)?;
+        let fields = self.parse_in_parentheses(|parser| {
+            parser.parse_comma_separated(TokenKind::RightBrace, |parser| {
+                parser.start_node(NodeKind::FieldInit);
+                let name = parser.parse_ident()?;
+                let value = if parser.eat(TokenKind::Colon).is_some() {
+                    Some(parser.parse_expression()?)
+                } else {
+                    None
+                };
+                parser.finish_node();
+                Ok(FieldInit {
+                    span: value
+                        .as_ref()
+                        .map_or(name.span, |value| name.span.to(value.span())),
+                    name,
+                    value,
+                })
+            })
+        })?;
+        let right_brace = self.expect_closing(TokenKind::RightBrace)?;
+        self.finish_node();
+        Ok(StructExpr {
+            span: path[0].span.to(right_brace),
+            path,
+            fields,
+        })
+    }
+
+    /// Parses names separated by `::`, such as `std::math::pi`.
+    fn parse_path_segments(&mut self) -> ParseResult<Vec<Ident>> {
+        let mut segments = vec![self.parse_ident()?];
+        while self.eat(TokenKind::ColonColon).is_some() {
+            segments.push(self.parse_ident()?);
+        }
+        Ok(segments)
+    }
+
+    fn parse_ident(&mut self) -> ParseResult<Ident> {
+        let token = self.expect(TokenKind::Identifier)?;
+        Ok(Ident {
+            name: token.text.to_string(),
+            span: token.span,
+        })
+    }
+
+    /// Whether the next token can't continue an expression, such as the end of a line or a `)`.
+    fn at_expression_end(&self) -> bool {
+        self.has_significant_newline_before()
+            || matches!(
+                self.peek(),
+                TokenKind::Semicolon
+                    | TokenKind::Comma
+                    | TokenKind::RightParenthesis
+                    | TokenKind::RightBrace
+                    | TokenKind::Eof
+            )
+    }
+
+    /// Parses the contents of parentheses, brackets, maps or structs, in which newlines are
+    /// insignificant and structs are allowed again.
+    fn parse_in_parentheses<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let ignored_newlines = std::mem::replace(&mut self.ignores_newlines, true);
+        let forbade_struct_literals = std::mem::replace(&mut self.forbids_struct_literals, false);
+        let result = parse(self);
+        self.ignores_newlines = ignored_newlines;
+        self.forbids_struct_literals = forbade_struct_literals;
+        result
+    }
+
+    /// Parses the elements of a tuple or the expression or pattern in parentheses, after the `(`.
+    /// Returns whether they are a tuple: `()` and `(a,)` are tuples, but `(a)` is just `a`.
+    fn parse_parenthesized_elements<T>(
+        &mut self,
+        mut parse_element: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<(Vec<T>, bool)> {
+        self.parse_in_parentheses(|parser| {
+            let mut elements = Vec::new();
+            while !parser.at(TokenKind::RightParenthesis) {
+                elements.push(parse_element(parser)?);
+                if parser.eat(TokenKind::Comma).is_none() {
+                    let is_tuple = elements.len() > 1;
+                    return Ok((elements, is_tuple));
+                }
+            }
+            Ok((elements, true))
+        })
+    }
+
+    /// Parses a list of elements separated by commas, up to the `closing` token, which is not
+    /// consumed. The last element can be followed by a comma, which the formatter adds when it
+    /// splits the list across lines.
+    fn parse_comma_separated<T>(
+        &mut self,
+        closing: TokenKind,
+        mut parse_element: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        let mut elements = Vec::new();
+        if self.at(closing) {
+            return Ok(elements);
+        }
+        loop {
+            elements.push(parse_element(self)?);
+            if self.eat(TokenKind::Comma).is_none() || self.at(closing) {
+                return Ok(elements);
+            }
+        }
+    }
+
+    /// Kind of the next token.
+    fn peek(&self) -> TokenKind {
+        self.peek_token().kind
+    }
+
+    fn peek_token(&self) -> &Token<'source> {
+        &self.tokens[self.position]
+    }
+
+    /// Kind of the `n`th token after the next one, skipping trivia, so that `nth(0)` is `peek()`.
+    fn nth(&self, n: usize) -> TokenKind {
+        self.tokens[self.position..]
+            .iter()
+            .filter(|token| !is_skipped_trivia(token.kind))
+            .nth(n)
+            .map_or(TokenKind::Eof, |token| token.kind)
+    }
+
+    fn at(&self, kind: TokenKind) -> bool {
+        self.peek() == kind
+    }
+
+    /// Consumes the next token, and the trivia that follows it. The [`TokenKind::Eof`] token is
+    /// never consumed, so it is returned again by every call once it is reached.
+    fn bump(&mut self) -> Token<'source> {
+        let token = self.peek_token().clone();
+        if token.kind != TokenKind::Eof {
+            self.add_trivia_to_tree();
+            if let Some(builder) = &mut self.builder {
+                builder.token(token.kind, token.text);
+            }
+            self.next_tree_token = self.position + 1;
+            self.previous_position = Some(self.position);
+            self.position += 1;
+            self.skip_trivia();
+        }
+        token
+    }
+
+    /// Consumes the next token if it is of the given kind.
+    fn eat(&mut self, kind: TokenKind) -> Option<Token<'source>> {
+        self.at(kind).then(|| self.bump())
+    }
+
+    /// Consumes the next token, which must be of the given kind.
+    fn expect(&mut self, kind: TokenKind) -> ParseResult<Token<'source>> {
+        self.eat(kind).ok_or_else(|| ParseError::ExpectedToken {
+            span: self.peek_token().span,
+            expected: kind,
+            found: self.peek(),
+        })
+    }
+
+    /// Consumes the closing delimiter of a group, such as the `)` of a call, returning its span.
+    ///
+    /// If the delimiter is missing but the group can't continue, because the next token is at
+    /// the end of the file, on the next line or the closing delimiter of an enclosing group, the
+    /// error is recorded and the group is parsed as if the delimiter was there, so that the
+    /// incomplete code that is being typed keeps its structure. The returned span is then empty,
+    /// see [`Parser::missing_span`].
+    fn expect_closing(&mut self, kind: TokenKind) -> ParseResult<Span> {
+        if let Some(token) = self.eat(kind) {
+            return Ok(token.span);
+        }
+        let error = ParseError::ExpectedToken {
+            span: self.peek_token().span,
+            expected: kind,
+            found: self.peek(),
+        };
+        let found = self.peek();
+        let can_end_group =
+            found == TokenKind::Eof || found.is_closing_delimiter() || self.has_newline_before();
+        if !can_end_group {
+            return Err(error);
+        }
+        self.errors.push(error);
+        self.add_missing_node();
+        Ok(self.missing_span())
+    }
+
+    /// Empty span in place of a token or an expression that is missing, at the end of the
+    /// previous token.
+    fn missing_span(&self) -> Span {
+        let position = self
+            .previous_position
+            .map_or(self.peek_token().span.start, |position| {
+                self.tokens[position].span.end
+            });
+        Span::new(position, position)
+    }
+
+    /// Adds an empty [`NodeKind::Missing`] node right after the previous token, before the
+    /// trivia that follows it.
+    fn add_missing_node(&mut self) {
+        if let Some(builder) = &mut self.builder {
+            builder.start_node(NodeKind::Missing);
+            builder.finish_node();
+        }
+    }
+
+    /// Skips the trivia at the current position. Doc comments are skipped too, since the items
+    /// and fields that they document read them back with [`Parser::doc_comment_before`], and so
+    /// are illegal characters, which the lexer already reported.
+    fn skip_trivia(&mut self) {
+        while is_skipped_trivia(self.peek()) {
+            self.position += 1;
+        }
+    }
+
+    /// Starts a node of the concrete syntax tree, if it is built. The trivia before the next token
+    /// is added to the parent node.
+    fn start_node(&mut self, kind: NodeKind) {
+        self.add_trivia_to_tree();
+        if let Some(builder) = &mut self.builder {
+            builder.start_node(kind);
+        }
+    }
+
+    /// Returns a marker of the current position in the concrete syntax tree, to wrap the nodes
+    /// parsed from now on into a node with [`Parser::start_node_at`].
+    fn checkpoint(&mut self) -> Checkpoint {
+        self.add_trivia_to_tree();
+        self.builder
+            .as_ref()
+            .map_or_else(Checkpoint::default, GreenNodeBuilder::checkpoint)
+    }
+
+    fn start_node_at(&mut self, checkpoint: Checkpoint, kind: NodeKind) {
+        if let Some(builder) = &mut self.builder {
+            builder.start_node_at(checkpoint, kind);
+        }
+    }
+
+    /// Number of nodes of the concrete syntax tree that have been started but not finished.
+    fn tree_depth(&self) -> usize {
+        self.builder.as_ref().map_or(0, GreenNodeBuilder::depth)
+    }
+
+    fn finish_node(&mut self) {
+        if let Some(builder) = &mut self.builder {
+            builder.finish_node();
+        }
+    }
+
+    /// Adds the trivia tokens before the next token to the concrete syntax tree, if it is built.
+    fn add_trivia_to_tree(&mut self) {
+        if let Some(builder) = &mut self.builder {
+            for token in &self.tokens[self.next_tree_token..self.position] {
+                builder.token(token.kind, token.text);
+            }
+        }
+        self.next_tree_token = self.position;
+    }
+
+    /// Returns the text of the `///` doc comments between the previous token and the next one, if
+    /// any, without their `///` and the space after it, and with their lines joined by newlines.
+    fn doc_comment_before(&self) -> Option<String> {
+        let first_trivia = self.previous_position.map_or(0, |position| position + 1);
+        let lines = self.tokens[first_trivia..self.position]
+            .iter()
+            .filter_map(|token| {
+                let line = token.text.strip_prefix("///")?;
+                (token.kind == TokenKind::DocComment).then_some(line)
+            })
+            .map(|line| {
+                let line = line.trim_end_matches(['\n', '\r']);
+                line.strip_prefix(' ').unwrap_or(line)
+            })
+            .collect::<Vec<_>>();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Whether there is a newline between the previous token and the next one.
+    fn has_newline_before(&self) -> bool {
+        let Some(previous_position) = self.previous_position else {
+            return false;
+        };
+        self.tokens[previous_position + 1..self.position]
+            .iter()
+            .any(|token| token.text.contains('\n'))
+    }
+
+    /// Whether there is a newline before the next token that ends the current statement.
+    fn has_significant_newline_before(&self) -> bool {
+        !self.ignores_newlines && self.has_newline_before()
+    }
+}
+
+/// What comes before the keyword of an item, see [`Parser::parse_item_prefix`].
+struct ItemPrefix<P: ExprStorage> {
+    /// The position of the start of the item in the concrete syntax tree.
+    checkpoint: Checkpoint,
+    /// Text of the `///` doc comments before the item, which are not part of its span.
+    doc: Option<String>,
+    attributes: Vec<Attribute<P>>,
+    is_public: bool,
+    /// The span of the prefix up to the end of its first token, if it is not empty.
+    span: Option<Span>,
+}
+
+/// Turns `lhs |> rhs` into a call that passes `lhs` as the first argument of `rhs`: `x |> f` is
+/// `f(x)`, and `x |> g(2)` is `g(x, 2)`. To pass `x` to the function returned by a call, the call
+/// must be in parentheses, such as `x |> (make_adder(1))`.
+fn desugar_pipe<P: ExprStorage>(
+    arena: &mut P::Arena,
+    lhs: Expr<P>,
+    rhs: Expr<P>,
+    span: Span,
+) -> Expr<P> {
+    let call = match rhs {
+        Expr::Call(mut call) => {
+            call.args.insert(0, lhs);
+            CallExpr { span, ..call }
+        }
+        callee => CallExpr {
+            callee: P::alloc(arena, callee),
+            args: vec![lhs],
+            span,
+        },
+    };
+    Expr::Call(call)
+}
+
+/// Whether tokens of the given kind are skipped by the parser, see [`Parser::skip_trivia`].
+fn is_skipped_trivia(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Trivia | TokenKind::DocComment | TokenKind::Illegal
+    )
+}
+
+/// Returns the kind of literal that a token of the given kind is (if any).
+fn literal_kind(kind: TokenKind) -> Option<LiteralKind> {
+    let literal_kind = match kind {
+        TokenKind::IntegerLiteral => LiteralKind::Integer,
+        TokenKind::FloatLiteral => LiteralKind::Float,
+        TokenKind::StringLiteral => LiteralKind::String,
+        TokenKind::CharLiteral => LiteralKind::Char,
+        TokenKind::True => LiteralKind::True,
+        TokenKind::False => LiteralKind::False,
+        TokenKind::None => LiteralKind::None,
+        _ => return None,
+    };
+    Some(literal_kind)
+}
+
+/// Whether the kind is a keyword that starts an item, where the parser can resume after an error.
+fn is_item_start(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::At
+            | TokenKind::Pub
+            | TokenKind::Def
+            | TokenKind::Struct
+            | TokenKind::Const
+            | TokenKind::Extend
+            | TokenKind::Import
+    )
+}
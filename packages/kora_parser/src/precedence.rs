@@ -0,0 +1,207 @@
+//! The precedence and associativity of the operators, in a single table that the parser uses and
+//! that tools such as the formatter and the highlighter can use too, so that they all agree on
+//! how expressions are grouped.
+//!
+//! From the loosest to the tightest:
+//!
+//! | Precedence | Operators                                      | Associativity |
+//! |------------|------------------------------------------------|---------------|
+//! | 1          | `=` `+=` `-=` `*=` `/=` `%=` `**=` `<<=` `>>=` | right         |
+//! | 2          | `\|>`                                          | left          |
+//! | 3          | `..` `..=`                                     | left          |
+//! | 4          | `\|\|`                                         | left          |
+//! | 5          | `&&`                                           | left          |
+//! | 6          | `==` `!=` `<` `>` `<=` `>=`                    | left          |
+//! | 7          | `\|`                                           | left          |
+//! | 8          | `^`                                            | left          |
+//! | 9          | `&`                                            | left          |
+//! | 10         | `<<` `>>`                                      | left          |
+//! | 11         | `+` `-`                                        | left          |
+//! | 12         | `*` `/` `%`                                    | left          |
+//! | 13         | prefix `-` `!`                                 |               |
+//! | 14         | `**`                                           | right         |
+
+use kora_lexer::TokenKind;
+
+use crate::ast::BinaryOp;
+
+/// How a chain of operators of the same precedence is grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a = b = c` is `a = (b = c)`.
+    Right,
+}
+
+/// An operator written between its two operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfixOperator {
+    /// An operation, such as `+`.
+    Binary(BinaryOp),
+    /// An assignment, combined with an operation for compound assignments such as `+=`.
+    Assign(Option<BinaryOp>),
+    /// `|>`, which passes its left side as the first argument of its right side.
+    Pipe,
+}
+
+/// An infix operator with the token that it is written with, its precedence and its
+/// associativity. Operators with a higher precedence bind tighter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfixOperatorInfo {
+    pub token: TokenKind,
+    pub operator: InfixOperator,
+    pub precedence: u8,
+    pub associativity: Associativity,
+}
+
+/// Precedence of the prefix operators `-` and `!`, which bind tighter than every infix operator
+/// except `**`, so `-a * b` is `(-a) * b` but `-a ** b` is `-(a ** b)`.
+pub const PREFIX_PRECEDENCE: u8 = 13;
+
+/// Every infix operator, from the loosest to the tightest.
+pub const INFIX_OPERATORS: [InfixOperatorInfo; 31] = {
+    use Associativity::{Left, Right};
+    use InfixOperator::{Assign, Binary, Pipe};
+
+    const fn info(
+        token: TokenKind,
+        operator: InfixOperator,
+        precedence: u8,
+        associativity: Associativity,
+    ) -> InfixOperatorInfo {
+        InfixOperatorInfo {
+            token,
+            operator,
+            precedence,
+            associativity,
+        }
+    }
+
+    [
+        info(TokenKind::Equal, Assign(None), 1, Right),
+        info(TokenKind::PlusEqual, Assign(Some(BinaryOp::Add)), 1, Right),
+        info(
+            TokenKind::MinusEqual,
+            Assign(Some(BinaryOp::Subtract)),
+            1,
+            Right,
+        ),
+        info(
+            TokenKind::MultiplyEqual,
+            Assign(Some(BinaryOp::Multiply)),
+            1,
+            Right,
+        ),
+        info(
+            TokenKind::DivideEqual,
+            Assign(Some(BinaryOp::Divide)),
+            1,
+            Right,
+        ),
+        info(
+            TokenKind::ModuloEqual,
+            Assign(Some(BinaryOp::Modulo)),
+            1,
+            Right,
+        ),
+        info(
+            TokenKind::PowerEqual,
+            Assign(Some(BinaryOp::Power)),
+            1,
+            Right,
+        ),
+        info(
+            TokenKind::LessThanLessThanEqual,
+            Assign(Some(BinaryOp::ShiftLeft)),
+            1,
+            Right,
+        ),
+        info(
+            TokenKind::GreaterThanGreaterThanEqual,
+            Assign(Some(BinaryOp::ShiftRight)),
+            1,
+            Right,
+        ),
+        info(TokenKind::Pipe, Pipe, 2, Left),
+        info(TokenKind::DotDot, Binary(BinaryOp::Range), 3, Left),
+        info(
+            TokenKind::DotDotEqual,
+            Binary(BinaryOp::RangeInclusive),
+            3,
+            Left,
+        ),
+        info(TokenKind::OrOr, Binary(BinaryOp::Or), 4, Left),
+        info(TokenKind::AndAnd, Binary(BinaryOp::And), 5, Left),
+        info(TokenKind::EqualEqual, Binary(BinaryOp::Equal), 6, Left),
+        info(TokenKind::NotEqual, Binary(BinaryOp::NotEqual), 6, Left),
+        info(TokenKind::LessThan, Binary(BinaryOp::LessThan), 6, Left),
+        info(
+            TokenKind::GreaterThan,
+            Binary(BinaryOp::GreaterThan),
+            6,
+            Left,
+        ),
+        info(
+            TokenKind::LessThanEqual,
+            Binary(BinaryOp::LessThanEqual),
+            6,
+            Left,
+        ),
+        info(
+            TokenKind::GreaterThanEqual,
+            Binary(BinaryOp::GreaterThanEqual),
+            6,
+            Left,
+        ),
+        info(TokenKind::Or, Binary(BinaryOp::BitOr), 7, Left),
+        info(TokenKind::Caret, Binary(BinaryOp::BitXor), 8, Left),
+        info(TokenKind::And, Binary(BinaryOp::BitAnd), 9, Left),
+        info(
+            TokenKind::LessThanLessThan,
+            Binary(BinaryOp::ShiftLeft),
+            10,
+            Left,
+        ),
+        info(
+            TokenKind::GreaterThanGreaterThan,
+            Binary(BinaryOp::ShiftRight),
+            10,
+            Left,
+        ),
+        info(TokenKind::Plus, Binary(BinaryOp::Add), 11, Left),
+        info(TokenKind::Minus, Binary(BinaryOp::Subtract), 11, Left),
+        info(TokenKind::Multiply, Binary(BinaryOp::Multiply), 12, Left),
+        info(TokenKind::Divide, Binary(BinaryOp::Divide), 12, Left),
+        info(TokenKind::Modulo, Binary(BinaryOp::Modulo), 12, Left),
+        info(TokenKind::Power, Binary(BinaryOp::Power), 14, Right),
+    ]
+};
+
+/// Returns the infix operator written with a token of the given kind, if any.
+pub fn infix_operator(kind: TokenKind) -> Option<&'static InfixOperatorInfo> {
+    INFIX_OPERATORS.iter().find(|info| info.token == kind)
+}
+
+impl InfixOperator {
+    /// Returns the token, precedence and associativity of the operator.
+    pub fn info(self) -> &'static InfixOperatorInfo {
+        INFIX_OPERATORS
+            .iter()
+            .find(|info| info.operator == self)
+            .expect("every infix operator is in the table")
+    }
+}
+
+impl BinaryOp {
+    /// Precedence of the operator written as an infix operator, see the [module documentation].
+    ///
+    /// [module documentation]: crate::precedence
+    pub fn precedence(self) -> u8 {
+        InfixOperator::Binary(self).info().precedence
+    }
+
+    pub fn associativity(self) -> Associativity {
+        InfixOperator::Binary(self).info().associativity
+    }
+}
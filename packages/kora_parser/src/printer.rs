@@ -0,0 +1,717 @@
+//! Printing of the AST back to source code, see [`Module::to_source`].
+//!
+//! The output is valid Kora for any AST, including ASTs that weren't parsed, such as the ones
+//! built by code generators: parentheses are added where the precedence of the operators requires
+//! them. Comments and the original layout are not kept, since the AST doesn't have them, and
+//! statements that couldn't be parsed ([`Stmt::Error`]) are left out. Missing expressions
+//! ([`Expr::Missing`]) are printed as nothing, so code with them stays invalid.
+
+use crate::{
+    ast::{
+        Attribute, BlockExpr, BreakExpr, CallExpr, ConstDecl, Expr, ExtendDecl, FieldDecl,
+        FieldExpr, FuncDecl, GenericParam, Ident, ImportDecl, ImportKind, IndexExpr, Item, LetStmt,
+        MatchArm, Module, Param, PathType, Pattern, ReturnExpr, Stmt, StringPart, StructDecl, Type,
+        UnaryExpr,
+    },
+    precedence::{Associativity, InfixOperator, PREFIX_PRECEDENCE},
+};
+
+/// Precedence of the expressions that take everything on their right, such as `return a` or
+/// `fn(x) x`, which is lower than the one of every operator.
+const OPEN_PRECEDENCE: u8 = 0;
+
+/// Precedence of calls, field accesses and indexing, which bind tighter than every operator.
+const POSTFIX_PRECEDENCE: u8 = 15;
+
+/// Precedence of the expressions that are complete on their own, such as literals.
+const PRIMARY_PRECEDENCE: u8 = 16;
+
+const INDENT: &str = "    ";
+
+impl Module {
+    /// Prints the module as source code, with one statement per line and blocks indented by four
+    /// spaces. See the [module documentation](crate::printer) for what is kept.
+    pub fn to_source(&self) -> String {
+        let mut printer = Printer::default();
+        for stmt in &self.stmts {
+            printer.print_stmt(stmt);
+        }
+        printer.output
+    }
+}
+
+impl Stmt {
+    /// Prints the statement as source code, ending with a newline.
+    pub fn to_source(&self) -> String {
+        let mut printer = Printer::default();
+        printer.print_stmt(self);
+        printer.output
+    }
+}
+
+impl Expr {
+    /// Prints the expression as source code.
+    pub fn to_source(&self) -> String {
+        let mut printer = Printer::default();
+        printer.print_expr(self);
+        printer.output
+    }
+}
+
+#[derive(Default)]
+struct Printer {
+    output: String,
+    /// Number of blocks that the next line is in.
+    depth: usize,
+}
+
+impl Printer {
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    /// Starts a new line at the current indentation.
+    fn start_line(&mut self) {
+        for _ in 0..self.depth {
+            self.output.push_str(INDENT);
+        }
+    }
+
+    /// Writes the items separated by `, `.
+    fn write_separated<T>(&mut self, items: &[T], mut print: impl FnMut(&mut Self, &T)) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            print(self, item);
+        }
+    }
+
+    fn print_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Item(item) => self.print_item(item),
+            Stmt::Let(stmt) => {
+                self.start_line();
+                self.print_let_stmt(stmt);
+                self.write("\n");
+            }
+            Stmt::Expr(stmt) => {
+                self.start_line();
+                self.print_expr(&stmt.expr);
+                if stmt.has_semicolon {
+                    self.write(";");
+                }
+                self.write("\n");
+            }
+            Stmt::Error(_) => {}
+        }
+    }
+
+    fn print_item(&mut self, item: &Item) {
+        match item {
+            Item::Func(decl) => self.print_func_decl(decl),
+            Item::Struct(decl) => self.print_struct_decl(decl),
+            Item::Extend(decl) => self.print_extend_decl(decl),
+            Item::Import(decl) => self.print_import_decl(decl),
+            Item::Const(decl) => self.print_const_decl(decl),
+        }
+    }
+
+    fn print_attributes(&mut self, attributes: &[Attribute]) {
+        for attribute in attributes {
+            self.start_line();
+            self.write("@");
+            self.print_ident(&attribute.name);
+            if !attribute.args.is_empty() {
+                self.write("(");
+                self.write_separated(&attribute.args, |printer, arg| printer.print_expr(arg));
+                self.write(")");
+            }
+            self.write("\n");
+        }
+    }
+
+    fn print_generics(&mut self, generics: &[GenericParam]) {
+        if !generics.is_empty() {
+            self.write("[");
+            self.write_separated(generics, |printer, param| printer.print_ident(&param.name));
+            self.write("]");
+        }
+    }
+
+    fn print_params(&mut self, params: &[Param]) {
+        self.write("(");
+        self.write_separated(params, |printer, param| {
+            printer.print_ident(&param.name);
+            if let Some(ty) = &param.ty {
+                printer.write(": ");
+                printer.print_type(ty);
+            }
+        });
+        self.write(")");
+    }
+
+    fn print_func_decl(&mut self, decl: &FuncDecl) {
+        self.print_doc(decl.doc.as_deref());
+        self.print_attributes(&decl.attributes);
+        self.start_line();
+        if decl.is_public {
+            self.write("pub ");
+        }
+        self.write("def ");
+        self.print_ident(&decl.name);
+        self.print_generics(&decl.generics);
+        self.print_params(&decl.params);
+        if let Some(ty) = &decl.return_type {
+            self.write(" -> ");
+            self.print_type(ty);
+        }
+        self.write(" ");
+        self.print_block(&decl.body);
+        self.write("\n");
+    }
+
+    fn print_struct_decl(&mut self, decl: &StructDecl) {
+        self.print_doc(decl.doc.as_deref());
+        self.print_attributes(&decl.attributes);
+        self.start_line();
+        if decl.is_public {
+            self.write("pub ");
+        }
+        self.write("struct ");
+        self.print_ident(&decl.name);
+        self.print_generics(&decl.generics);
+        if decl.fields.is_empty() {
+            self.write(" {}\n");
+            return;
+        }
+        self.write(" {\n");
+        self.depth += 1;
+        for field in &decl.fields {
+            self.print_field_decl(field);
+        }
+        self.depth -= 1;
+        self.start_line();
+        self.write("}\n");
+    }
+
+    fn print_doc(&mut self, doc: Option<&str>) {
+        for line in doc.into_iter().flat_map(str::lines) {
+            self.start_line();
+            self.write("///");
+            if !line.is_empty() {
+                self.write(" ");
+                self.write(line);
+            }
+            self.write("\n");
+        }
+    }
+
+    fn print_field_decl(&mut self, field: &FieldDecl) {
+        self.print_doc(field.doc.as_deref());
+        self.start_line();
+        if field.is_public {
+            self.write("pub ");
+        }
+        self.print_ident(&field.name);
+        if let Some(ty) = &field.ty {
+            self.write(": ");
+            self.print_type(ty);
+        }
+        self.write(",\n");
+    }
+
+    fn print_extend_decl(&mut self, decl: &ExtendDecl) {
+        self.start_line();
+        self.write("extend ");
+        self.print_type(&decl.ty);
+        if let Some(ty) = &decl.with_type {
+            self.write(" with ");
+            self.print_type(ty);
+        }
+        if decl.methods.is_empty() {
+            self.write(" {}\n");
+            return;
+        }
+        self.write(" {\n");
+        self.depth += 1;
+        for method in &decl.methods {
+            self.print_func_decl(method);
+        }
+        self.depth -= 1;
+        self.start_line();
+        self.write("}\n");
+    }
+
+    fn print_import_decl(&mut self, decl: &ImportDecl) {
+        self.start_line();
+        self.write("import ");
+        for (index, segment) in decl.path.iter().enumerate() {
+            if index > 0 {
+                self.write(".");
+            }
+            self.print_ident(segment);
+        }
+        match &decl.kind {
+            ImportKind::Module { alias } => self.print_alias(alias.as_ref()),
+            ImportKind::Items(items) => {
+                self.write(".{");
+                self.write_separated(items, |printer, item| {
+                    printer.print_ident(&item.name);
+                    printer.print_alias(item.alias.as_ref());
+                });
+                self.write("}");
+            }
+        }
+        self.write("\n");
+    }
+
+    fn print_alias(&mut self, alias: Option<&Ident>) {
+        if let Some(alias) = alias {
+            self.write(" as ");
+            self.print_ident(alias);
+        }
+    }
+
+    fn print_type(&mut self, ty: &Type) {
+        match ty {
+            Type::Path(PathType { segments, args, .. }) => {
+                self.print_path(segments);
+                if !args.is_empty() {
+                    self.write("[");
+                    self.write_separated(args, Self::print_type);
+                    self.write("]");
+                }
+            }
+        }
+    }
+
+    fn print_const_decl(&mut self, decl: &ConstDecl) {
+        self.print_doc(decl.doc.as_deref());
+        self.print_attributes(&decl.attributes);
+        self.start_line();
+        if decl.is_public {
+            self.write("pub ");
+        }
+        self.write("const ");
+        self.print_ident(&decl.name);
+        if let Some(ty) = &decl.ty {
+            self.write(": ");
+            self.print_type(ty);
+        }
+        self.write(" = ");
+        self.print_expr(&decl.value);
+        self.write("\n");
+    }
+
+    fn print_let_stmt(&mut self, stmt: &LetStmt) {
+        self.write("let ");
+        if stmt.is_mutable {
+            self.write("mut ");
+        }
+        self.print_pattern(&stmt.pattern);
+        if let Some(ty) = &stmt.ty {
+            self.write(": ");
+            self.print_type(ty);
+        }
+        self.write(" = ");
+        self.print_expr(&stmt.value);
+    }
+
+    fn print_block(&mut self, block: &BlockExpr) {
+        if block.stmts.is_empty() {
+            self.write("{}");
+            return;
+        }
+        self.write("{\n");
+        self.depth += 1;
+        for stmt in &block.stmts {
+            self.print_stmt(stmt);
+        }
+        self.depth -= 1;
+        self.start_line();
+        self.write("}");
+    }
+
+    /// Prints an expression that is delimited by the code around it, such as an argument.
+    fn print_expr(&mut self, expr: &Expr) {
+        self.print_subexpr(expr, OPEN_PRECEDENCE, true);
+    }
+
+    /// Prints the expression, in parentheses if its precedence is lower than `min_precedence`.
+    ///
+    /// `open_end` is whether the expression can take everything up to the end of the enclosing
+    /// delimited expression, such as the right side of `a + b`, unlike the left side. Otherwise,
+    /// open expressions such as `fn(x) x` need parentheses.
+    fn print_subexpr(&mut self, expr: &Expr, min_precedence: u8, open_end: bool) {
+        let needs_parentheses = match expr {
+            Expr::Closure(_) | Expr::Break(_) | Expr::Return(_) => !open_end,
+            _ => precedence(expr) < min_precedence,
+        };
+        if needs_parentheses {
+            self.write("(");
+        }
+        let open_end = open_end || needs_parentheses;
+
+        match expr {
+            Expr::Literal(literal) => self.write(&literal.text),
+            Expr::InterpolatedString(string) => {
+                // Triple quotes allow newlines and quotes in the text
+                let quote = if string.parts.iter().any(|part| {
+                    matches!(part, StringPart::Fragment { text, .. } if text.contains(['\n', '"']))
+                }) {
+                    "\"\"\""
+                } else {
+                    "\""
+                };
+                self.write("f");
+                self.write(quote);
+                for part in &string.parts {
+                    match part {
+                        StringPart::Fragment { text, .. } => self.write(text),
+                        StringPart::Expr(expr) => {
+                            self.write("{");
+                            self.print_expr(expr);
+                            self.write("}");
+                        }
+                    }
+                }
+                self.write(quote);
+            }
+            Expr::Path(path) => self.print_path(&path.segments),
+            Expr::Paren(paren) => {
+                self.write("(");
+                self.print_expr(&paren.expr);
+                self.write(")");
+            }
+            Expr::Tuple(tuple) => {
+                self.write("(");
+                self.write_separated(&tuple.elements, |printer, element| {
+                    printer.print_expr(element)
+                });
+                if tuple.elements.len() == 1 {
+                    self.write(",");
+                }
+                self.write(")");
+            }
+            Expr::List(list) => {
+                self.write("[");
+                self.write_separated(&list.elements, |printer, element| {
+                    printer.print_expr(element)
+                });
+                self.write("]");
+            }
+            Expr::Map(map) => {
+                if map.entries.is_empty() {
+                    self.write("{:}");
+                } else {
+                    self.write("{ ");
+                    self.write_separated(&map.entries, |printer, entry| {
+                        // Only names and literals can be keys without parentheses, see
+                        // `Parser::at_map_start`
+                        let is_simple_key = match &entry.key {
+                            Expr::Literal(_) | Expr::Paren(_) | Expr::Tuple(_) => true,
+                            Expr::Path(path) => path.segments.len() == 1,
+                            _ => false,
+                        };
+                        if is_simple_key {
+                            printer.print_expr(&entry.key);
+                        } else {
+                            printer.write("(");
+                            printer.print_expr(&entry.key);
+                            printer.write(")");
+                        }
+                        printer.write(": ");
+                        printer.print_expr(&entry.value);
+                    });
+                    self.write(" }");
+                }
+            }
+            Expr::Struct(expr) => {
+                self.print_path(&expr.path);
+                if expr.fields.is_empty() {
+                    self.write(" {}");
+                } else {
+                    self.write(" { ");
+                    self.write_separated(&expr.fields, |printer, field| {
+                        printer.print_ident(&field.name);
+                        if let Some(value) = &field.value {
+                            printer.write(": ");
+                            printer.print_expr(value);
+                        }
+                    });
+                    self.write(" }");
+                }
+            }
+            Expr::Block(block) => self.print_block(block),
+            Expr::Call(call) => {
+                self.print_subexpr(&call.callee, POSTFIX_PRECEDENCE, false);
+                self.write("(");
+                self.write_separated(&call.args, |printer, arg| printer.print_expr(arg));
+                self.write(")");
+            }
+            Expr::Field(field) => {
+                self.print_subexpr(&field.expr, POSTFIX_PRECEDENCE, false);
+                self.write(".");
+                self.print_ident(&field.field);
+            }
+            Expr::Index(index) => {
+                self.print_subexpr(&index.expr, POSTFIX_PRECEDENCE, false);
+                self.write("[");
+                self.print_expr(&index.index);
+                self.write("]");
+            }
+            Expr::If(expr) => {
+                self.write("if ");
+                self.print_before_block(&expr.condition);
+                self.write(" ");
+                self.print_block(&expr.then_branch);
+                match expr.else_branch.as_deref() {
+                    None => {}
+                    Some(Expr::Block(block)) => {
+                        self.write(" else ");
+                        self.print_block(block);
+                    }
+                    Some(else_branch @ Expr::If(_)) => {
+                        self.write(" else ");
+                        self.print_expr(else_branch);
+                    }
+                    Some(else_branch) => {
+                        self.write(" else ");
+                        self.print_in_block(else_branch);
+                    }
+                }
+            }
+            Expr::For(expr) => {
+                self.write("for ");
+                self.print_pattern(&expr.pattern);
+                self.write(" in ");
+                self.print_before_block(&expr.iterable);
+                self.write(" ");
+                self.print_block(&expr.body);
+            }
+            Expr::While(expr) => {
+                self.write("while ");
+                self.print_before_block(&expr.condition);
+                self.write(" ");
+                self.print_block(&expr.body);
+            }
+            Expr::Match(expr) => {
+                self.write("match ");
+                self.print_before_block(&expr.scrutinee);
+                if expr.arms.is_empty() {
+                    self.write(" {}");
+                } else {
+                    self.write(" {\n");
+                    self.depth += 1;
+                    for arm in &expr.arms {
+                        self.print_match_arm(arm);
+                    }
+                    self.depth -= 1;
+                    self.start_line();
+                    self.write("}");
+                }
+            }
+            Expr::Closure(closure) => {
+                self.write("fn");
+                self.print_params(&closure.params);
+                self.write(" ");
+                match &closure.return_type {
+                    // The body of a closure with a return type must be a block
+                    Some(ty) => {
+                        self.write("-> ");
+                        self.print_type(ty);
+                        self.write(" ");
+                        match closure.body.as_ref() {
+                            Expr::Block(block) => self.print_block(block),
+                            body => self.print_in_block(body),
+                        }
+                    }
+                    None => self.print_subexpr(&closure.body, OPEN_PRECEDENCE, open_end),
+                }
+            }
+            Expr::Break(expr) => {
+                self.write("break");
+                if let Some(value) = &expr.value {
+                    self.write(" ");
+                    self.print_subexpr(value, OPEN_PRECEDENCE, open_end);
+                }
+            }
+            Expr::Continue(_) => self.write("continue"),
+            // There is no code to print, so the output is invalid like the parsed code was
+            Expr::Missing(_) => {}
+            Expr::Return(expr) => {
+                self.write("return");
+                if let Some(value) = &expr.value {
+                    self.write(" ");
+                    self.print_subexpr(value, OPEN_PRECEDENCE, open_end);
+                }
+            }
+            Expr::Unary(unary) => {
+                self.write(unary.op.symbol());
+                self.print_operand(&unary.expr, PREFIX_PRECEDENCE + 1, open_end);
+            }
+            Expr::Binary(binary) => {
+                let precedence = binary.op.precedence();
+                let (lhs_precedence, rhs_precedence) = match binary.op.associativity() {
+                    Associativity::Left => (precedence, precedence + 1),
+                    Associativity::Right => (precedence + 1, precedence),
+                };
+                self.print_subexpr(&binary.lhs, lhs_precedence, false);
+                self.write(" ");
+                self.write(binary.op.symbol());
+                self.write(" ");
+                self.print_operand(&binary.rhs, rhs_precedence, open_end);
+            }
+            Expr::Assign(assign) => {
+                let precedence = InfixOperator::Assign(assign.op).info().precedence;
+                self.print_subexpr(&assign.target, precedence + 1, false);
+                self.write(" ");
+                if let Some(op) = assign.op {
+                    self.write(op.symbol());
+                }
+                self.write("= ");
+                self.print_operand(&assign.value, precedence, open_end);
+            }
+        }
+
+        if needs_parentheses {
+            self.write(")");
+        }
+    }
+
+    /// Prints the operand on the right of an operator. Prefix operations don't need parentheses
+    /// there, since a prefix operator only applies to the operand that follows it, such as in
+    /// `a ** -b`.
+    fn print_operand(&mut self, expr: &Expr, min_precedence: u8, open_end: bool) {
+        match expr {
+            Expr::Unary(_) => self.print_subexpr(expr, PREFIX_PRECEDENCE, open_end),
+            _ => self.print_subexpr(expr, min_precedence, open_end),
+        }
+    }
+
+    /// Prints an expression that is followed by a block, such as the condition of an `if`, in
+    /// parentheses if it has a struct outside of delimiters, whose `{` would start the block.
+    fn print_before_block(&mut self, expr: &Expr) {
+        if has_undelimited_struct(expr) {
+            self.write("(");
+            self.print_expr(expr);
+            self.write(")");
+        } else {
+            self.print_subexpr(expr, OPEN_PRECEDENCE, false);
+        }
+    }
+
+    /// Prints an expression that isn't a block where a block is required, as the only statement
+    /// of a block.
+    fn print_in_block(&mut self, expr: &Expr) {
+        self.write("{\n");
+        self.depth += 1;
+        self.start_line();
+        self.print_expr(expr);
+        self.write("\n");
+        self.depth -= 1;
+        self.start_line();
+        self.write("}");
+    }
+
+    fn print_match_arm(&mut self, arm: &MatchArm) {
+        self.start_line();
+        self.print_pattern(&arm.pattern);
+        self.write(" => ");
+        self.print_expr(&arm.body);
+        if !arm.body.is_block_like() {
+            self.write(",");
+        }
+        self.write("\n");
+    }
+
+    fn print_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Ident(name) => self.print_ident(name),
+            Pattern::Wildcard(_) => self.write("_"),
+            Pattern::Literal(literal) => self.write(&literal.text),
+            Pattern::Path(path) => self.print_path(&path.segments),
+            Pattern::Tuple(tuple) => {
+                self.write("(");
+                self.write_separated(&tuple.elements, Self::print_pattern);
+                if tuple.elements.len() == 1 {
+                    self.write(",");
+                }
+                self.write(")");
+            }
+            Pattern::Struct(pattern) => {
+                self.print_path(&pattern.path);
+                if pattern.fields.is_empty() && !pattern.has_rest {
+                    self.write(" {}");
+                    return;
+                }
+                self.write(" { ");
+                self.write_separated(&pattern.fields, |printer, field| {
+                    printer.print_ident(&field.name);
+                    if let Some(pattern) = &field.pattern {
+                        printer.write(": ");
+                        printer.print_pattern(pattern);
+                    }
+                });
+                if pattern.has_rest {
+                    if !pattern.fields.is_empty() {
+                        self.write(", ");
+                    }
+                    self.write("..");
+                }
+                self.write(" }");
+            }
+        }
+    }
+
+    fn print_path(&mut self, segments: &[Ident]) {
+        for (index, segment) in segments.iter().enumerate() {
+            if index > 0 {
+                self.write("::");
+            }
+            self.print_ident(segment);
+        }
+    }
+
+    fn print_ident(&mut self, ident: &Ident) {
+        self.write(&ident.name);
+    }
+}
+
+/// Precedence of the expression, which is the precedence of its operator for operations.
+/// Whether the expression has a struct that isn't inside parentheses, brackets or braces, such as
+/// `p == Point { x, y }`, see [`Printer::print_before_block`].
+fn has_undelimited_struct(expr: &Expr) -> bool {
+    match expr {
+        Expr::Struct(_) => true,
+        Expr::Call(CallExpr { callee: expr, .. })
+        | Expr::Field(FieldExpr { expr, .. })
+        | Expr::Index(IndexExpr { expr, .. })
+        | Expr::Unary(UnaryExpr { expr, .. }) => has_undelimited_struct(expr),
+        Expr::Binary(binary) => {
+            has_undelimited_struct(&binary.lhs) || has_undelimited_struct(&binary.rhs)
+        }
+        Expr::Assign(assign) => {
+            has_undelimited_struct(&assign.target) || has_undelimited_struct(&assign.value)
+        }
+        Expr::Closure(closure) => {
+            closure.return_type.is_none() && has_undelimited_struct(&closure.body)
+        }
+        Expr::Break(BreakExpr { value, .. }) | Expr::Return(ReturnExpr { value, .. }) => {
+            value.as_deref().is_some_and(has_undelimited_struct)
+        }
+        _ => false,
+    }
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Closure(_) | Expr::Break(_) | Expr::Return(_) => OPEN_PRECEDENCE,
+        Expr::Assign(assign) => InfixOperator::Assign(assign.op).info().precedence,
+        Expr::Binary(binary) => binary.op.precedence(),
+        Expr::Unary(_) => PREFIX_PRECEDENCE,
+        Expr::Call(_) | Expr::Field(_) | Expr::Index(_) => POSTFIX_PRECEDENCE,
+        _ => PRIMARY_PRECEDENCE,
+    }
+}
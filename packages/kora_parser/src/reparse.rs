@@ -0,0 +1,299 @@
+//! Incremental reparsing of a module after an edit, see [`Parser::reparse`].
+
+use kora_lexer::{Span, TextEdit};
+
+use crate::{
+    ast::{
+        Attribute, BlockExpr, ConstDecl, Expr, ExtendDecl, FieldDecl, FuncDecl, GenericParam,
+        Ident, ImportDecl, ImportKind, LetStmt, MatchArm, Module, Param, Pattern, Stmt, StringPart,
+        StructDecl, Type,
+    },
+    error::{moved_span, ParseError},
+    visit::{
+        walk_attribute_mut, walk_block_mut, walk_const_decl_mut, walk_expr_mut,
+        walk_extend_decl_mut, walk_field_decl_mut, walk_func_decl_mut, walk_generic_param_mut,
+        walk_import_decl_mut, walk_let_stmt_mut, walk_match_arm_mut, walk_param_mut,
+        walk_pattern_mut, walk_stmt_mut, walk_struct_decl_mut, walk_type_mut, VisitMut,
+    },
+    Parser,
+};
+
+impl<'source> Parser<'source> {
+    /// Parses `source_code`, which is the result of applying `edit` to the source code of
+    /// `old_module` and `old_errors`, only reparsing the top-level statements touched by the
+    /// edit. The other statements are reused, and the ones after the edit are moved by the size
+    /// difference of the edit.
+    ///
+    /// The result is the same as calling [`Parser::parse_module`] on the edited source code.
+    pub fn reparse(
+        source_code: &'source str,
+        old_module: Module,
+        old_errors: &[ParseError],
+        edit: &TextEdit,
+    ) -> (Module, Vec<ParseError>) {
+        let delta = edit.text.len() as i64 - edit.span.len() as i64;
+        let old_len = old_module.span.end;
+        let mut stmts = old_module.stmts;
+
+        // The statements touched by the edit are reparsed with an untouched statement on each
+        // side, if any. If these are parsed the same as before, the edit can't have changed where
+        // the statements around them start and end. The region starts at the end of the statement
+        // before them, since the doc comments of an item are not part of its span.
+        let touched_start = stmts.partition_point(|stmt| stmt.span().end < edit.span.start);
+        let touched_end = stmts.partition_point(|stmt| stmt.span().start <= edit.span.end);
+        let first_reparsed = touched_start.saturating_sub(1);
+        let last_reparsed = (touched_end + 1).min(stmts.len());
+        let has_stmt_before = touched_start > 0;
+        let has_stmt_after = touched_end < stmts.len();
+
+        let old_region = Span::new(
+            match first_reparsed {
+                0 => 0,
+                _ => stmts[first_reparsed - 1].span().end,
+            },
+            if has_stmt_after {
+                stmts[last_reparsed - 1].span().end
+            } else {
+                old_len
+            },
+        );
+        let region = Span::new(old_region.start, (old_region.end as i64 + delta) as u32);
+        // The errors up to the start of the first reparsed statement, such as an unexpected token
+        // found after an unclosed `(`, belong to the statements before the region
+        let errors_start = match first_reparsed {
+            0 => None,
+            _ => Some(stmts[first_reparsed].span().start),
+        };
+        let (mut reparsed, region_errors) = Self::parse_statements_at(
+            &source_code[region.start as usize..region.end as usize],
+            region.start,
+        );
+
+        let mut shifter = SpanShifter { delta };
+        let mut stmts_after = stmts.split_off(last_reparsed);
+        let mut old_reparsed = stmts.split_off(first_reparsed);
+        if has_stmt_after {
+            // Compared with the reparsed statement, which is after the edit
+            shifter.visit_stmt_mut(old_reparsed.last_mut().unwrap());
+        }
+
+        // An error in the reparsed statements could have been recovered differently with the code
+        // around them, such as an unclosed `{` that contains the statements after it.
+        let is_reused = region_errors.is_empty()
+            && (!has_stmt_before || reparsed.first() == old_reparsed.first())
+            && (!has_stmt_after || reparsed.last() == old_reparsed.last());
+        if !is_reused {
+            return Self::parse_module(source_code);
+        }
+
+        if delta != 0 {
+            for stmt in &mut stmts_after {
+                shifter.visit_stmt_mut(stmt);
+            }
+        }
+        stmts.append(&mut reparsed);
+        stmts.append(&mut stmts_after);
+
+        let is_in_region = |error: &ParseError| {
+            let start = error.span().start;
+            errors_start.is_none_or(|errors_start| start > errors_start)
+                && (start < old_region.end || old_region.end == old_len)
+        };
+        let errors = old_errors
+            .iter()
+            .filter(|error| !is_in_region(error))
+            .map(|error| {
+                if errors_start.is_some_and(|errors_start| error.span().start <= errors_start) {
+                    error.clone()
+                } else {
+                    error.moved_by(delta)
+                }
+            })
+            .collect();
+
+        let module = Module {
+            stmts,
+            span: Span::new(0, source_code.len() as u32),
+        };
+        (module, errors)
+    }
+}
+
+/// Moves every span of the nodes it visits by `delta` bytes.
+struct SpanShifter {
+    delta: i64,
+}
+
+impl SpanShifter {
+    fn shift(&self, span: &mut Span) {
+        *span = moved_span(*span, self.delta);
+    }
+}
+
+impl VisitMut for SpanShifter {
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            // Items and `let` statements are moved by their own methods
+            Stmt::Item(_) | Stmt::Let(_) => {}
+            Stmt::Expr(stmt) => self.shift(&mut stmt.span),
+            Stmt::Error(stmt) => self.shift(&mut stmt.span),
+        }
+        walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_func_decl_mut(&mut self, decl: &mut FuncDecl) {
+        self.shift(&mut decl.span);
+        walk_func_decl_mut(self, decl);
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut Attribute) {
+        self.shift(&mut attribute.span);
+        walk_attribute_mut(self, attribute);
+    }
+
+    fn visit_generic_param_mut(&mut self, param: &mut GenericParam) {
+        self.shift(&mut param.span);
+        walk_generic_param_mut(self, param);
+    }
+
+    fn visit_param_mut(&mut self, param: &mut Param) {
+        self.shift(&mut param.span);
+        walk_param_mut(self, param);
+    }
+
+    fn visit_struct_decl_mut(&mut self, decl: &mut StructDecl) {
+        self.shift(&mut decl.span);
+        walk_struct_decl_mut(self, decl);
+    }
+
+    fn visit_field_decl_mut(&mut self, field: &mut FieldDecl) {
+        self.shift(&mut field.span);
+        walk_field_decl_mut(self, field);
+    }
+
+    fn visit_extend_decl_mut(&mut self, decl: &mut ExtendDecl) {
+        self.shift(&mut decl.span);
+        walk_extend_decl_mut(self, decl);
+    }
+
+    fn visit_import_decl_mut(&mut self, decl: &mut ImportDecl) {
+        self.shift(&mut decl.span);
+        if let ImportKind::Items(items) = &mut decl.kind {
+            for item in items {
+                self.shift(&mut item.span);
+            }
+        }
+        walk_import_decl_mut(self, decl);
+    }
+
+    fn visit_const_decl_mut(&mut self, decl: &mut ConstDecl) {
+        self.shift(&mut decl.span);
+        walk_const_decl_mut(self, decl);
+    }
+
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        match ty {
+            Type::Path(ty) => self.shift(&mut ty.span),
+        }
+        walk_type_mut(self, ty);
+    }
+
+    fn visit_let_stmt_mut(&mut self, stmt: &mut LetStmt) {
+        self.shift(&mut stmt.span);
+        walk_let_stmt_mut(self, stmt);
+    }
+
+    fn visit_block_mut(&mut self, block: &mut BlockExpr) {
+        self.shift(&mut block.span);
+        walk_block_mut(self, block);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Literal(expr) => self.shift(&mut expr.span),
+            Expr::InterpolatedString(expr) => {
+                self.shift(&mut expr.span);
+                for part in &mut expr.parts {
+                    if let StringPart::Fragment { span, .. } = part {
+                        self.shift(span);
+                    }
+                }
+            }
+            Expr::Path(expr) => self.shift(&mut expr.span),
+            Expr::Paren(expr) => self.shift(&mut expr.span),
+            Expr::Tuple(expr) => self.shift(&mut expr.span),
+            Expr::List(expr) => self.shift(&mut expr.span),
+            Expr::Map(expr) => {
+                self.shift(&mut expr.span);
+                for entry in &mut expr.entries {
+                    self.shift(&mut entry.span);
+                }
+            }
+            Expr::Struct(expr) => {
+                self.shift(&mut expr.span);
+                for field in &mut expr.fields {
+                    self.shift(&mut field.span);
+                }
+            }
+            // Blocks are moved by their own method
+            Expr::Block(_) => {}
+            Expr::Call(expr) => self.shift(&mut expr.span),
+            Expr::Field(expr) => self.shift(&mut expr.span),
+            Expr::Index(expr) => self.shift(&mut expr.span),
+            Expr::If(expr) => self.shift(&mut expr.span),
+            Expr::For(expr) => self.shift(&mut expr.span),
+            Expr::While(expr) => self.shift(&mut expr.span),
+            Expr::Match(expr) => self.shift(&mut expr.span),
+            Expr::Closure(expr) => {
+                self.shift(&mut expr.span);
+                for capture in &mut expr.captures {
+                    self.visit_ident_mut(capture);
+                }
+            }
+            Expr::Break(expr) => self.shift(&mut expr.span),
+            Expr::Continue(expr) => self.shift(&mut expr.span),
+            Expr::Return(expr) => self.shift(&mut expr.span),
+            Expr::Unary(expr) => {
+                self.shift(&mut expr.span);
+                self.shift(&mut expr.op_span);
+            }
+            Expr::Binary(expr) => {
+                self.shift(&mut expr.span);
+                self.shift(&mut expr.op_span);
+            }
+            Expr::Assign(expr) => {
+                self.shift(&mut expr.span);
+                self.shift(&mut expr.op_span);
+            }
+            Expr::Missing(expr) => self.shift(&mut expr.span),
+        }
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_match_arm_mut(&mut self, arm: &mut MatchArm) {
+        self.shift(&mut arm.span);
+        walk_match_arm_mut(self, arm);
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        match pattern {
+            // Identifiers are moved by their own method
+            Pattern::Ident(_) => {}
+            Pattern::Wildcard(span) => self.shift(span),
+            Pattern::Literal(pattern) => self.shift(&mut pattern.span),
+            Pattern::Path(pattern) => self.shift(&mut pattern.span),
+            Pattern::Tuple(pattern) => self.shift(&mut pattern.span),
+            Pattern::Struct(pattern) => {
+                self.shift(&mut pattern.span);
+                for field in &mut pattern.fields {
+                    self.shift(&mut field.span);
+                }
+            }
+        }
+        walk_pattern_mut(self, pattern);
+    }
+
+    fn visit_ident_mut(&mut self, ident: &mut Ident) {
+        self.shift(&mut ident.span);
+    }
+}
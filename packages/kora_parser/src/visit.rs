@@ -0,0 +1,758 @@
+//! Traversal of the AST, so that passes such as lints, name resolution and interpreters only
+//! need to handle the nodes they are interested in.
+//!
+//! Every method of [`Visit`] and [`VisitMut`] visits the children of its node by default, by
+//! calling the matching `walk_*` function. A pass overrides the methods of the nodes it handles,
+//! and calls the `walk_*` function itself to keep visiting the children, such as:
+//!
+//! ```
+//! use kora_parser::{ast::Expr, visit::{walk_expr, Visit}, Parser};
+//!
+//! struct CallCounter(usize);
+//!
+//! impl<'ast> Visit<'ast> for CallCounter {
+//!     fn visit_expr(&mut self, expr: &'ast Expr) {
+//!         if let Expr::Call(_) = expr {
+//!             self.0 += 1;
+//!         }
+//!         walk_expr(self, expr);
+//!     }
+//! }
+//!
+//! let (module, _) = Parser::parse_module("print(add(1, 2))");
+//! let mut counter = CallCounter(0);
+//! counter.visit_module(&module);
+//! assert_eq!(counter.0, 2);
+//! ```
+
+use crate::ast::{
+    Attribute, BlockExpr, BreakExpr, ConstDecl, Expr, ExtendDecl, FieldDecl, FuncDecl,
+    GenericParam, Ident, ImportDecl, ImportKind, Item, LetStmt, ListExpr, MatchArm, Module, Param,
+    Pattern, ReturnExpr, Stmt, StringPart, StructDecl, TupleExpr, Type,
+};
+
+/// Visits the nodes of the AST by reference, see the [module documentation](self).
+///
+/// The `'ast` lifetime lets a pass keep references to the nodes it visits.
+pub trait Visit<'ast> {
+    fn visit_module(&mut self, module: &'ast Module) {
+        walk_module(self, module);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_item(&mut self, item: &'ast Item) {
+        walk_item(self, item);
+    }
+
+    fn visit_func_decl(&mut self, decl: &'ast FuncDecl) {
+        walk_func_decl(self, decl);
+    }
+
+    fn visit_attribute(&mut self, attribute: &'ast Attribute) {
+        walk_attribute(self, attribute);
+    }
+
+    fn visit_generic_param(&mut self, param: &'ast GenericParam) {
+        walk_generic_param(self, param);
+    }
+
+    fn visit_param(&mut self, param: &'ast Param) {
+        walk_param(self, param);
+    }
+
+    fn visit_struct_decl(&mut self, decl: &'ast StructDecl) {
+        walk_struct_decl(self, decl);
+    }
+
+    fn visit_field_decl(&mut self, field: &'ast FieldDecl) {
+        walk_field_decl(self, field);
+    }
+
+    fn visit_extend_decl(&mut self, decl: &'ast ExtendDecl) {
+        walk_extend_decl(self, decl);
+    }
+
+    fn visit_import_decl(&mut self, decl: &'ast ImportDecl) {
+        walk_import_decl(self, decl);
+    }
+
+    fn visit_const_decl(&mut self, decl: &'ast ConstDecl) {
+        walk_const_decl(self, decl);
+    }
+
+    fn visit_type(&mut self, ty: &'ast Type) {
+        walk_type(self, ty);
+    }
+
+    fn visit_let_stmt(&mut self, stmt: &'ast LetStmt) {
+        walk_let_stmt(self, stmt);
+    }
+
+    fn visit_block(&mut self, block: &'ast BlockExpr) {
+        walk_block(self, block);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        walk_expr(self, expr);
+    }
+
+    fn visit_match_arm(&mut self, arm: &'ast MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
+    fn visit_pattern(&mut self, pattern: &'ast Pattern) {
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_ident(&mut self, _ident: &'ast Ident) {}
+}
+
+/// Visits the nodes of the AST by mutable reference, so that a pass can rewrite them in place.
+/// See the [module documentation](self).
+pub trait VisitMut {
+    fn visit_module_mut(&mut self, module: &mut Module) {
+        walk_module_mut(self, module);
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_item_mut(&mut self, item: &mut Item) {
+        walk_item_mut(self, item);
+    }
+
+    fn visit_func_decl_mut(&mut self, decl: &mut FuncDecl) {
+        walk_func_decl_mut(self, decl);
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut Attribute) {
+        walk_attribute_mut(self, attribute);
+    }
+
+    fn visit_generic_param_mut(&mut self, param: &mut GenericParam) {
+        walk_generic_param_mut(self, param);
+    }
+
+    fn visit_param_mut(&mut self, param: &mut Param) {
+        walk_param_mut(self, param);
+    }
+
+    fn visit_struct_decl_mut(&mut self, decl: &mut StructDecl) {
+        walk_struct_decl_mut(self, decl);
+    }
+
+    fn visit_field_decl_mut(&mut self, field: &mut FieldDecl) {
+        walk_field_decl_mut(self, field);
+    }
+
+    fn visit_extend_decl_mut(&mut self, decl: &mut ExtendDecl) {
+        walk_extend_decl_mut(self, decl);
+    }
+
+    fn visit_import_decl_mut(&mut self, decl: &mut ImportDecl) {
+        walk_import_decl_mut(self, decl);
+    }
+
+    fn visit_const_decl_mut(&mut self, decl: &mut ConstDecl) {
+        walk_const_decl_mut(self, decl);
+    }
+
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        walk_type_mut(self, ty);
+    }
+
+    fn visit_let_stmt_mut(&mut self, stmt: &mut LetStmt) {
+        walk_let_stmt_mut(self, stmt);
+    }
+
+    fn visit_block_mut(&mut self, block: &mut BlockExpr) {
+        walk_block_mut(self, block);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_match_arm_mut(&mut self, arm: &mut MatchArm) {
+        walk_match_arm_mut(self, arm);
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        walk_pattern_mut(self, pattern);
+    }
+
+    fn visit_ident_mut(&mut self, _ident: &mut Ident) {}
+}
+
+pub fn walk_module<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, module: &'ast Module) {
+    for stmt in &module.stmts {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+pub fn walk_stmt<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, stmt: &'ast Stmt) {
+    match stmt {
+        Stmt::Item(item) => visitor.visit_item(item),
+        Stmt::Let(stmt) => visitor.visit_let_stmt(stmt),
+        Stmt::Expr(stmt) => visitor.visit_expr(&stmt.expr),
+        Stmt::Error(_) => {}
+    }
+}
+
+pub fn walk_item<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, item: &'ast Item) {
+    match item {
+        Item::Func(decl) => visitor.visit_func_decl(decl),
+        Item::Struct(decl) => visitor.visit_struct_decl(decl),
+        Item::Extend(decl) => visitor.visit_extend_decl(decl),
+        Item::Import(decl) => visitor.visit_import_decl(decl),
+        Item::Const(decl) => visitor.visit_const_decl(decl),
+    }
+}
+
+pub fn walk_func_decl<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, decl: &'ast FuncDecl) {
+    for attribute in &decl.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_ident(&decl.name);
+    for param in &decl.generics {
+        visitor.visit_generic_param(param);
+    }
+    for param in &decl.params {
+        visitor.visit_param(param);
+    }
+    if let Some(ty) = &decl.return_type {
+        visitor.visit_type(ty);
+    }
+    visitor.visit_block(&decl.body);
+}
+
+pub fn walk_attribute<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, attribute: &'ast Attribute) {
+    visitor.visit_ident(&attribute.name);
+    for arg in &attribute.args {
+        visitor.visit_expr(arg);
+    }
+}
+
+pub fn walk_generic_param<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    param: &'ast GenericParam,
+) {
+    visitor.visit_ident(&param.name);
+}
+
+pub fn walk_param<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, param: &'ast Param) {
+    visitor.visit_ident(&param.name);
+    if let Some(ty) = &param.ty {
+        visitor.visit_type(ty);
+    }
+}
+
+pub fn walk_struct_decl<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, decl: &'ast StructDecl) {
+    for attribute in &decl.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_ident(&decl.name);
+    for param in &decl.generics {
+        visitor.visit_generic_param(param);
+    }
+    for field in &decl.fields {
+        visitor.visit_field_decl(field);
+    }
+}
+
+pub fn walk_field_decl<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, field: &'ast FieldDecl) {
+    visitor.visit_ident(&field.name);
+    if let Some(ty) = &field.ty {
+        visitor.visit_type(ty);
+    }
+}
+
+pub fn walk_extend_decl<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, decl: &'ast ExtendDecl) {
+    visitor.visit_type(&decl.ty);
+    if let Some(ty) = &decl.with_type {
+        visitor.visit_type(ty);
+    }
+    for method in &decl.methods {
+        visitor.visit_func_decl(method);
+    }
+}
+
+pub fn walk_import_decl<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, decl: &'ast ImportDecl) {
+    for segment in &decl.path {
+        visitor.visit_ident(segment);
+    }
+    match &decl.kind {
+        ImportKind::Module { alias } => {
+            if let Some(alias) = alias {
+                visitor.visit_ident(alias);
+            }
+        }
+        ImportKind::Items(items) => {
+            for item in items {
+                visitor.visit_ident(&item.name);
+                if let Some(alias) = &item.alias {
+                    visitor.visit_ident(alias);
+                }
+            }
+        }
+    }
+}
+
+pub fn walk_const_decl<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, decl: &'ast ConstDecl) {
+    for attribute in &decl.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_ident(&decl.name);
+    if let Some(ty) = &decl.ty {
+        visitor.visit_type(ty);
+    }
+    visitor.visit_expr(&decl.value);
+}
+
+pub fn walk_type<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, ty: &'ast Type) {
+    match ty {
+        Type::Path(ty) => {
+            for segment in &ty.segments {
+                visitor.visit_ident(segment);
+            }
+            for arg in &ty.args {
+                visitor.visit_type(arg);
+            }
+        }
+    }
+}
+
+pub fn walk_let_stmt<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, stmt: &'ast LetStmt) {
+    visitor.visit_pattern(&stmt.pattern);
+    if let Some(ty) = &stmt.ty {
+        visitor.visit_type(ty);
+    }
+    visitor.visit_expr(&stmt.value);
+}
+
+pub fn walk_block<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, block: &'ast BlockExpr) {
+    for stmt in &block.stmts {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+pub fn walk_expr<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, expr: &'ast Expr) {
+    match expr {
+        Expr::Literal(_) | Expr::Continue(_) | Expr::Missing(_) => {}
+        Expr::InterpolatedString(expr) => {
+            for part in &expr.parts {
+                if let StringPart::Expr(expr) = part {
+                    visitor.visit_expr(expr);
+                }
+            }
+        }
+        Expr::Path(expr) => {
+            for segment in &expr.segments {
+                visitor.visit_ident(segment);
+            }
+        }
+        Expr::Paren(expr) => visitor.visit_expr(&expr.expr),
+        Expr::Tuple(TupleExpr { elements, .. }) | Expr::List(ListExpr { elements, .. }) => {
+            for element in elements {
+                visitor.visit_expr(element);
+            }
+        }
+        Expr::Map(expr) => {
+            for entry in &expr.entries {
+                visitor.visit_expr(&entry.key);
+                visitor.visit_expr(&entry.value);
+            }
+        }
+        Expr::Struct(expr) => {
+            for segment in &expr.path {
+                visitor.visit_ident(segment);
+            }
+            for field in &expr.fields {
+                visitor.visit_ident(&field.name);
+                if let Some(value) = &field.value {
+                    visitor.visit_expr(value);
+                }
+            }
+        }
+        Expr::Block(block) => visitor.visit_block(block),
+        Expr::Call(expr) => {
+            visitor.visit_expr(&expr.callee);
+            for arg in &expr.args {
+                visitor.visit_expr(arg);
+            }
+        }
+        Expr::Field(expr) => {
+            visitor.visit_expr(&expr.expr);
+            visitor.visit_ident(&expr.field);
+        }
+        Expr::Index(expr) => {
+            visitor.visit_expr(&expr.expr);
+            visitor.visit_expr(&expr.index);
+        }
+        Expr::If(expr) => {
+            visitor.visit_expr(&expr.condition);
+            visitor.visit_block(&expr.then_branch);
+            if let Some(else_branch) = &expr.else_branch {
+                visitor.visit_expr(else_branch);
+            }
+        }
+        Expr::For(expr) => {
+            visitor.visit_pattern(&expr.pattern);
+            visitor.visit_expr(&expr.iterable);
+            visitor.visit_block(&expr.body);
+        }
+        Expr::While(expr) => {
+            visitor.visit_expr(&expr.condition);
+            visitor.visit_block(&expr.body);
+        }
+        Expr::Match(expr) => {
+            visitor.visit_expr(&expr.scrutinee);
+            for arm in &expr.arms {
+                visitor.visit_match_arm(arm);
+            }
+        }
+        Expr::Closure(expr) => {
+            for param in &expr.params {
+                visitor.visit_param(param);
+            }
+            if let Some(ty) = &expr.return_type {
+                visitor.visit_type(ty);
+            }
+            visitor.visit_expr(&expr.body);
+        }
+        Expr::Break(BreakExpr { value, .. }) | Expr::Return(ReturnExpr { value, .. }) => {
+            if let Some(value) = value {
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::Unary(expr) => visitor.visit_expr(&expr.expr),
+        Expr::Binary(expr) => {
+            visitor.visit_expr(&expr.lhs);
+            visitor.visit_expr(&expr.rhs);
+        }
+        Expr::Assign(expr) => {
+            visitor.visit_expr(&expr.target);
+            visitor.visit_expr(&expr.value);
+        }
+    }
+}
+
+pub fn walk_match_arm<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, arm: &'ast MatchArm) {
+    visitor.visit_pattern(&arm.pattern);
+    visitor.visit_expr(&arm.body);
+}
+
+pub fn walk_pattern<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, pattern: &'ast Pattern) {
+    match pattern {
+        Pattern::Ident(name) => visitor.visit_ident(name),
+        Pattern::Wildcard(_) | Pattern::Literal(_) => {}
+        Pattern::Path(path) => {
+            for segment in &path.segments {
+                visitor.visit_ident(segment);
+            }
+        }
+        Pattern::Tuple(pattern) => {
+            for element in &pattern.elements {
+                visitor.visit_pattern(element);
+            }
+        }
+        Pattern::Struct(pattern) => {
+            for segment in &pattern.path {
+                visitor.visit_ident(segment);
+            }
+            for field in &pattern.fields {
+                visitor.visit_ident(&field.name);
+                if let Some(pattern) = &field.pattern {
+                    visitor.visit_pattern(pattern);
+                }
+            }
+        }
+    }
+}
+
+pub fn walk_module_mut<V: VisitMut + ?Sized>(visitor: &mut V, module: &mut Module) {
+    for stmt in &mut module.stmts {
+        visitor.visit_stmt_mut(stmt);
+    }
+}
+
+pub fn walk_stmt_mut<V: VisitMut + ?Sized>(visitor: &mut V, stmt: &mut Stmt) {
+    match stmt {
+        Stmt::Item(item) => visitor.visit_item_mut(item),
+        Stmt::Let(stmt) => visitor.visit_let_stmt_mut(stmt),
+        Stmt::Expr(stmt) => visitor.visit_expr_mut(&mut stmt.expr),
+        Stmt::Error(_) => {}
+    }
+}
+
+pub fn walk_item_mut<V: VisitMut + ?Sized>(visitor: &mut V, item: &mut Item) {
+    match item {
+        Item::Func(decl) => visitor.visit_func_decl_mut(decl),
+        Item::Struct(decl) => visitor.visit_struct_decl_mut(decl),
+        Item::Extend(decl) => visitor.visit_extend_decl_mut(decl),
+        Item::Import(decl) => visitor.visit_import_decl_mut(decl),
+        Item::Const(decl) => visitor.visit_const_decl_mut(decl),
+    }
+}
+
+pub fn walk_func_decl_mut<V: VisitMut + ?Sized>(visitor: &mut V, decl: &mut FuncDecl) {
+    for attribute in &mut decl.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    visitor.visit_ident_mut(&mut decl.name);
+    for param in &mut decl.generics {
+        visitor.visit_generic_param_mut(param);
+    }
+    for param in &mut decl.params {
+        visitor.visit_param_mut(param);
+    }
+    if let Some(ty) = &mut decl.return_type {
+        visitor.visit_type_mut(ty);
+    }
+    visitor.visit_block_mut(&mut decl.body);
+}
+
+pub fn walk_attribute_mut<V: VisitMut + ?Sized>(visitor: &mut V, attribute: &mut Attribute) {
+    visitor.visit_ident_mut(&mut attribute.name);
+    for arg in &mut attribute.args {
+        visitor.visit_expr_mut(arg);
+    }
+}
+
+pub fn walk_generic_param_mut<V: VisitMut + ?Sized>(visitor: &mut V, param: &mut GenericParam) {
+    visitor.visit_ident_mut(&mut param.name);
+}
+
+pub fn walk_param_mut<V: VisitMut + ?Sized>(visitor: &mut V, param: &mut Param) {
+    visitor.visit_ident_mut(&mut param.name);
+    if let Some(ty) = &mut param.ty {
+        visitor.visit_type_mut(ty);
+    }
+}
+
+pub fn walk_struct_decl_mut<V: VisitMut + ?Sized>(visitor: &mut V, decl: &mut StructDecl) {
+    for attribute in &mut decl.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    visitor.visit_ident_mut(&mut decl.name);
+    for param in &mut decl.generics {
+        visitor.visit_generic_param_mut(param);
+    }
+    for field in &mut decl.fields {
+        visitor.visit_field_decl_mut(field);
+    }
+}
+
+pub fn walk_field_decl_mut<V: VisitMut + ?Sized>(visitor: &mut V, field: &mut FieldDecl) {
+    visitor.visit_ident_mut(&mut field.name);
+    if let Some(ty) = &mut field.ty {
+        visitor.visit_type_mut(ty);
+    }
+}
+
+pub fn walk_extend_decl_mut<V: VisitMut + ?Sized>(visitor: &mut V, decl: &mut ExtendDecl) {
+    visitor.visit_type_mut(&mut decl.ty);
+    if let Some(ty) = &mut decl.with_type {
+        visitor.visit_type_mut(ty);
+    }
+    for method in &mut decl.methods {
+        visitor.visit_func_decl_mut(method);
+    }
+}
+
+pub fn walk_import_decl_mut<V: VisitMut + ?Sized>(visitor: &mut V, decl: &mut ImportDecl) {
+    for segment in &mut decl.path {
+        visitor.visit_ident_mut(segment);
+    }
+    match &mut decl.kind {
+        ImportKind::Module { alias } => {
+            if let Some(alias) = alias {
+                visitor.visit_ident_mut(alias);
+            }
+        }
+        ImportKind::Items(items) => {
+            for item in items {
+                visitor.visit_ident_mut(&mut item.name);
+                if let Some(alias) = &mut item.alias {
+                    visitor.visit_ident_mut(alias);
+                }
+            }
+        }
+    }
+}
+
+pub fn walk_const_decl_mut<V: VisitMut + ?Sized>(visitor: &mut V, decl: &mut ConstDecl) {
+    for attribute in &mut decl.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    visitor.visit_ident_mut(&mut decl.name);
+    if let Some(ty) = &mut decl.ty {
+        visitor.visit_type_mut(ty);
+    }
+    visitor.visit_expr_mut(&mut decl.value);
+}
+
+pub fn walk_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, ty: &mut Type) {
+    match ty {
+        Type::Path(ty) => {
+            for segment in &mut ty.segments {
+                visitor.visit_ident_mut(segment);
+            }
+            for arg in &mut ty.args {
+                visitor.visit_type_mut(arg);
+            }
+        }
+    }
+}
+
+pub fn walk_let_stmt_mut<V: VisitMut + ?Sized>(visitor: &mut V, stmt: &mut LetStmt) {
+    visitor.visit_pattern_mut(&mut stmt.pattern);
+    if let Some(ty) = &mut stmt.ty {
+        visitor.visit_type_mut(ty);
+    }
+    visitor.visit_expr_mut(&mut stmt.value);
+}
+
+pub fn walk_block_mut<V: VisitMut + ?Sized>(visitor: &mut V, block: &mut BlockExpr) {
+    for stmt in &mut block.stmts {
+        visitor.visit_stmt_mut(stmt);
+    }
+}
+
+pub fn walk_expr_mut<V: VisitMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Literal(_) | Expr::Continue(_) | Expr::Missing(_) => {}
+        Expr::InterpolatedString(expr) => {
+            for part in &mut expr.parts {
+                if let StringPart::Expr(expr) = part {
+                    visitor.visit_expr_mut(expr);
+                }
+            }
+        }
+        Expr::Path(expr) => {
+            for segment in &mut expr.segments {
+                visitor.visit_ident_mut(segment);
+            }
+        }
+        Expr::Paren(expr) => visitor.visit_expr_mut(&mut expr.expr),
+        Expr::Tuple(TupleExpr { elements, .. }) | Expr::List(ListExpr { elements, .. }) => {
+            for element in elements {
+                visitor.visit_expr_mut(element);
+            }
+        }
+        Expr::Map(expr) => {
+            for entry in &mut expr.entries {
+                visitor.visit_expr_mut(&mut entry.key);
+                visitor.visit_expr_mut(&mut entry.value);
+            }
+        }
+        Expr::Struct(expr) => {
+            for segment in &mut expr.path {
+                visitor.visit_ident_mut(segment);
+            }
+            for field in &mut expr.fields {
+                visitor.visit_ident_mut(&mut field.name);
+                if let Some(value) = &mut field.value {
+                    visitor.visit_expr_mut(value);
+                }
+            }
+        }
+        Expr::Block(block) => visitor.visit_block_mut(block),
+        Expr::Call(expr) => {
+            visitor.visit_expr_mut(&mut expr.callee);
+            for arg in &mut expr.args {
+                visitor.visit_expr_mut(arg);
+            }
+        }
+        Expr::Field(expr) => {
+            visitor.visit_expr_mut(&mut expr.expr);
+            visitor.visit_ident_mut(&mut expr.field);
+        }
+        Expr::Index(expr) => {
+            visitor.visit_expr_mut(&mut expr.expr);
+            visitor.visit_expr_mut(&mut expr.index);
+        }
+        Expr::If(expr) => {
+            visitor.visit_expr_mut(&mut expr.condition);
+            visitor.visit_block_mut(&mut expr.then_branch);
+            if let Some(else_branch) = &mut expr.else_branch {
+                visitor.visit_expr_mut(else_branch);
+            }
+        }
+        Expr::For(expr) => {
+            visitor.visit_pattern_mut(&mut expr.pattern);
+            visitor.visit_expr_mut(&mut expr.iterable);
+            visitor.visit_block_mut(&mut expr.body);
+        }
+        Expr::While(expr) => {
+            visitor.visit_expr_mut(&mut expr.condition);
+            visitor.visit_block_mut(&mut expr.body);
+        }
+        Expr::Match(expr) => {
+            visitor.visit_expr_mut(&mut expr.scrutinee);
+            for arm in &mut expr.arms {
+                visitor.visit_match_arm_mut(arm);
+            }
+        }
+        Expr::Closure(expr) => {
+            for param in &mut expr.params {
+                visitor.visit_param_mut(param);
+            }
+            if let Some(ty) = &mut expr.return_type {
+                visitor.visit_type_mut(ty);
+            }
+            visitor.visit_expr_mut(&mut expr.body);
+        }
+        Expr::Break(BreakExpr { value, .. }) | Expr::Return(ReturnExpr { value, .. }) => {
+            if let Some(value) = value {
+                visitor.visit_expr_mut(value);
+            }
+        }
+        Expr::Unary(expr) => visitor.visit_expr_mut(&mut expr.expr),
+        Expr::Binary(expr) => {
+            visitor.visit_expr_mut(&mut expr.lhs);
+            visitor.visit_expr_mut(&mut expr.rhs);
+        }
+        Expr::Assign(expr) => {
+            visitor.visit_expr_mut(&mut expr.target);
+            visitor.visit_expr_mut(&mut expr.value);
+        }
+    }
+}
+
+pub fn walk_match_arm_mut<V: VisitMut + ?Sized>(visitor: &mut V, arm: &mut MatchArm) {
+    visitor.visit_pattern_mut(&mut arm.pattern);
+    visitor.visit_expr_mut(&mut arm.body);
+}
+
+pub fn walk_pattern_mut<V: VisitMut + ?Sized>(visitor: &mut V, pattern: &mut Pattern) {
+    match pattern {
+        Pattern::Ident(name) => visitor.visit_ident_mut(name),
+        Pattern::Wildcard(_) | Pattern::Literal(_) => {}
+        Pattern::Path(path) => {
+            for segment in &mut path.segments {
+                visitor.visit_ident_mut(segment);
+            }
+        }
+        Pattern::Tuple(pattern) => {
+            for element in &mut pattern.elements {
+                visitor.visit_pattern_mut(element);
+            }
+        }
+        Pattern::Struct(pattern) => {
+            for segment in &mut pattern.path {
+                visitor.visit_ident_mut(segment);
+            }
+            for field in &mut pattern.fields {
+                visitor.visit_ident_mut(&mut field.name);
+                if let Some(pattern) = &mut field.pattern {
+                    visitor.visit_pattern_mut(pattern);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use kora_lexer::Span;
+use kora_parser::{
+    ast::{
+        BinaryOp, BlockExpr, Expr, FieldDecl, FuncDecl, LiteralExpr, MatchArm, Module, Param,
+        Pattern, Stmt, StructDecl, Type, UnaryOp,
+    },
+    Parser,
+};
+
+/// Asserts that the module is printed as `expected`, and that parsing the printed code gives a
+/// module that is printed the same.
+#[track_caller]
+fn assert_builds(module: Module, expected: &str) {
+    let printed = module.to_source();
+    assert_eq!(printed, expected);
+    let (parsed, errors) = Parser::parse_module(&printed);
+    assert_eq!(errors, []);
+    assert_eq!(parsed.to_source(), printed);
+}
+
+#[test]
+fn test_build_expressions() {
+    let expr = Expr::call(Expr::ident("f"), [Expr::int(1)]);
+    assert_eq!(expr.to_source(), "f(1)");
+    assert_eq!(expr.span(), Span::default());
+
+    let expr = Expr::ident("a")
+        .binary(BinaryOp::Add, Expr::ident("b"))
+        .binary(
+            BinaryOp::Multiply,
+            Expr::unary(UnaryOp::Negate, Expr::float(2.0)),
+        );
+    assert_eq!(expr.to_source(), "(a + b) * -2.0");
+
+    let expr = Expr::path("std::math::max")
+        .call([Expr::list([Expr::int(1), Expr::int(2)])])
+        .field("value")
+        .index(Expr::string("key"));
+    assert_eq!(expr.to_source(), r#"std::math::max([1, 2]).value["key"]"#);
+
+    let expr =
+        Expr::closure([Param::new("x")], Expr::ident("x")).call([Expr::tuple([Expr::bool(true)])]);
+    assert_eq!(expr.to_source(), "(fn(x) x)((true,))");
+
+    let expr = Expr::struct_(
+        "geometry::Point",
+        [("x", Expr::int(1)), ("y", Expr::int(2))],
+    );
+    assert_eq!(expr.to_source(), "geometry::Point { x: 1, y: 2 }");
+}
+
+#[test]
+fn test_build_struct_before_block() {
+    let condition = Expr::ident("p").binary(
+        BinaryOp::Equal,
+        Expr::struct_("Point", [("x", Expr::int(0))]),
+    );
+    let module = Module::new([
+        Stmt::expr(Expr::if_then(condition, BlockExpr::new([]))),
+        Stmt::expr(Expr::for_in(
+            Pattern::ident("x"),
+            Expr::struct_("Range", [("end", Expr::int(3))]).field("items"),
+            BlockExpr::new([]),
+        )),
+        Stmt::expr(Expr::while_loop(
+            Expr::list([Expr::struct_::<&str>("Unit", [])]).index(Expr::int(0)),
+            BlockExpr::new([]),
+        )),
+    ]);
+    assert_builds(
+        module,
+        "if (p == Point { x: 0 }) {}\nfor x in (Range { end: 3 }.items) {}\nwhile [Unit {}][0] {}\n",
+    );
+}
+
+#[test]
+fn test_build_module() {
+    let point = StructDecl::new(
+        "Point",
+        [
+            FieldDecl::new("x", Type::named("Int")).public(),
+            FieldDecl::new("y", Type::named("Int")),
+        ],
+    )
+    .public();
+    let sum = FuncDecl::new(
+        "sum",
+        [Param::typed(
+            "xs",
+            Type::generic("List", [Type::named("Int")]),
+        )],
+        BlockExpr::new([
+            Stmt::let_mut(Pattern::ident("total"), Expr::int(0)),
+            Stmt::expr(Expr::for_in(
+                Pattern::ident("x"),
+                Expr::ident("xs"),
+                BlockExpr::new([Stmt::expr(
+                    Expr::ident("total").compound_assign(BinaryOp::Add, Expr::ident("x")),
+                )]),
+            )),
+            Stmt::expr(Expr::ident("total").match_arms([
+                MatchArm::new(LiteralExpr::int(0).into(), Expr::none()),
+                MatchArm::new(Pattern::wildcard(), Expr::ident("total")),
+            ])),
+        ]),
+    )
+    .returns(Type::named("Int"));
+
+    assert_builds(
+        Module::new([point.into(), sum.into()]),
+        "\
+pub struct Point {
+    pub x: Int,
+    y: Int,
+}
+def sum(xs: List[Int]) -> Int {
+    let mut total = 0
+    for x in xs {
+        total += x
+    }
+    match total {
+        0 => none,
+        _ => total,
+    }
+}
+",
+    );
+}
+
+#[test]
+fn test_build_literals() {
+    let module = Module::new([
+        Stmt::semi(Expr::string("a \"quoted\"\nline\\")),
+        Stmt::semi(Expr::char('\'')),
+        Stmt::semi(Expr::float(1e100)),
+        Stmt::semi(Expr::float(0.5)),
+    ]);
+    assert_builds(
+        module,
+        "\"a \\\"quoted\\\"\\nline\\\\\";\n'\\'';\n1e100;\n0.5;\n",
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_build_infinite_float() {
+    Expr::float(f64::INFINITY);
+}
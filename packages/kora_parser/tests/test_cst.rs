@@ -0,0 +1,93 @@
+use std::ops::Range;
+
+use kora_parser::{
+    cst::{NodeKind, SyntaxElement, SyntaxNode},
+    Parser,
+};
+
+#[test]
+fn test_syntax_tree_is_lossless() {
+    insta::glob!("inputs/*.kora", |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+
+        let (_, syntax, _) = Parser::parse_module_with_syntax(&input);
+
+        assert_eq!(syntax.kind(), NodeKind::Module);
+        assert_eq!(syntax.text(), input);
+        assert_eq!(Range::from(syntax.span()), 0..input.len());
+    });
+}
+
+#[test]
+fn test_syntax_tree() {
+    let source_code = "// Adds them\ndef add(a, b) {\n    a + b * 2 // result\n}\n";
+
+    let (_, syntax, errors) = Parser::parse_module_with_syntax(source_code);
+
+    assert_eq!(errors, []);
+    insta::assert_debug_snapshot!(syntax);
+}
+
+#[test]
+fn test_syntax_tree_matches_ast_spans() {
+    let source_code = "print(a) ;  f(-b)\n  c = d ** e";
+
+    let (module, syntax, _) = Parser::parse_module_with_syntax(source_code);
+
+    let stmt_spans = syntax
+        .child_nodes()
+        .map(|node| node.span())
+        .collect::<Vec<_>>();
+    let ast_stmt_spans = module
+        .stmts
+        .iter()
+        .map(|stmt| stmt.span())
+        .collect::<Vec<_>>();
+    assert_eq!(stmt_spans, ast_stmt_spans);
+}
+
+#[test]
+fn test_syntax_tree_keeps_unparsed_tokens() {
+    let source_code = "a b c\n// comment";
+
+    let (_, syntax, errors) = Parser::parse_module_with_syntax(source_code);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(syntax.text(), source_code);
+    let error_node = syntax
+        .child_nodes()
+        .find(|node| node.kind() == NodeKind::Error)
+        .unwrap();
+    assert_eq!(error_node.text(), "a b c");
+
+    let token = syntax.tokens().into_iter().last().unwrap();
+    assert_eq!(token.text(), "// comment");
+    assert_eq!(token.parent(), &syntax);
+    assert!(syntax
+        .children()
+        .any(|child| child == SyntaxElement::Token(token.clone())));
+}
+
+/// Returns the nodes of the given kind in the tree, in order.
+fn find_nodes(node: &SyntaxNode, kind: NodeKind) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    if node.kind() == kind {
+        spans.push(node.span().into());
+    }
+    for child in node.child_nodes() {
+        spans.extend(find_nodes(&child, kind));
+    }
+    spans
+}
+
+#[test]
+fn test_syntax_tree_has_missing_nodes() {
+    let source_code = "f(a, b\nc = 1 +";
+
+    let (_, syntax, errors) = Parser::parse_module_with_syntax(source_code);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(syntax.text(), source_code);
+    assert_eq!(find_nodes(&syntax, NodeKind::Missing), [6..6, 14..14]);
+    assert_eq!(find_nodes(&syntax, NodeKind::ArgList), vec![1..6]);
+}
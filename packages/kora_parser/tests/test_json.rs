@@ -0,0 +1,23 @@
+#![cfg(feature = "serde")]
+
+use kora_parser::{ast::Module, to_json, Parser};
+
+#[test]
+fn test_to_json() {
+    let (module, errors) = Parser::parse_module("a + 1");
+    assert!(errors.is_empty());
+
+    let json = to_json(&module);
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"stmts":[{"Expr":{"expr":{"Binary":{"#,
+            r#""op":"Add","op_span":{"start":2,"end":3},"#,
+            r#""lhs":{"Path":{"segments":[{"name":"a","span":{"start":0,"end":1}}],"span":{"start":0,"end":1}}},"#,
+            r#""rhs":{"Literal":{"kind":"Integer","text":"1","span":{"start":4,"end":5}}},"#,
+            r#""span":{"start":0,"end":5}"#,
+            r#"}},"has_semicolon":false,"span":{"start":0,"end":5}}}],"span":{"start":0,"end":5}}"#,
+        )
+    );
+    assert_eq!(serde_json::from_str::<Module>(&json).unwrap(), module);
+}
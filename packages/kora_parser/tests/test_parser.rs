@@ -0,0 +1,392 @@
+use std::ops::Range;
+
+use kora_diagnostics::{Applicability, Diagnostic, Suggestion};
+use kora_lexer::TokenKind;
+use kora_parser::{
+    ast::{Expr, IfExpr, Module, Stmt, StringPart},
+    ParseError, Parser,
+};
+
+#[test]
+fn test_parser() {
+    insta::glob!("inputs/*.kora", |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+
+        let (module, errors) = Parser::parse_module(&input);
+
+        insta::with_settings!({
+            description => &input,
+            omit_expression => true,
+        }, {
+            insta::assert_debug_snapshot!(module);
+            insta::assert_debug_snapshot!("errors", errors);
+        });
+    })
+}
+
+/// Prints an expression with every operation in parentheses, to show how it was grouped.
+fn parenthesize(expr: &Expr) -> String {
+    match expr {
+        Expr::Literal(literal) => literal.text.clone(),
+        Expr::Path(path) => path
+            .segments
+            .iter()
+            .map(|segment| segment.name.as_str())
+            .collect::<Vec<_>>()
+            .join("::"),
+        Expr::Paren(paren) => parenthesize(&paren.expr),
+        Expr::Unary(unary) => format!("({:?} {})", unary.op, parenthesize(&unary.expr)),
+        Expr::Binary(binary) => format!(
+            "({} {:?} {})",
+            parenthesize(&binary.lhs),
+            binary.op,
+            parenthesize(&binary.rhs)
+        ),
+        Expr::Assign(assign) => format!(
+            "({} Assign{} {})",
+            parenthesize(&assign.target),
+            assign.op.map_or(String::new(), |op| format!("{op:?}")),
+            parenthesize(&assign.value)
+        ),
+        Expr::Field(field) => format!("{}.{}", parenthesize(&field.expr), field.field.name),
+        Expr::Index(index) => format!(
+            "{}[{}]",
+            parenthesize(&index.expr),
+            parenthesize(&index.index)
+        ),
+        Expr::Call(call) => format!(
+            "{}({})",
+            parenthesize(&call.callee),
+            call.args
+                .iter()
+                .map(parenthesize)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Expr::Tuple(tuple) => format!(
+            "Tuple({})",
+            tuple
+                .elements
+                .iter()
+                .map(parenthesize)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Expr::List(list) => format!(
+            "[{}]",
+            list.elements
+                .iter()
+                .map(parenthesize)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Expr::Closure(closure) => format!(
+            "(fn({}) {})",
+            closure
+                .params
+                .iter()
+                .map(|param| param.name.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            parenthesize(&closure.body)
+        ),
+        Expr::Return(expr) => match &expr.value {
+            Some(value) => format!("(return {})", parenthesize(value)),
+            None => "return".to_string(),
+        },
+        Expr::Break(expr) => match &expr.value {
+            Some(value) => format!("(break {})", parenthesize(value)),
+            None => "break".to_string(),
+        },
+        Expr::Missing(_) => "<missing>".to_string(),
+        _ => format!("{expr:?}"),
+    }
+}
+
+/// Prints how each statement of the module was grouped, one per line next to its source code,
+/// followed by the errors.
+fn grouping(source_code: &str, module: &Module, errors: &[ParseError]) -> String {
+    let mut lines = module
+        .stmts
+        .iter()
+        .map(|stmt| {
+            let grouped = match stmt {
+                Stmt::Expr(stmt) => {
+                    let semicolon = if stmt.has_semicolon { ";" } else { "" };
+                    format!("{}{semicolon}", parenthesize(&stmt.expr))
+                }
+                Stmt::Let(stmt) => format!(
+                    "let {} = {}",
+                    &source_code[Range::from(stmt.pattern.span())],
+                    parenthesize(&stmt.value)
+                ),
+                Stmt::Item(_) => "<item>".to_string(),
+                Stmt::Error(_) => "<error>".to_string(),
+            };
+            let code = source_code[Range::from(stmt.span())]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{code:<24} => {grouped}")
+        })
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        lines.push(String::new());
+    }
+    lines.extend(
+        errors
+            .iter()
+            .map(|error| format!("{:?}: {error}", error.span())),
+    );
+    lines.join("\n")
+}
+
+/// Snapshots of how expressions are grouped, which are smaller to review than the whole AST when
+/// the grammar changes.
+#[test]
+fn test_grouping() {
+    insta::glob!("inputs_parser/*.kora", |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+
+        let (module, errors) = Parser::parse_module(&input);
+
+        insta::with_settings!({
+            description => &input,
+            omit_expression => true,
+        }, {
+            insta::assert_snapshot!(grouping(&input, &module, &errors));
+        });
+    })
+}
+
+#[test]
+fn test_operator_precedence() {
+    let expressions = [
+        "a = b = c",
+        "a += b * c",
+        "a <<= b || c",
+        "a || b && c",
+        "a && b == c",
+        "a == b < c",
+        "a < b | c",
+        "a | b ^ c",
+        "a ^ b & c",
+        "a & b << c",
+        "a >> b + c",
+        "a + b * c",
+        "a - b - c",
+        "a / b % c",
+        "-a * b",
+        "-a ** b",
+        "a ** b ** c",
+        "a ** -b",
+        "!a && !b",
+        "!-a",
+        "-f(a) + (b + c) * d",
+        "a == b != c",
+        "a..b + c",
+        "a || b..=c",
+        "-a.b(c).d",
+        "a.b.c = d.e ** 2",
+        "-a[b + c][d].e()[f]",
+        "a[b] += c[d]",
+        "a + b |> f |> g(c)",
+        "a = b..c |> f",
+        "a |> b(c)(d)",
+        "a |> (b(c))",
+    ];
+
+    let lines = expressions
+        .iter()
+        .map(|source_code| {
+            let (module, errors) = Parser::parse_module(source_code);
+            assert_eq!(errors, [], "{source_code}");
+            let Stmt::Expr(stmt) = &module.stmts[0] else {
+                panic!("expected an expression statement");
+            };
+            format!("{source_code:<20} => {}", parenthesize(&stmt.expr))
+        })
+        .collect::<Vec<_>>();
+    insta::assert_snapshot!(lines.join("\n"));
+}
+
+#[test]
+fn test_invalid_assignment_target() {
+    let (_, errors) = Parser::parse_module("a + b = c");
+
+    assert_eq!(
+        errors,
+        [ParseError::InvalidAssignmentTarget {
+            span: (0..5).into()
+        }]
+    );
+    assert_eq!(errors[0].to_string(), "invalid left side of assignment");
+}
+
+/// Returns the `if` expression of the first statement of the source code.
+fn parse_if_expression(source_code: &str) -> IfExpr {
+    let (module, errors) = Parser::parse_module(source_code);
+    assert_eq!(errors, [], "{source_code}");
+    match &module.stmts[0] {
+        Stmt::Expr(stmt) => match &stmt.expr {
+            Expr::If(expr) => expr.clone(),
+            expr => panic!("expected an if expression, found {expr:?}"),
+        },
+        stmt => panic!("expected an expression statement, found {stmt:?}"),
+    }
+}
+
+#[test]
+fn test_else_belongs_to_nearest_if() {
+    let outer = parse_if_expression("if a { if b { c } else { d } }");
+
+    assert_eq!(outer.else_branch, None);
+    let [Stmt::Expr(stmt)] = outer.then_branch.stmts.as_slice() else {
+        panic!("expected a single statement in the outer if");
+    };
+    let Expr::If(inner) = &stmt.expr else {
+        panic!("expected an inner if expression");
+    };
+    assert!(matches!(inner.else_branch.as_deref(), Some(Expr::Block(_))));
+}
+
+#[test]
+fn test_else_if_chain() {
+    let expr = parse_if_expression("if a { 1 } else if b { 2 } else { 3 }");
+
+    let Some(Expr::If(else_if)) = expr.else_branch.as_deref() else {
+        panic!("expected an else if branch");
+    };
+    assert_eq!(else_if.span, (16..37).into());
+    assert!(matches!(
+        else_if.else_branch.as_deref(),
+        Some(Expr::Block(_))
+    ));
+}
+
+/// The arena mode parses the same code as the default one, only the child expressions are stored
+/// elsewhere.
+#[test]
+fn test_parse_module_in_arena() {
+    insta::glob!("inputs/*.kora", |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+
+        let (module, errors) = Parser::parse_module(&input);
+        let (arena_module, _, arena_errors) = Parser::parse_module_in_arena(&input);
+
+        assert_eq!(arena_errors, errors);
+        assert_eq!(arena_module.span, module.span);
+        assert_eq!(
+            arena_module
+                .stmts
+                .iter()
+                .map(Stmt::span)
+                .collect::<Vec<_>>(),
+            module.stmts.iter().map(Stmt::span).collect::<Vec<_>>(),
+        );
+    });
+
+    // The expressions embedded in strings are stored in the same arena
+    let (module, arena, errors) = Parser::parse_module_in_arena("f\"{a + -b}\"");
+    assert_eq!(errors, []);
+    let Stmt::Expr(stmt) = &module.stmts[0] else {
+        panic!("expected an expression statement");
+    };
+    let Expr::InterpolatedString(string) = &stmt.expr else {
+        panic!("expected an interpolated string");
+    };
+    let [StringPart::Expr(Expr::Binary(binary))] = string.parts.as_slice() else {
+        panic!("expected a binary expression in the string");
+    };
+    assert_eq!(arena[binary.lhs].span(), (3..4).into());
+    let Expr::Unary(unary) = &arena[binary.rhs] else {
+        panic!("expected a unary expression");
+    };
+    assert!(matches!(&arena[unary.expr], Expr::Path(path) if path.span == (8..9).into()));
+    assert_eq!(arena.len(), 3);
+}
+
+#[test]
+fn test_parse_expr() {
+    let (expr, errors) = Parser::parse_expr("  a +\n  f(b)\n");
+    assert_eq!(errors, []);
+    assert_eq!(parenthesize(&expr.unwrap()), "(a Add f(b))");
+
+    let (expr, errors) = Parser::parse_expr("if a { b\n c } else { d }");
+    assert_eq!(errors, []);
+    assert!(matches!(expr, Some(Expr::If(_))));
+}
+
+#[test]
+fn test_parse_expr_errors() {
+    let (expr, errors) = Parser::parse_expr("a b");
+    assert_eq!(expr, None);
+    assert_eq!(
+        errors,
+        [ParseError::ExpectedToken {
+            span: (2..3).into(),
+            expected: TokenKind::Eof,
+            found: TokenKind::Identifier,
+        }]
+    );
+
+    let (expr, errors) = Parser::parse_expr("");
+    assert_eq!(expr, None);
+    assert_eq!(
+        errors,
+        [ParseError::ExpectedExpression {
+            span: (0..0).into(),
+            found: TokenKind::Eof,
+        }]
+    );
+
+    // Syntax errors of the lexer don't prevent the expression from being parsed
+    let (expr, errors) = Parser::parse_expr("a + `b");
+    assert!(expr.is_some());
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_errors_to_diagnostics() {
+    let (_, errors) = Parser::parse_module("let a = \"b\nf(a");
+    let diagnostics = errors.iter().map(Diagnostic::from).collect::<Vec<_>>();
+
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>(),
+        [
+            "error[E0001]: unterminated string literal",
+            "error[E0100]: expected `)`, found the end of the file",
+        ]
+    );
+    assert_eq!(diagnostics[0].span(), (8..10).into());
+    assert_eq!(
+        diagnostics[1].primary.message.as_deref(),
+        Some("expected `)`")
+    );
+}
+
+#[test]
+fn test_assignment_in_condition() {
+    let (module, errors) = Parser::parse_module("if a = b { c }\nwhile a += 1 { }");
+
+    assert!(matches!(&module.stmts[0], Stmt::Expr(stmt) if matches!(stmt.expr, Expr::If(_))));
+    assert_eq!(
+        errors,
+        [ParseError::AssignmentInCondition {
+            span: (5..6).into()
+        }]
+    );
+    let diagnostic = Diagnostic::from(&errors[0]);
+    assert_eq!(
+        diagnostic.suggestions,
+        [Suggestion::new(
+            "did you mean `==`?",
+            (5..6).into(),
+            "==",
+            Applicability::MaybeIncorrect
+        )]
+    );
+}
@@ -0,0 +1,28 @@
+use kora_parser::{
+    ast::BinaryOp,
+    precedence::{infix_operator, Associativity, INFIX_OPERATORS, PREFIX_PRECEDENCE},
+};
+
+#[test]
+fn test_every_operator_is_found_by_its_token_and_by_itself() {
+    for info in &INFIX_OPERATORS {
+        assert_eq!(infix_operator(info.token), Some(info));
+        assert_eq!(info.operator.info(), info);
+    }
+}
+
+#[test]
+fn test_operators_are_sorted_from_loosest_to_tightest() {
+    assert!(INFIX_OPERATORS
+        .windows(2)
+        .all(|pair| pair[0].precedence <= pair[1].precedence));
+}
+
+#[test]
+fn test_binary_op_precedence() {
+    assert!(BinaryOp::Multiply.precedence() > BinaryOp::Add.precedence());
+    assert!(BinaryOp::Power.precedence() > PREFIX_PRECEDENCE);
+    assert!(BinaryOp::Multiply.precedence() < PREFIX_PRECEDENCE);
+    assert_eq!(BinaryOp::Power.associativity(), Associativity::Right);
+    assert_eq!(BinaryOp::Subtract.associativity(), Associativity::Left);
+}
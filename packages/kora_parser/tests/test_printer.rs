@@ -0,0 +1,181 @@
+use std::fs;
+
+use kora_parser::{
+    ast::{BlockExpr, Expr, Module, Stmt},
+    visit::{walk_block_mut, walk_expr, Visit, VisitMut},
+    ParseError, Parser,
+};
+
+/// Finds whether a statement contains a missing expression, which is printed as nothing.
+#[derive(Default)]
+struct MissingFinder {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for MissingFinder {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        self.found |= matches!(expr, Expr::Missing(_));
+        walk_expr(self, expr);
+    }
+}
+
+/// Removes the statements that couldn't be parsed, which are not printed, and the statements
+/// that were parsed despite errors in them, such as missing expressions or unterminated strings.
+struct ErrorRemover {
+    errors: Vec<ParseError>,
+}
+
+impl ErrorRemover {
+    fn is_invalid(&self, stmt: &Stmt) -> bool {
+        let mut finder = MissingFinder::default();
+        finder.visit_stmt(stmt);
+        let span = stmt.span();
+        finder.found
+            || matches!(stmt, Stmt::Error(_))
+            || self.errors.iter().any(|error| {
+                let start = error.span().start;
+                span.start <= start && start < span.end
+            })
+    }
+}
+
+impl VisitMut for ErrorRemover {
+    fn visit_module_mut(&mut self, module: &mut Module) {
+        module.stmts.retain(|stmt| !self.is_invalid(stmt));
+        for stmt in &mut module.stmts {
+            self.visit_stmt_mut(stmt);
+        }
+    }
+
+    fn visit_block_mut(&mut self, block: &mut BlockExpr) {
+        block.stmts.retain(|stmt| !self.is_invalid(stmt));
+        walk_block_mut(self, block);
+    }
+}
+
+/// Debug output of the module without the spans, which change when the code is printed.
+fn without_spans(module: &Module) -> String {
+    let debug = format!("{module:#?}");
+    let mut output = String::with_capacity(debug.len());
+    let mut chars = debug.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char.is_ascii_digit() && output.ends_with(char::is_whitespace) {
+            let mut number = char.to_string();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                number.push(digit);
+            }
+            if chars.next_if_eq(&'.').is_some() && chars.next_if_eq(&'.').is_some() {
+                while chars.next_if(char::is_ascii_digit).is_some() {}
+                output.push_str("<span>");
+            } else {
+                output.push_str(&number);
+            }
+        } else {
+            output.push(char);
+        }
+    }
+    output
+}
+
+/// Asserts that parsing the printed module gives back the same module, and that printing is
+/// stable.
+fn assert_round_trip(mut module: Module, errors: Vec<ParseError>, name: &str) {
+    ErrorRemover { errors }.visit_module_mut(&mut module);
+    let printed = module.to_source();
+    let (reparsed, errors) = Parser::parse_module(&printed);
+    assert_eq!(errors, [], "{name}:\n{printed}");
+    assert_eq!(
+        without_spans(&reparsed),
+        without_spans(&module),
+        "{name}:\n{printed}"
+    );
+    assert_eq!(reparsed.to_source(), printed, "{name}");
+}
+
+#[test]
+fn test_round_trip_inputs() {
+    for entry in fs::read_dir("tests/inputs").unwrap() {
+        let path = entry.unwrap().path();
+        let source_code = fs::read_to_string(&path).unwrap();
+        let (module, errors) = Parser::parse_module(&source_code);
+        assert_round_trip(module, errors, &path.display().to_string());
+    }
+}
+
+#[test]
+fn test_print() {
+    let (module, _) = Parser::parse_module(
+        "@test(1)\npub def f[T](a: T, b) -> List[T] { let mut x = -a ** 2; if x { y } else { z } }\nstruct P { /// The x.\nx: int, pub y }",
+    );
+    assert_eq!(
+        module.to_source(),
+        "\
+@test(1)
+pub def f[T](a: T, b) -> List[T] {
+    let mut x = -a ** 2
+    if x {
+        y
+    } else {
+        z
+    }
+}
+struct P {
+    /// The x.
+    x: int,
+    pub y,
+}
+"
+    );
+}
+
+#[test]
+fn test_parentheses_follow_precedence() {
+    let (module, _) = Parser::parse_module(
+        "(a + b) * c\na - (b - c)\n(a ** b) ** c\n(-a) ** b\na ** -b\n(a = b).c\n(fn(x) x)(1)",
+    );
+    // Parentheses from the source code are kept, so remove them to check the ones that are added
+    let module = Module {
+        stmts: module
+            .stmts
+            .into_iter()
+            .map(|stmt| match stmt {
+                Stmt::Expr(mut stmt) => {
+                    stmt.expr = unparenthesize(stmt.expr);
+                    Stmt::Expr(stmt)
+                }
+                stmt => stmt,
+            })
+            .collect(),
+        ..module
+    };
+    assert_eq!(
+        module.to_source(),
+        "(a + b) * c\na - (b - c)\n(a ** b) ** c\n(-a) ** b\na ** -b\n(a = b).c\n(fn(x) x)(1)\n"
+    );
+}
+
+/// Removes the parentheses of the AST, but not the ones of tuples.
+fn unparenthesize(expr: kora_parser::ast::Expr) -> kora_parser::ast::Expr {
+    use kora_parser::ast::Expr;
+    match expr {
+        Expr::Paren(paren) => unparenthesize(*paren.expr),
+        Expr::Binary(mut binary) => {
+            *binary.lhs = unparenthesize(*binary.lhs);
+            *binary.rhs = unparenthesize(*binary.rhs);
+            Expr::Binary(binary)
+        }
+        Expr::Unary(mut unary) => {
+            *unary.expr = unparenthesize(*unary.expr);
+            Expr::Unary(unary)
+        }
+        Expr::Field(mut field) => {
+            *field.expr = unparenthesize(*field.expr);
+            Expr::Field(field)
+        }
+        Expr::Call(mut call) => {
+            *call.callee = unparenthesize(*call.callee);
+            Expr::Call(call)
+        }
+        expr => expr,
+    }
+}
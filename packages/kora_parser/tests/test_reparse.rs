@@ -0,0 +1,86 @@
+use kora_lexer::{Span, TextEdit, TokenKind};
+use kora_parser::{ParseError, Parser};
+
+/// Asserts that reparsing after the edit gives the same result as parsing the edited source code
+/// from scratch.
+fn assert_reparse(source_code: &str, start: u32, end: u32, text: &str) {
+    let (old_module, old_errors) = Parser::parse_module(source_code);
+    let edit = TextEdit::new(Span::new(start, end), text);
+    let edited_source_code = edit.apply(source_code);
+
+    let reparsed = Parser::reparse(&edited_source_code, old_module, &old_errors, &edit);
+    assert_eq!(
+        reparsed,
+        Parser::parse_module(&edited_source_code),
+        "{edited_source_code:?}"
+    );
+}
+
+#[test]
+fn test_reparse() {
+    let source_code = "import std.io\n\ndef add(a, b) {\n    a + b\n}\n\nlet x = f\"{add(1, 2)}\"; y = x\nstruct Point { x: int }\n";
+
+    // Edits inside a statement
+    assert_reparse(source_code, 38, 39, "-");
+    assert_reparse(source_code, 33, 33, "let c = a\n    ");
+    assert_reparse(source_code, 59, 60, "3 * 4");
+    // Edits between statements
+    assert_reparse(source_code, 42, 42, "\nprint(1)\n");
+    assert_reparse(source_code, 0, 0, "\n\n");
+    assert_reparse(
+        source_code,
+        source_code.len() as u32,
+        source_code.len() as u32,
+        "z",
+    );
+    // Edits that join or split statements
+    assert_reparse(source_code, 13, 15, " ");
+    assert_reparse(source_code, 66, 68, "\n");
+    assert_reparse(source_code, 73, 74, "; ");
+    assert_reparse(source_code, 42, 42, " +");
+    // Edits that change the rest of the file
+    assert_reparse(source_code, 15, 15, "/*");
+    assert_reparse(source_code, 30, 31, "");
+    assert_reparse(source_code, 0, source_code.len() as u32, "");
+}
+
+#[test]
+fn test_reparse_after_doc_comment() {
+    let source_code = "w = 0\n/// Does nothing.\ndef a() {}\nx = 1\ny = 2\nz = 3\n";
+    assert_reparse(source_code, 39, 40, "10");
+
+    // An error that the old module doesn't really have is only kept if the statements around the
+    // edit are reused
+    let (old_module, _) = Parser::parse_module(source_code);
+    let old_errors = [ParseError::ExpectedExpression {
+        span: (47..48).into(),
+        found: TokenKind::Identifier,
+    }];
+    let edit = TextEdit::new(Span::new(39, 40), "10");
+    let (module, errors) =
+        Parser::reparse(&edit.apply(source_code), old_module, &old_errors, &edit);
+
+    assert_eq!(module, Parser::parse_module(&edit.apply(source_code)).0);
+    assert_eq!(
+        errors,
+        [ParseError::ExpectedExpression {
+            span: (48..49).into(),
+            found: TokenKind::Identifier,
+        }]
+    );
+}
+
+#[test]
+fn test_reparse_with_errors() {
+    // Errors before, inside and after the edit
+    let source_code = "a b\nc = 1\nd = (\ne = 2\n€\n";
+    assert_reparse(source_code, 4, 5, "f");
+    assert_reparse(source_code, 14, 15, "");
+    assert_reparse(source_code, 14, 15, "(1)");
+    assert_reparse(source_code, 2, 3, "");
+    assert_reparse(source_code, 18, 22, "");
+    // Errors found at the start of the statement after the one they belong to
+    let source_code = "x = f(1\n/// A.\nstruct S { x: int }\ny = 2\n";
+    assert_reparse(source_code, 35, 35, " ");
+    assert_reparse(source_code, 8, 9, "");
+}
@@ -0,0 +1,90 @@
+use kora_parser::{
+    ast::{Expr, Ident, Module},
+    visit::{walk_expr, Visit, VisitMut},
+    Parser,
+};
+
+fn parse(source_code: &str) -> Module {
+    let (module, errors) = Parser::parse_module(source_code);
+    assert_eq!(errors, []);
+    module
+}
+
+/// Collects the names of every identifier, in order.
+#[derive(Default)]
+struct IdentCollector<'ast>(Vec<&'ast str>);
+
+impl<'ast> Visit<'ast> for IdentCollector<'ast> {
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        self.0.push(&ident.name);
+    }
+}
+
+#[test]
+fn test_visit_every_ident() {
+    let module = parse(
+        "
+        import std.math.{pi as PI}
+        struct Point[T] { x: T, y: T }
+        def norm(p: Point[float]) -> float { sqrt(p.x ** 2 + p.y ** 2) }
+        extend Point { def zero() -> Point { Point(0, 0) } }
+        let (a, _) = f\"{pi}\"
+        for i in xs { match i { Some { v, .. } => v, n => n } }
+        ",
+    );
+    let mut collector = IdentCollector::default();
+    collector.visit_module(&module);
+    assert_eq!(
+        collector.0,
+        [
+            "std", "math", "pi", "PI", "Point", "T", "x", "T", "y", "T", "norm", "p", "Point",
+            "float", "float", "sqrt", "p", "x", "p", "y", "Point", "zero", "Point", "Point", "a",
+            "pi", "i", "xs", "i", "Some", "v", "v", "n", "n",
+        ]
+    );
+}
+
+/// Stops at closures, so that only the calls outside of them are counted.
+#[derive(Default)]
+struct CallCounter(usize);
+
+impl<'ast> Visit<'ast> for CallCounter {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match expr {
+            Expr::Closure(_) => {}
+            Expr::Call(_) => {
+                self.0 += 1;
+                walk_expr(self, expr);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
+
+#[test]
+fn test_override_stops_traversal() {
+    let module = parse("f(g(1), fn(x) h(x))");
+    let mut counter = CallCounter::default();
+    counter.visit_module(&module);
+    assert_eq!(counter.0, 2);
+}
+
+struct Renamer;
+
+impl VisitMut for Renamer {
+    fn visit_ident_mut(&mut self, ident: &mut Ident) {
+        if ident.name == "old" {
+            ident.name = "new".to_string();
+        }
+    }
+}
+
+#[test]
+fn test_visit_mut_rewrites_in_place() {
+    let mut module = parse("let old = old + 1; print(old.old)");
+    Renamer.visit_module_mut(&mut module);
+
+    let mut collector = IdentCollector::default();
+    collector.visit_module(&module);
+    assert_eq!(collector.0, ["new", "new", "print", "new", "new"]);
+}
@@ -0,0 +1,168 @@
+//! Python bindings for the Kora lexer, for lint scripts and notebooks that work with the token
+//! stream.
+//!
+//! ```python
+//! import kora
+//!
+//! lexer = kora.Lexer("let a = 1")
+//! for token in lexer:
+//!     print(token.kind, repr(token.text), token.start, token.end)
+//! print(lexer.errors)
+//! ```
+
+use kora_lexer::Severity;
+use pyo3::prelude::*;
+
+/// A token, with the name of its kind, its text and the byte range of its text in the source code.
+#[pyclass(module = "kora", frozen, get_all, skip_from_py_object)]
+#[derive(Debug, Clone)]
+struct Token {
+    kind: String,
+    text: String,
+    start: u32,
+    end: u32,
+}
+
+#[pymethods]
+impl Token {
+    fn __repr__(&self) -> String {
+        format!(
+            "Token({}, {:?}, {}..{})",
+            self.kind, self.text, self.start, self.end
+        )
+    }
+}
+
+/// An error found while tokenizing. Warnings have the `"warning"` severity.
+#[pyclass(module = "kora", frozen, get_all, skip_from_py_object)]
+#[derive(Debug, Clone)]
+struct LexerError {
+    code: i32,
+    message: String,
+    severity: &'static str,
+    start: u32,
+    end: u32,
+}
+
+#[pymethods]
+impl LexerError {
+    fn __repr__(&self) -> String {
+        format!(
+            "LexerError({}, {:?}, {}..{})",
+            self.code, self.message, self.start, self.end
+        )
+    }
+}
+
+/// Iterator over the tokens of the source code, including trivia.
+#[pyclass(module = "kora")]
+struct Lexer {
+    tokens: std::vec::IntoIter<Token>,
+    errors: Vec<LexerError>,
+}
+
+#[pymethods]
+impl Lexer {
+    #[new]
+    fn new(source_code: &str) -> Self {
+        let (tokens, errors) = kora_lexer::Lexer::tokenize(source_code);
+        let tokens = tokens
+            .into_iter()
+            .map(|token| Token {
+                kind: token.kind.to_string(),
+                text: token.text.to_string(),
+                start: token.span.start,
+                end: token.span.end,
+            })
+            .collect::<Vec<_>>();
+        let errors = errors
+            .into_iter()
+            .map(|error| LexerError {
+                code: error.code(),
+                message: error.message(),
+                severity: match error.severity() {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                },
+                start: error.span().start,
+                end: error.span().end,
+            })
+            .collect();
+
+        Self {
+            tokens: tokens.into_iter(),
+            errors,
+        }
+    }
+
+    /// Syntax errors found in the whole source code.
+    #[getter]
+    fn errors(&self) -> Vec<LexerError> {
+        self.errors.clone()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<Token> {
+        slf.tokens.next()
+    }
+}
+
+/// Tokenizes the whole source code at once, returning the tokens, including trivia, and the
+/// errors found.
+#[pyfunction]
+fn tokenize(source_code: &str) -> (Vec<Token>, Vec<LexerError>) {
+    let lexer = Lexer::new(source_code);
+    (lexer.tokens.collect(), lexer.errors)
+}
+
+#[pymodule]
+fn kora(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Lexer>()?;
+    module.add_class::<Token>()?;
+    module.add_class::<LexerError>()?;
+    module.add_function(wrap_pyfunction!(tokenize, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexer_spans_and_severities() {
+        // The byte order mark is a warning and the illegal character an error
+        let (tokens, errors) = tokenize("\u{FEFF}é = €");
+
+        let tokens = tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.kind.as_str(),
+                    token.text.as_str(),
+                    token.start,
+                    token.end,
+                )
+            })
+            .collect::<Vec<_>>();
+        // Spans are byte offsets, like in Rust
+        assert_eq!(
+            tokens,
+            [
+                ("Trivia", "\u{FEFF}", 0, 3),
+                ("Identifier", "é", 3, 5),
+                ("Trivia", " ", 5, 6),
+                ("Equal", "=", 6, 7),
+                ("Trivia", " ", 7, 8),
+                ("Illegal", "€", 8, 11),
+            ]
+        );
+        let errors = errors
+            .iter()
+            .map(|error| (error.severity, error.start, error.end))
+            .collect::<Vec<_>>();
+        assert_eq!(errors, [("warning", 0, 3), ("error", 8, 11)]);
+    }
+}
@@ -0,0 +1,7 @@
+/// Functions that are visible in every module without being imported.
+pub const BUILTIN_FUNCTIONS: &[&str] = &["assert", "len", "print", "range"];
+
+/// Types that are visible in every module without being imported.
+pub const BUILTIN_TYPES: &[&str] = &[
+    "Bool", "Char", "Float", "Int", "List", "Map", "Option", "String",
+];
@@ -0,0 +1,9 @@
+//! Name resolution, which links every name used in the AST to its definition.
+
+mod builtins;
+mod resolution;
+mod resolver;
+
+pub use builtins::{BUILTIN_FUNCTIONS, BUILTIN_TYPES};
+pub use resolution::{DefId, Definition, DefinitionKind, Reference, ReferenceKind, Resolution};
+pub use resolver::resolve;
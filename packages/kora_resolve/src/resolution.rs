@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use kora_diagnostics::Diagnostic;
+use kora_lexer::Span;
+
+/// Identifies a [`Definition`] of a [`Resolution`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DefId(pub(crate) u32);
+
+/// Something that a name can refer to, such as a function or a variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub name: String,
+    pub kind: DefinitionKind,
+    /// Span of the name in the definition, or `None` for builtins.
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DefinitionKind {
+    Function,
+    /// A method of an `extend` block, which isn't visible as a name but is resolved on the
+    /// type of its receiver, such as `show` in `point.show()`.
+    Method,
+    Struct,
+    Const,
+    /// A module or a name imported from a module.
+    Import,
+    /// A variable bound by a `let`, a `for` or a `match` arm.
+    Variable {
+        is_mutable: bool,
+    },
+    /// A parameter of a function or a closure.
+    Param,
+    GenericParam,
+    BuiltinFunction,
+    BuiltinType,
+}
+
+/// A use of a name, see [`Resolution::reference`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub def: DefId,
+    pub kind: ReferenceKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// The value of the definition is used, such as `a` in `print(a)`.
+    Read,
+    /// The definition is assigned to, such as `a` in `a = 1` or `a += 1`.
+    Write,
+}
+
+/// The result of name resolution: the definitions of a module and the names that refer to them.
+#[derive(Debug, Clone, Default)]
+pub struct Resolution {
+    pub(crate) definitions: Vec<Definition>,
+    /// The definition of every name that is used, by the span of the name. For paths such as
+    /// `math::max`, only the first segment is resolved.
+    pub(crate) references: HashMap<Span, Reference>,
+    /// The definitions by the span of their name.
+    pub(crate) declarations: HashMap<Span, DefId>,
+    /// Unresolved names and names defined twice.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Resolution {
+    /// Returns the definition with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id belongs to another resolution.
+    pub fn definition(&self, id: DefId) -> &Definition {
+        &self.definitions[id.0 as usize]
+    }
+
+    /// Returns all definitions, including the builtins, in the order they were found.
+    pub fn definitions(&self) -> impl Iterator<Item = (DefId, &Definition)> {
+        (self.definitions.iter().enumerate()).map(|(index, def)| (DefId(index as u32), def))
+    }
+
+    /// Returns what the name at the given span refers to, or `None` if the span isn't a resolved
+    /// name.
+    pub fn reference(&self, span: Span) -> Option<Reference> {
+        self.references.get(&span).copied()
+    }
+
+    /// Returns the definition whose name is at the given span, such as the `a` of `let a = 1`.
+    pub fn declaration(&self, span: Span) -> Option<DefId> {
+        self.declarations.get(&span).copied()
+    }
+
+    /// Returns the spans of the names that refer to the definition, in no particular order.
+    pub fn references_to(&self, id: DefId) -> impl Iterator<Item = (Span, ReferenceKind)> + '_ {
+        (self.references.iter())
+            .filter(move |(_, reference)| reference.def == id)
+            .map(|(span, reference)| (*span, reference.kind))
+    }
+}
@@ -0,0 +1,565 @@
+use std::collections::{HashMap, HashSet};
+
+use kora_diagnostics::{similar_names, Applicability, Diagnostic, ErrorCode, Suggestion};
+use kora_lexer::Span;
+use kora_parser::{
+    ast::{
+        Attribute, BlockExpr, ConstDecl, Expr, ExtendDecl, FuncDecl, Ident, ImportDecl, ImportKind,
+        Item, LetStmt, MatchArm, Module, Param, Pattern, Stmt, StructDecl, Type,
+    },
+    visit::{walk_block_mut, walk_expr_mut, walk_module_mut, VisitMut},
+};
+
+use crate::{
+    builtins::{BUILTIN_FUNCTIONS, BUILTIN_TYPES},
+    resolution::{DefId, Definition, DefinitionKind, Reference, ReferenceKind, Resolution},
+};
+
+/// Resolves the names used in the module, and fills in the captures of its closures.
+///
+/// Variables, parameters and imports that are never read are reported with warnings, unless
+/// their name starts with `_`.
+/// Items are visible in the whole module or block that declares them, so functions can call
+/// functions declared after them. Variables and parameters are visible from their declaration
+/// to the end of their block, and a variable can shadow any name, including another variable of
+/// the same block. Function bodies are resolved where they are declared, so they can use the
+/// variables declared before them.
+///
+/// ```
+/// # use kora_parser::{ast::{Expr, Stmt}, Parser};
+/// # use kora_resolve::resolve;
+/// let (mut module, _) = Parser::parse_module("let a = 1\nprint(b)");
+/// let resolution = resolve(&mut module);
+/// assert_eq!(
+///     resolution.diagnostics[0].to_string(),
+///     "error[E0200]: cannot find `b` in this scope",
+/// );
+/// ```
+pub fn resolve(module: &mut Module) -> Resolution {
+    let mut resolver = Resolver {
+        resolution: Resolution::default(),
+        scopes: Vec::new(),
+        shorthand_bindings: HashSet::new(),
+        unaliased_imports: HashSet::new(),
+        redefined_names: HashSet::new(),
+    };
+    resolver.push_scope(ScopeKind::Builtins);
+    for (names, kind) in [
+        (BUILTIN_FUNCTIONS, DefinitionKind::BuiltinFunction),
+        (BUILTIN_TYPES, DefinitionKind::BuiltinType),
+    ] {
+        for name in names {
+            resolver.add_definition(name, kind, None);
+        }
+    }
+    resolver.visit_module_mut(module);
+    resolver.report_unused();
+    resolver.resolution
+}
+
+struct Resolver {
+    resolution: Resolution,
+    /// The scopes enclosing the visited node, from the outermost to the innermost.
+    scopes: Vec<Scope>,
+    /// Spans of the fields of struct patterns that bind a variable of the same name, such as
+    /// `x` in `Point { x, .. }`.
+    shorthand_bindings: HashSet<Span>,
+    /// Spans of the imported names that have no alias, such as `Set` in `import a.{Set}`.
+    unaliased_imports: HashSet<Span>,
+    /// Spans of the names whose definition is repeated later, which are not reported as unused
+    /// because the repetition already is.
+    redefined_names: HashSet<Span>,
+}
+
+struct Scope {
+    kind: ScopeKind,
+    names: HashMap<String, DefId>,
+}
+
+enum ScopeKind {
+    Builtins,
+    Module,
+    /// The generic parameters and the parameters of a function, or the generic parameters of a
+    /// struct.
+    Function,
+    /// The parameters of a closure, and the variables of the enclosing functions that it uses.
+    Closure {
+        captures: Vec<Ident>,
+    },
+    /// The items and variables of a block, or the variables bound by a pattern of a `for` or a
+    /// `match` arm.
+    Block,
+}
+
+/// Index of the module scope in [`Resolver::scopes`]. Variables of deeper scopes are local to a
+/// function or a block.
+const MODULE_SCOPE: usize = 1;
+
+impl Resolver {
+    fn push_scope(&mut self, kind: ScopeKind) {
+        self.scopes.push(Scope {
+            kind,
+            names: HashMap::new(),
+        });
+    }
+
+    fn pop_scope(&mut self) -> Scope {
+        self.scopes.pop().expect("scopes are balanced")
+    }
+
+    /// Adds a definition to the innermost scope, shadowing any definition of the same name.
+    fn add_definition(&mut self, name: &str, kind: DefinitionKind, span: Option<Span>) -> DefId {
+        let id = self.new_definition(name, kind, span);
+        let scope = self.scopes.last_mut().expect("there is always a scope");
+        scope.names.insert(name.to_string(), id);
+        id
+    }
+
+    /// Adds a definition without making it visible in any scope.
+    fn new_definition(&mut self, name: &str, kind: DefinitionKind, span: Option<Span>) -> DefId {
+        let id = DefId(self.resolution.definitions.len() as u32);
+        self.resolution.definitions.push(Definition {
+            name: name.to_string(),
+            kind,
+            span,
+        });
+        if let Some(span) = span {
+            self.resolution.declarations.insert(span, id);
+        }
+        id
+    }
+
+    /// Defines the names in the innermost scope, reporting the names that are defined twice.
+    fn define_all(&mut self, names: &[Ident], kind: DefinitionKind) {
+        let mut first_spans = HashMap::new();
+        for name in names {
+            if let Some(&first_span) = first_spans.get(&name.name) {
+                self.report_duplicate(name, first_span);
+                continue;
+            }
+            first_spans.insert(&name.name, name.span);
+            self.add_definition(&name.name, kind, Some(name.span));
+        }
+    }
+
+    fn report_duplicate(&mut self, name: &Ident, first_span: Span) {
+        self.redefined_names.insert(first_span);
+        self.resolution.diagnostics.push(
+            Diagnostic::error(
+                format!("the name `{}` is defined multiple times", name.name),
+                name.span,
+            )
+            .with_code(ErrorCode(201))
+            .with_primary_message(format!("`{}` redefined here", name.name))
+            .with_label(
+                first_span,
+                format!("previous definition of `{}` here", name.name),
+            ),
+        );
+    }
+
+    /// Reports the names that are declared twice without defining them, such as struct fields.
+    fn check_duplicates<'a>(&mut self, names: impl IntoIterator<Item = &'a Ident>) {
+        let mut first_spans = HashMap::new();
+        for name in names {
+            match first_spans.get(&name.name) {
+                Some(&first_span) => self.report_duplicate(name, first_span),
+                None => {
+                    first_spans.insert(&name.name, name.span);
+                }
+            }
+        }
+    }
+
+    /// Defines the items of the statements in the innermost scope, so that they are visible in
+    /// the whole module or block.
+    fn define_items(&mut self, stmts: &[Stmt]) {
+        let mut names = Vec::new();
+        for stmt in stmts {
+            let Stmt::Item(item) = stmt else {
+                continue;
+            };
+            match item {
+                Item::Func(decl) => names.push((decl.name.clone(), DefinitionKind::Function)),
+                Item::Struct(decl) => names.push((decl.name.clone(), DefinitionKind::Struct)),
+                Item::Const(decl) => names.push((decl.name.clone(), DefinitionKind::Const)),
+                Item::Import(decl) => {
+                    self.unaliased_imports
+                        .extend(unaliased_names(decl).map(|name| name.span));
+                    names.extend(
+                        imported_names(decl).map(|name| (name.clone(), DefinitionKind::Import)),
+                    );
+                }
+                Item::Extend(_) => {}
+            }
+        }
+
+        let mut first_spans = HashMap::new();
+        for (name, kind) in names {
+            if let Some(&first_span) = first_spans.get(&name.name) {
+                self.report_duplicate(&name, first_span);
+                continue;
+            }
+            first_spans.insert(name.name.clone(), name.span);
+            self.add_definition(&name.name, kind, Some(name.span));
+        }
+    }
+
+    /// Resolves the name to the innermost definition with that name.
+    fn resolve_name(&mut self, name: &Ident, kind: ReferenceKind, is_type: bool) {
+        let found = self
+            .scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, scope)| Some((index, *scope.names.get(&name.name)?)));
+        let Some((scope_index, def)) = found else {
+            self.report_unresolved(name, is_type);
+            return;
+        };
+
+        self.resolution
+            .references
+            .insert(name.span, Reference { def, kind });
+        let is_local = scope_index > MODULE_SCOPE
+            && matches!(
+                self.resolution.definition(def).kind,
+                DefinitionKind::Variable { .. } | DefinitionKind::Param
+            );
+        if is_local {
+            for scope in &mut self.scopes[scope_index + 1..] {
+                if let ScopeKind::Closure { captures } = &mut scope.kind {
+                    if !captures.iter().any(|capture| capture.name == name.name) {
+                        captures.push(name.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn report_unresolved(&mut self, name: &Ident, is_type: bool) {
+        let (message, code) = if is_type {
+            (
+                format!("cannot find type `{}` in this scope", name.name),
+                202,
+            )
+        } else {
+            (format!("cannot find `{}` in this scope", name.name), 200)
+        };
+        let mut diagnostic = Diagnostic::error(message, name.span)
+            .with_code(ErrorCode(code))
+            .with_primary_message("not found in this scope");
+
+        let visible_names = self
+            .scopes
+            .iter()
+            .flat_map(|scope| scope.names.keys().map(String::as_str));
+        if let Some(similar_name) = similar_names(&name.name, visible_names).first() {
+            diagnostic = diagnostic.with_suggestion(Suggestion::new(
+                format!("a similar name exists: `{similar_name}`"),
+                name.span,
+                *similar_name,
+                Applicability::MaybeIncorrect,
+            ));
+        }
+        self.resolution.diagnostics.push(diagnostic);
+    }
+
+    /// Resolves the types of the parameters and defines them in the innermost scope.
+    fn define_params(&mut self, params: &mut [Param]) {
+        for param in params.iter_mut() {
+            if let Some(ty) = &mut param.ty {
+                self.visit_type_mut(ty);
+            }
+        }
+        let names = params
+            .iter()
+            .map(|param| param.name.clone())
+            .collect::<Vec<_>>();
+        self.define_all(&names, DefinitionKind::Param);
+    }
+
+    /// Resolves the paths of the pattern, and defines the variables that it binds in the
+    /// innermost scope.
+    fn bind_pattern(&mut self, pattern: &Pattern, is_mutable: bool) {
+        let mut names = Vec::new();
+        self.collect_bindings(pattern, &mut names);
+        self.define_all(&names, DefinitionKind::Variable { is_mutable });
+    }
+
+    fn collect_bindings(&mut self, pattern: &Pattern, names: &mut Vec<Ident>) {
+        match pattern {
+            Pattern::Ident(name) => names.push(name.clone()),
+            Pattern::Wildcard(_) | Pattern::Literal(_) => {}
+            Pattern::Path(path) => {
+                self.resolve_name(&path.segments[0], ReferenceKind::Read, false);
+            }
+            Pattern::Tuple(pattern) => {
+                for element in &pattern.elements {
+                    self.collect_bindings(element, names);
+                }
+            }
+            Pattern::Struct(pattern) => {
+                self.resolve_name(&pattern.path[0], ReferenceKind::Read, false);
+                for field in &pattern.fields {
+                    match &field.pattern {
+                        Some(pattern) => self.collect_bindings(pattern, names),
+                        None => {
+                            self.shorthand_bindings.insert(field.name.span);
+                            names.push(field.name.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Warns about the variables, parameters and imports that are never read, except `self` and
+    /// the names that start with `_`.
+    fn report_unused(&mut self) {
+        let read = (self.resolution.references.values())
+            .filter(|reference| reference.kind == ReferenceKind::Read)
+            .map(|reference| reference.def)
+            .collect::<HashSet<_>>();
+        let referenced = (self.resolution.references.values())
+            .map(|reference| reference.def)
+            .collect::<HashSet<_>>();
+
+        let mut diagnostics = Vec::new();
+        for (id, definition) in self.resolution.definitions() {
+            let Some(span) = definition.span else {
+                continue;
+            };
+            if read.contains(&id)
+                || self.redefined_names.contains(&span)
+                || definition.name == "self"
+                || definition.name.starts_with('_')
+            {
+                continue;
+            }
+            let name = &definition.name;
+            let diagnostic = match definition.kind {
+                DefinitionKind::Variable { .. } | DefinitionKind::Param => {
+                    let primary_message = if referenced.contains(&id) {
+                        "assigned to, but never read"
+                    } else {
+                        "never read"
+                    };
+                    // `Point { x }` binds `x` to the field `x`, so the field name has to stay
+                    let replacement = if self.shorthand_bindings.contains(&span) {
+                        format!("{name}: _{name}")
+                    } else {
+                        format!("_{name}")
+                    };
+                    Diagnostic::warning(format!("unused variable: `{name}`"), span)
+                        .with_code(ErrorCode(203))
+                        .with_primary_message(primary_message)
+                        .with_suggestion(Suggestion::new(
+                            "if this is intentional, prefix it with an underscore",
+                            span,
+                            replacement,
+                            Applicability::MachineApplicable,
+                        ))
+                }
+                DefinitionKind::Import => {
+                    // Renaming the imported name would import something else, so it gets an
+                    // alias instead
+                    let replacement = if self.unaliased_imports.contains(&span) {
+                        format!("{name} as _{name}")
+                    } else {
+                        format!("_{name}")
+                    };
+                    Diagnostic::warning(format!("unused import: `{name}`"), span)
+                        .with_code(ErrorCode(204))
+                        .with_primary_message("never used")
+                        .with_suggestion(Suggestion::new(
+                            "if this is intentional, prefix it with an underscore",
+                            span,
+                            replacement,
+                            Applicability::MachineApplicable,
+                        ))
+                }
+                _ => continue,
+            };
+            diagnostics.push(diagnostic);
+        }
+        self.resolution.diagnostics.extend(diagnostics);
+    }
+}
+
+impl VisitMut for Resolver {
+    fn visit_module_mut(&mut self, module: &mut Module) {
+        self.push_scope(ScopeKind::Module);
+        self.define_items(&module.stmts);
+        walk_module_mut(self, module);
+        self.pop_scope();
+    }
+
+    fn visit_block_mut(&mut self, block: &mut BlockExpr) {
+        self.push_scope(ScopeKind::Block);
+        self.define_items(&block.stmts);
+        walk_block_mut(self, block);
+        self.pop_scope();
+    }
+
+    fn visit_func_decl_mut(&mut self, decl: &mut FuncDecl) {
+        self.push_scope(ScopeKind::Function);
+        let generics = (decl.generics.iter())
+            .map(|param| param.name.clone())
+            .collect::<Vec<_>>();
+        self.define_all(&generics, DefinitionKind::GenericParam);
+        self.define_params(&mut decl.params);
+        if let Some(ty) = &mut decl.return_type {
+            self.visit_type_mut(ty);
+        }
+        self.visit_block_mut(&mut decl.body);
+        self.pop_scope();
+    }
+
+    fn visit_struct_decl_mut(&mut self, decl: &mut StructDecl) {
+        self.push_scope(ScopeKind::Function);
+        let generics = (decl.generics.iter())
+            .map(|param| param.name.clone())
+            .collect::<Vec<_>>();
+        self.define_all(&generics, DefinitionKind::GenericParam);
+        self.check_duplicates(decl.fields.iter().map(|field| &field.name));
+        for field in &mut decl.fields {
+            if let Some(ty) = &mut field.ty {
+                self.visit_type_mut(ty);
+            }
+        }
+        self.pop_scope();
+    }
+
+    fn visit_extend_decl_mut(&mut self, decl: &mut ExtendDecl) {
+        self.visit_type_mut(&mut decl.ty);
+        if let Some(ty) = &mut decl.with_type {
+            self.visit_type_mut(ty);
+        }
+        // Methods are not visible as names, they are resolved on their receiver by type checking
+        for method in &mut decl.methods {
+            self.new_definition(
+                &method.name.name,
+                DefinitionKind::Method,
+                Some(method.name.span),
+            );
+            self.visit_func_decl_mut(method);
+        }
+    }
+
+    /// Imported names are defined with the other items of their scope, and modules are not
+    /// resolved.
+    fn visit_import_decl_mut(&mut self, _decl: &mut ImportDecl) {}
+
+    fn visit_const_decl_mut(&mut self, decl: &mut ConstDecl) {
+        if let Some(ty) = &mut decl.ty {
+            self.visit_type_mut(ty);
+        }
+        self.visit_expr_mut(&mut decl.value);
+    }
+
+    /// Attributes are read by the tools that handle them, such as `@derive(Debug)`, so their
+    /// arguments are not resolved.
+    fn visit_attribute_mut(&mut self, _attribute: &mut Attribute) {}
+
+    fn visit_let_stmt_mut(&mut self, stmt: &mut LetStmt) {
+        if let Some(ty) = &mut stmt.ty {
+            self.visit_type_mut(ty);
+        }
+        // The value can't use the variables that the pattern binds, so `let a = a + 1` uses
+        // the previous `a`
+        self.visit_expr_mut(&mut stmt.value);
+        self.bind_pattern(&stmt.pattern, stmt.is_mutable);
+    }
+
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        match ty {
+            Type::Path(ty) => {
+                self.resolve_name(&ty.segments[0], ReferenceKind::Read, true);
+                for arg in &mut ty.args {
+                    self.visit_type_mut(arg);
+                }
+            }
+        }
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Path(path) => self.resolve_name(&path.segments[0], ReferenceKind::Read, false),
+            Expr::Struct(expr) => {
+                self.resolve_name(&expr.path[0], ReferenceKind::Read, false);
+                for field in &mut expr.fields {
+                    match &mut field.value {
+                        Some(value) => self.visit_expr_mut(value),
+                        // `Point { x }` is `Point { x: x }`
+                        None => self.resolve_name(&field.name, ReferenceKind::Read, false),
+                    }
+                }
+            }
+            Expr::Assign(expr) => {
+                self.visit_expr_mut(&mut expr.value);
+                match &mut *expr.target {
+                    Expr::Path(path) => {
+                        self.resolve_name(&path.segments[0], ReferenceKind::Write, false)
+                    }
+                    target => self.visit_expr_mut(target),
+                }
+            }
+            Expr::For(expr) => {
+                self.visit_expr_mut(&mut expr.iterable);
+                self.push_scope(ScopeKind::Block);
+                self.bind_pattern(&expr.pattern, false);
+                self.visit_block_mut(&mut expr.body);
+                self.pop_scope();
+            }
+            Expr::Closure(expr) => {
+                self.push_scope(ScopeKind::Closure {
+                    captures: Vec::new(),
+                });
+                self.define_params(&mut expr.params);
+                if let Some(ty) = &mut expr.return_type {
+                    self.visit_type_mut(ty);
+                }
+                self.visit_expr_mut(&mut expr.body);
+                if let ScopeKind::Closure { captures } = self.pop_scope().kind {
+                    expr.captures = captures;
+                }
+            }
+            _ => walk_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_match_arm_mut(&mut self, arm: &mut MatchArm) {
+        self.push_scope(ScopeKind::Block);
+        self.bind_pattern(&arm.pattern, false);
+        self.visit_expr_mut(&mut arm.body);
+        self.pop_scope();
+    }
+}
+
+/// Returns the names that the import defines, such as `math` for `import std.math`, or `b` and
+/// `d` for `import a.{b, c as d}`.
+fn imported_names(decl: &ImportDecl) -> impl Iterator<Item = &Ident> {
+    let names: Vec<&Ident> = match &decl.kind {
+        ImportKind::Module { alias } => alias.as_ref().or(decl.path.last()).into_iter().collect(),
+        ImportKind::Items(items) => items
+            .iter()
+            .map(|item| item.alias.as_ref().unwrap_or(&item.name))
+            .collect(),
+    };
+    names.into_iter()
+}
+
+/// Returns the names that the import defines without an alias, such as `math` for
+/// `import std.math`, or `b` for `import a.{b, c as d}`.
+fn unaliased_names(decl: &ImportDecl) -> impl Iterator<Item = &Ident> {
+    let names: Vec<&Ident> = match &decl.kind {
+        ImportKind::Module { alias: Some(_) } => Vec::new(),
+        ImportKind::Module { alias: None } => decl.path.last().into_iter().collect(),
+        ImportKind::Items(items) => (items.iter())
+            .filter(|item| item.alias.is_none())
+            .map(|item| &item.name)
+            .collect(),
+    };
+    names.into_iter()
+}
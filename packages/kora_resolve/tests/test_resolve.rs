@@ -0,0 +1,100 @@
+use std::fmt::Write;
+
+use kora_diagnostics::{Renderer, SourceMap};
+use kora_parser::{
+    ast::{Expr, Module},
+    visit::{walk_expr, Visit},
+    Parser,
+};
+use kora_resolve::{resolve, ReferenceKind, Resolution};
+
+/// Formats the references of the module, with the location of their definitions, followed by
+/// the rendered diagnostics.
+fn format_resolution(path: &str, source_code: &str) -> String {
+    let mut source_map = SourceMap::new();
+    let file = source_map.add_file(path, source_code);
+    let (mut module, errors) = Parser::parse_module(source_code);
+    assert_eq!(errors, []);
+    let resolution = resolve(&mut module);
+
+    let source_file = source_map.file(file);
+    let mut references = resolution
+        .definitions()
+        .flat_map(|(id, definition)| {
+            (resolution.references_to(id)).map(move |(span, kind)| (span, kind, definition))
+        })
+        .collect::<Vec<_>>();
+    references.sort_by_key(|(span, ..)| span.start);
+
+    let mut output = String::new();
+    for (span, kind, definition) in references {
+        let kind = match kind {
+            ReferenceKind::Read => "read",
+            ReferenceKind::Write => "write",
+        };
+        let position = source_file.position(span.start);
+        write!(output, "{position} {kind} `{}` -> ", definition.name).unwrap();
+        match definition.span {
+            Some(def_span) => {
+                let position = source_file.position(def_span.start);
+                writeln!(output, "{:?} at {position}", definition.kind).unwrap();
+            }
+            None => writeln!(output, "{:?}", definition.kind).unwrap(),
+        }
+    }
+    writeln!(output, "{}", format_captures(&module)).unwrap();
+    for diagnostic in &resolution.diagnostics {
+        writeln!(
+            output,
+            "{}",
+            Renderer::new(false).render(diagnostic, &source_map, file)
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Formats the captures of the closures of the module, in the order of the closures.
+fn format_captures(module: &Module) -> String {
+    struct Captures(Vec<String>);
+
+    impl<'ast> Visit<'ast> for Captures {
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            if let Expr::Closure(closure) = expr {
+                let names = (closure.captures.iter())
+                    .map(|capture| capture.name.as_str())
+                    .collect::<Vec<_>>();
+                self.0.push(format!("captures: [{}]", names.join(", ")));
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut captures = Captures(Vec::new());
+    captures.visit_module(module);
+    captures.0.join("\n")
+}
+
+#[test]
+fn test_resolve() {
+    insta::glob!("inputs/*.kora", |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        insta::assert_snapshot!(format_resolution(name, &input));
+    });
+}
+
+#[test]
+fn test_resolution_lookups() {
+    let (mut module, _) = Parser::parse_module("let a = 1\na = a");
+    let resolution: Resolution = resolve(&mut module);
+
+    let def = resolution.declaration((4..5).into()).unwrap();
+    assert_eq!(resolution.definition(def).name, "a");
+    let write = resolution.reference((10..11).into()).unwrap();
+    assert_eq!((write.def, write.kind), (def, ReferenceKind::Write));
+    let read = resolution.reference((14..15).into()).unwrap();
+    assert_eq!((read.def, read.kind), (def, ReferenceKind::Read));
+    assert_eq!(resolution.references_to(def).count(), 2);
+    assert!(resolution.diagnostics.is_empty());
+}
@@ -0,0 +1,1575 @@
+use std::collections::{HashMap, HashSet};
+
+use kora_diagnostics::{similar_names, Applicability, Diagnostic, ErrorCode, Suggestion};
+use kora_lexer::Span;
+use kora_parser::ast::{
+    BinaryExpr, BinaryOp, BlockExpr, CallExpr, ClosureExpr, Expr, ExtendDecl, FieldExpr, FieldInit,
+    FuncDecl, GenericParam, Ident, Item, LetStmt, LiteralExpr, LiteralKind, MatchExpr, Module,
+    Pattern, Stmt, StringPart, StructExpr, Type, UnaryOp,
+};
+use kora_resolve::{DefId, DefinitionKind, Resolution};
+
+use crate::{
+    exhaustiveness::{missing_patterns, unreachable_arms, Ctor, Pat},
+    methods::{Method, MethodTable, TypeKey},
+    table::InferenceTable,
+    ty::{Ty, TyVar},
+    typing::Typing,
+};
+
+/// Infers the types of the expressions of a resolved module, and reports the expressions whose
+/// types don't fit together, such as the arguments of a call that don't match its parameters.
+///
+/// Types are inferred from how values are used, in the order of the code, like in
+/// Hindley-Milner inference: the parameters of `def double(x) { x * 2 }` are inferred from its
+/// body. Type annotations, such as `x: Int` or `-> Bool`, fix the types instead, and a value
+/// that doesn't have the type of its annotation is reported with the annotation. Once its body
+/// is checked, a function is generalized over the types that it doesn't constrain, so that
+/// `def id(x) { x }` can be called with any type, like `def id[T](x: T) -> T`. Functions can
+/// be called before they are declared, but then the call constrains their parameters like
+/// their body.
+///
+/// ```
+/// # use kora_parser::Parser;
+/// # use kora_resolve::resolve;
+/// # use kora_typeck::check;
+/// let (mut module, _) = Parser::parse_module("def double(x) { x * 2 }\ndouble(\"a\")");
+/// let resolution = resolve(&mut module);
+/// let typing = check(&module, &resolution);
+/// assert_eq!(
+///     typing.diagnostics[0].to_string(),
+///     "error[E0300]: mismatched types",
+/// );
+/// ```
+pub fn check(module: &Module, resolution: &Resolution) -> Typing {
+    let mut checker = Checker {
+        resolution,
+        table: InferenceTable::default(),
+        schemes: HashMap::new(),
+        struct_fields: HashMap::new(),
+        struct_generics: HashMap::new(),
+        param_annotations: HashMap::new(),
+        methods: MethodTable::default(),
+        method_calls: HashMap::new(),
+        locals: Vec::new(),
+        return_types: Vec::new(),
+        expr_types: HashMap::new(),
+        diagnostics: Vec::new(),
+    };
+    checker.check_stmts(&module.stmts);
+
+    let table = &checker.table;
+    Typing {
+        expr_types: (checker.expr_types.iter())
+            .map(|(span, ty)| (*span, table.resolve(ty)))
+            .collect(),
+        def_types: (checker.schemes.iter())
+            .map(|(def, scheme)| (*def, table.resolve(&scheme.ty)))
+            .collect(),
+        method_calls: checker.method_calls,
+        diagnostics: checker.diagnostics,
+    }
+}
+
+/// The type of a definition, whose variables in `vars` and generic parameters in `generics` are
+/// replaced by new variables at each use of the definition.
+struct Scheme {
+    vars: Vec<TyVar>,
+    generics: Vec<DefId>,
+    ty: Ty,
+}
+
+impl Scheme {
+    fn monomorphic(ty: Ty) -> Self {
+        Self {
+            vars: Vec::new(),
+            generics: Vec::new(),
+            ty,
+        }
+    }
+}
+
+struct Checker<'a> {
+    resolution: &'a Resolution,
+    table: InferenceTable,
+    schemes: HashMap<DefId, Scheme>,
+    /// The names of the fields of the structs, in the order of the parameters of their
+    /// constructor.
+    struct_fields: HashMap<DefId, Vec<String>>,
+    struct_generics: HashMap<DefId, Vec<DefId>>,
+    /// The spans of the type annotations of the parameters of the functions, and of the fields
+    /// of the structs, to show why an argument is expected to have a type.
+    param_annotations: HashMap<DefId, Vec<Option<Span>>>,
+    methods: MethodTable,
+    /// The methods that method calls refer to, by the span of the name of the method.
+    method_calls: HashMap<Span, DefId>,
+    /// The variables and parameters of the functions being checked, in the order they are
+    /// declared.
+    locals: Vec<DefId>,
+    /// The return types of the functions and closures being checked, from the outermost to the
+    /// innermost, with the span of their annotation.
+    return_types: Vec<(Ty, Option<Span>)>,
+    expr_types: HashMap<Span, Ty>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    /// Checks the statements of a module or a block, and returns the type of its value.
+    fn check_stmts(&mut self, stmts: &[Stmt]) -> Ty {
+        self.declare_items(stmts);
+        let mut ty = Ty::unit();
+        for (index, stmt) in stmts.iter().enumerate() {
+            match stmt {
+                Stmt::Item(item) => self.check_item(item),
+                Stmt::Let(stmt) => self.check_let(stmt),
+                Stmt::Expr(stmt) => {
+                    let expr_ty = self.check_expr(&stmt.expr);
+                    if index + 1 == stmts.len() {
+                        ty = if diverges(&stmt.expr) {
+                            // `return a;` leaves the block, so the block can have any type
+                            self.table.new_var()
+                        } else if stmt.has_semicolon {
+                            Ty::unit()
+                        } else {
+                            expr_ty
+                        };
+                    }
+                }
+                Stmt::Error(_) => {}
+            }
+        }
+        ty
+    }
+
+    /// Gives a type to the items of the statements, so that they can be used before they are
+    /// checked. Items without type annotations get type variables, which are inferred when
+    /// the items are checked or used.
+    fn declare_items(&mut self, stmts: &[Stmt]) {
+        let items = (stmts.iter())
+            .filter_map(|stmt| match stmt {
+                Stmt::Item(item) => Some(item),
+                _ => None,
+            })
+            // Items defined twice have no definition
+            .filter_map(|item| {
+                let name = match item {
+                    Item::Func(decl) => &decl.name,
+                    Item::Struct(decl) => &decl.name,
+                    Item::Const(decl) => &decl.name,
+                    Item::Extend(_) | Item::Import(_) => return None,
+                };
+                Some((self.resolution.declaration(name.span)?, item))
+            })
+            .collect::<Vec<_>>();
+
+        // The number of generic parameters of the structs is needed to read the annotations
+        for (def, item) in &items {
+            if let Item::Struct(decl) = item {
+                let generics = self.generic_params(&decl.generics);
+                self.struct_generics.insert(*def, generics);
+            }
+        }
+        for (def, item) in items {
+            let scheme = match item {
+                Item::Func(decl) => self.declare_func(def, decl),
+                Item::Struct(decl) => {
+                    let generics = self.struct_generics[&def].clone();
+                    let params = (decl.fields.iter())
+                        .map(|field| self.annotation_type(field.ty.as_ref()))
+                        .collect();
+                    let args = (generics.iter())
+                        .map(|param| self.generic_param_type(*param))
+                        .collect();
+                    let ret = Ty::Struct {
+                        def,
+                        name: decl.name.name.clone(),
+                        args,
+                    };
+                    let fields = decl.fields.iter().map(|field| field.name.name.clone());
+                    self.struct_fields.insert(def, fields.collect());
+                    let annotations = decl
+                        .fields
+                        .iter()
+                        .map(|field| field.ty.as_ref().map(Type::span));
+                    self.param_annotations.insert(def, annotations.collect());
+                    Scheme {
+                        vars: Vec::new(),
+                        generics,
+                        ty: Ty::Function {
+                            params,
+                            ret: Box::new(ret),
+                        },
+                    }
+                }
+                Item::Const(decl) => Scheme::monomorphic(self.annotation_type(decl.ty.as_ref())),
+                Item::Extend(_) | Item::Import(_) => continue,
+            };
+            self.schemes.insert(def, scheme);
+        }
+
+        // Methods are declared once the types they are added to are known
+        for stmt in stmts {
+            if let Stmt::Item(Item::Extend(decl)) = stmt {
+                self.declare_extend(decl);
+            }
+        }
+    }
+
+    /// Gives a type to the methods of the `extend` block and adds them to the method table of
+    /// the extended type. A method that the type already has, or a second `extend` block of the
+    /// type with the same type after `with`, is reported as a conflict.
+    fn declare_extend(&mut self, decl: &ExtendDecl) {
+        let extended_ty = self.lower_type(&decl.ty);
+        let Type::Path(extended_path) = &decl.ty;
+        let extended_name = &extended_path.segments[extended_path.segments.len() - 1].name;
+        let mut key = TypeKey::of(&extended_ty);
+
+        let with_def = (decl.with_type.as_ref()).and_then(|Type::Path(path)| {
+            let reference = self.resolution.reference(path.segments[0].span)?;
+            Some((reference.def, path.span))
+        });
+        if let (Some(type_key), Some((with_def, with_span))) = (key, with_def) {
+            if let Err(first_span) = self
+                .methods
+                .add_implementation(type_key, with_def, with_span)
+            {
+                let with_name = &self.resolution.definition(with_def).name;
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "conflicting implementations of `{with_name}` for type `{extended_name}`"
+                        ),
+                        with_span,
+                    )
+                    .with_code(ErrorCode(313))
+                    .with_primary_message(format!("conflicting implementation for `{extended_name}`"))
+                    .with_label(first_span, "first implementation here"),
+                );
+                // The methods are only checked, since they conflict with the first block
+                key = None;
+            }
+        }
+
+        for method in &decl.methods {
+            let Some(def) = self.resolution.declaration(method.name.span) else {
+                continue;
+            };
+            let mut scheme = self.declare_func(def, method);
+            let has_self = (method.params.first()).is_some_and(|param| param.name.name == "self");
+            if let (true, Ty::Function { params, .. }) = (has_self, &mut scheme.ty) {
+                if method.params[0].ty.is_none() {
+                    // Every method gets its own type arguments, such as the `T` of `Box[T]`, so
+                    // that it can be generalized over them
+                    let table = &mut self.table;
+                    params[0] = extended_ty.map_vars(&mut |_| table.new_var());
+                }
+            }
+            self.schemes.insert(def, scheme);
+
+            let Some(key) = key else {
+                continue;
+            };
+            let new_method = Method {
+                def,
+                span: method.name.span,
+                has_self,
+            };
+            if let Err(previous) = self.methods.add_method(key, &method.name.name, new_method) {
+                let name = &method.name.name;
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "duplicate definitions of method `{name}` for type `{extended_name}`"
+                        ),
+                        method.name.span,
+                    )
+                    .with_code(ErrorCode(314))
+                    .with_primary_message(format!("duplicate definition of `{name}`"))
+                    .with_label(previous.span, format!("other definition of `{name}`")),
+                );
+            }
+        }
+    }
+
+    /// Returns the scheme of the function from the annotations of its signature.
+    fn declare_func(&mut self, def: DefId, decl: &FuncDecl) -> Scheme {
+        let annotations = decl
+            .params
+            .iter()
+            .map(|param| param.ty.as_ref().map(Type::span));
+        self.param_annotations.insert(def, annotations.collect());
+        Scheme {
+            vars: Vec::new(),
+            generics: self.generic_params(&decl.generics),
+            ty: self.signature(decl),
+        }
+    }
+
+    fn signature(&mut self, decl: &FuncDecl) -> Ty {
+        let params = (decl.params.iter())
+            .map(|param| self.annotation_type(param.ty.as_ref()))
+            .collect();
+        Ty::Function {
+            params,
+            ret: Box::new(self.annotation_type(decl.return_type.as_ref())),
+        }
+    }
+
+    fn generic_params(&self, generics: &[GenericParam]) -> Vec<DefId> {
+        (generics.iter())
+            .filter_map(|param| self.resolution.declaration(param.name.span))
+            .collect()
+    }
+
+    fn generic_param_type(&self, def: DefId) -> Ty {
+        Ty::Param {
+            def,
+            name: self.resolution.definition(def).name.clone(),
+        }
+    }
+
+    /// Returns the type written in the annotation, or a new type variable if there is no
+    /// annotation.
+    fn annotation_type(&mut self, annotation: Option<&Type>) -> Ty {
+        match annotation {
+            Some(ty) => self.lower_type(ty),
+            None => self.table.new_var(),
+        }
+    }
+
+    /// Returns the type that a type annotation refers to, such as `List[Int]`. Generic types
+    /// without arguments, such as `List`, get new type variables as arguments.
+    fn lower_type(&mut self, ty: &Type) -> Ty {
+        let Type::Path(path) = ty;
+        let args = path
+            .args
+            .iter()
+            .map(|arg| self.lower_type(arg))
+            .collect::<Vec<_>>();
+        // Unresolved types are reported by name resolution
+        let Some(reference) = self.resolution.reference(path.segments[0].span) else {
+            return Ty::Error;
+        };
+        // Paths into modules, such as `std::Option`, aren't checked
+        if path.segments.len() > 1 {
+            return Ty::Error;
+        }
+        let definition = self.resolution.definition(reference.def);
+        let param_count = match definition.kind {
+            DefinitionKind::BuiltinType => match definition.name.as_str() {
+                "List" | "Option" => 1,
+                "Map" => 2,
+                _ => 0,
+            },
+            DefinitionKind::Struct => self.struct_generics[&reference.def].len(),
+            DefinitionKind::GenericParam => 0,
+            _ => {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("`{}` is not a type", definition.name), ty.span())
+                        .with_code(ErrorCode(306))
+                        .with_primary_message("not a type"),
+                );
+                return Ty::Error;
+            }
+        };
+        let mut args = if args.is_empty() {
+            (0..param_count).map(|_| self.table.new_var()).collect()
+        } else {
+            args
+        };
+        if args.len() != param_count {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "`{}` takes {} but {} {} supplied",
+                        definition.name,
+                        plural(param_count, "type argument"),
+                        plural(args.len(), "type argument"),
+                        if args.len() == 1 { "was" } else { "were" }
+                    ),
+                    ty.span(),
+                )
+                .with_code(ErrorCode(305))
+                .with_primary_message(format!("expected {}", plural(param_count, "type argument"))),
+            );
+            args = vec![Ty::Error; param_count];
+        }
+
+        let mut args = args.into_iter();
+        let mut next_arg = || Box::new(args.next().expect("the arguments are counted"));
+        match definition.kind {
+            DefinitionKind::Struct => Ty::Struct {
+                def: reference.def,
+                name: definition.name.clone(),
+                args: (0..param_count).map(|_| *next_arg()).collect(),
+            },
+            DefinitionKind::GenericParam => self.generic_param_type(reference.def),
+            _ => match definition.name.as_str() {
+                "Int" => Ty::Int,
+                "Float" => Ty::Float,
+                "Bool" => Ty::Bool,
+                "Char" => Ty::Char,
+                "String" => Ty::String,
+                "List" => Ty::List(next_arg()),
+                "Option" => Ty::Option(next_arg()),
+                "Map" => Ty::Map(next_arg(), next_arg()),
+                _ => Ty::Error,
+            },
+        }
+    }
+
+    fn check_item(&mut self, item: &Item) {
+        match item {
+            Item::Func(decl) => self.check_func(decl),
+            Item::Const(decl) => {
+                let ty = self.check_expr(&decl.value);
+                if let Some(def) = self.resolution.declaration(decl.name.span) {
+                    let const_ty = self.schemes[&def].ty.clone();
+                    let annotation = decl.ty.as_ref().map(Type::span);
+                    self.expect_annotated(&const_ty, &ty, decl.value.span(), annotation);
+                }
+            }
+            Item::Extend(decl) => {
+                for method in &decl.methods {
+                    self.check_func(method);
+                }
+            }
+            Item::Struct(_) | Item::Import(_) => {}
+        }
+    }
+
+    fn check_func(&mut self, decl: &FuncDecl) {
+        let def = self.resolution.declaration(decl.name.span);
+        let signature = match def.and_then(|def| self.schemes.get(&def)) {
+            Some(scheme) => scheme.ty.clone(),
+            // Functions defined twice have no definition, and their signature is only known here
+            None => self.signature(decl),
+        };
+        let Ty::Function { params, ret } = &signature else {
+            unreachable!("functions are declared with a function type")
+        };
+
+        let first_local = self.locals.len();
+        for (param, ty) in decl.params.iter().zip(params) {
+            self.define_local(&param.name, ty.clone());
+        }
+        let annotation = decl.return_type.as_ref().map(Type::span);
+        self.return_types.push(((**ret).clone(), annotation));
+        let body_ty = self.check_stmts(&decl.body.stmts);
+        self.expr_types.insert(decl.body.span, body_ty.clone());
+        self.expect_annotated(ret, &body_ty, block_value_span(&decl.body), annotation);
+        self.return_types.pop();
+        let locals = self.locals.drain(first_local..).collect::<HashSet<_>>();
+
+        if let Some(def) = def {
+            let generics = self.generic_params(&decl.generics);
+            let scheme = self.generalize(def, &signature, &locals, generics);
+            self.schemes.insert(def, scheme);
+        }
+    }
+
+    /// Returns the scheme of the function, quantified over the type variables that no other
+    /// definition uses. The locals of the function, which are checked, are ignored.
+    fn generalize(
+        &self,
+        def: DefId,
+        signature: &Ty,
+        locals: &HashSet<DefId>,
+        generics: Vec<DefId>,
+    ) -> Scheme {
+        let mut used_vars = HashSet::new();
+        for (other, scheme) in &self.schemes {
+            if *other == def || locals.contains(other) {
+                continue;
+            }
+            self.table.resolve(&scheme.ty).for_each_var(&mut |var| {
+                if !scheme.vars.contains(&var) {
+                    used_vars.insert(var);
+                }
+            });
+        }
+
+        let ty = self.table.resolve(signature);
+        let mut vars = Vec::new();
+        ty.for_each_var(&mut |var| {
+            if !used_vars.contains(&var) && !vars.contains(&var) {
+                vars.push(var);
+            }
+        });
+        Scheme { vars, generics, ty }
+    }
+
+    /// Returns the type of a use of the definition, with new variables for the variables and
+    /// generic parameters that the definition is generalized over.
+    fn instantiate(&mut self, def: DefId) -> Ty {
+        let Some(scheme) = self.schemes.get(&def) else {
+            return Ty::Error;
+        };
+        let (vars, generics) = (scheme.vars.clone(), scheme.generics.clone());
+        let ty = scheme.ty.clone();
+        let new_vars = (vars.iter())
+            .map(|var| (*var, self.table.new_var()))
+            .collect::<HashMap<_, _>>();
+        let new_params = (generics.iter())
+            .map(|param| (*param, self.table.new_var()))
+            .collect::<HashMap<_, _>>();
+        ty.map_vars(&mut |var| new_vars.get(&var).cloned().unwrap_or(Ty::Var(var)))
+            .substitute_params(&new_params)
+    }
+
+    fn define_local(&mut self, name: &Ident, ty: Ty) {
+        if let Some(def) = self.resolution.declaration(name.span) {
+            self.schemes.insert(def, Scheme::monomorphic(ty));
+            self.locals.push(def);
+        }
+    }
+
+    fn check_let(&mut self, stmt: &LetStmt) {
+        let ty = self.check_expr(&stmt.value);
+        let ty = match &stmt.ty {
+            Some(annotation) => {
+                let annotated_ty = self.lower_type(annotation);
+                let span = value_span(&stmt.value);
+                self.expect_annotated(&annotated_ty, &ty, span, Some(annotation.span()));
+                annotated_ty
+            }
+            None => ty,
+        };
+        self.check_pattern(&stmt.pattern, &ty);
+    }
+
+    /// Checks that the pattern can match values of the expected type, and gives types to the
+    /// variables that it binds.
+    fn check_pattern(&mut self, pattern: &Pattern, expected: &Ty) {
+        match pattern {
+            Pattern::Ident(name) => self.define_local(name, expected.clone()),
+            Pattern::Wildcard(_) | Pattern::Path(_) => {}
+            Pattern::Literal(literal) => {
+                let ty = self.literal_type(literal);
+                self.expect(expected, &ty, literal.span);
+            }
+            Pattern::Tuple(pattern) => {
+                let elements = (pattern.elements.iter())
+                    .map(|_| self.table.new_var())
+                    .collect::<Vec<_>>();
+                self.expect(expected, &Ty::Tuple(elements.clone()), pattern.span);
+                for (element, ty) in pattern.elements.iter().zip(&elements) {
+                    self.check_pattern(element, ty);
+                }
+            }
+            Pattern::Struct(pattern) => {
+                let struct_ty = self.struct_type(&pattern.path);
+                if let Some(struct_ty) = &struct_ty {
+                    self.expect(expected, struct_ty, pattern.span);
+                }
+                for field in &pattern.fields {
+                    let ty = struct_ty
+                        .as_ref()
+                        .and_then(|ty| self.field_type(ty, &field.name.name))
+                        .unwrap_or(Ty::Error);
+                    match &field.pattern {
+                        Some(pattern) => self.check_pattern(pattern, &ty),
+                        None => self.define_local(&field.name, ty),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the struct that the path refers to, if it refers to a struct, with new type
+    /// variables as arguments.
+    fn struct_type(&mut self, path: &[Ident]) -> Option<Ty> {
+        let def = self.struct_def(path)?;
+        let args = (self.struct_generics[&def].iter())
+            .map(|_| self.table.new_var())
+            .collect();
+        Some(Ty::Struct {
+            def,
+            name: self.resolution.definition(def).name.clone(),
+            args,
+        })
+    }
+
+    /// Returns the struct that the path refers to, if it refers to a struct.
+    fn struct_def(&self, path: &[Ident]) -> Option<DefId> {
+        let [name] = path else {
+            return None;
+        };
+        let reference = self.resolution.reference(name.span)?;
+        (self.resolution.definition(reference.def).kind == DefinitionKind::Struct)
+            .then_some(reference.def)
+    }
+
+    /// Reports the values that no arm of the `match` matches, and warns about the arms that are
+    /// never taken because the arms before them match every value that they match.
+    fn check_match_arms(&mut self, expr: &MatchExpr) {
+        let patterns = (expr.arms.iter())
+            .map(|arm| self.lower_pattern(&arm.pattern))
+            .collect::<Vec<_>>();
+
+        for index in unreachable_arms(&patterns) {
+            let mut diagnostic =
+                Diagnostic::warning("unreachable pattern", expr.arms[index].pattern.span())
+                    .with_code(ErrorCode(316))
+                    .with_primary_message("no value reaches this pattern");
+            if let Some(catch_all) = patterns[..index].iter().position(|pat| *pat == Pat::Wild) {
+                diagnostic = diagnostic.with_label(
+                    expr.arms[catch_all].pattern.span(),
+                    "this pattern matches any value",
+                );
+            }
+            self.diagnostics.push(diagnostic);
+        }
+
+        let missing = missing_patterns(&patterns);
+        if missing.is_empty() {
+            return;
+        }
+        // Only a few of the missing patterns are listed, since there can be many
+        const LISTED: usize = 3;
+        let mut listed = (missing.iter().take(LISTED))
+            .map(|pat| format!("`{}`", self.format_pat(pat)))
+            .collect::<Vec<_>>();
+        if missing.len() > LISTED {
+            listed.push(format!("{} more", missing.len() - LISTED));
+        }
+        let list = join_names(&listed);
+        let help = if missing == [Pat::Wild] {
+            "add an arm with the wildcard pattern `_`, which matches any value".to_string()
+        } else {
+            format!(
+                "add {} for the missing values, or an arm with the wildcard pattern `_`",
+                if missing.len() == 1 { "an arm" } else { "arms" }
+            )
+        };
+        self.diagnostics.push(
+            Diagnostic::error(
+                format!("non-exhaustive patterns: {list} not covered"),
+                expr.scrutinee.span(),
+            )
+            .with_code(ErrorCode(315))
+            .with_primary_message(format!(
+                "{} {list} not covered",
+                if missing.len() == 1 {
+                    "pattern"
+                } else {
+                    "patterns"
+                }
+            ))
+            .with_help(help),
+        );
+    }
+
+    /// Reduces the pattern to the values it matches, see [`Pat`].
+    fn lower_pattern(&self, pattern: &Pattern) -> Pat {
+        let ctor = |ctor, fields| Pat::Ctor(ctor, fields);
+        match pattern {
+            Pattern::Ident(_) | Pattern::Wildcard(_) => Pat::Wild,
+            Pattern::Literal(literal) => match literal.kind {
+                LiteralKind::True => ctor(Ctor::Bool(true), Vec::new()),
+                LiteralKind::False => ctor(Ctor::Bool(false), Vec::new()),
+                LiteralKind::None => ctor(Ctor::None, Vec::new()),
+                _ => ctor(Ctor::Constant(literal.text.clone()), Vec::new()),
+            },
+            Pattern::Path(path) => {
+                let segments = (path.segments.iter()).map(|segment| segment.name.as_str());
+                ctor(
+                    Ctor::Constant(segments.collect::<Vec<_>>().join("::")),
+                    Vec::new(),
+                )
+            }
+            Pattern::Tuple(tuple) => ctor(
+                Ctor::Tuple(tuple.elements.len()),
+                (tuple.elements.iter())
+                    .map(|element| self.lower_pattern(element))
+                    .collect(),
+            ),
+            Pattern::Struct(pattern) => {
+                // Patterns of unknown structs have already been reported
+                let Some(def) = self.struct_def(&pattern.path) else {
+                    return Pat::Wild;
+                };
+                let fields = (self.struct_fields[&def].iter())
+                    .map(|name| {
+                        (pattern.fields.iter())
+                            .find(|field| field.name.name == *name)
+                            .and_then(|field| field.pattern.as_ref())
+                            .map_or(Pat::Wild, |pattern| self.lower_pattern(pattern))
+                    })
+                    .collect::<Vec<_>>();
+                ctor(Ctor::Struct(def, fields.len()), fields)
+            }
+        }
+    }
+
+    /// Formats the pattern as code, such as `(true, _)`. The fields of structs that match any
+    /// value are left out, such as in `Point { x: 0, .. }`.
+    fn format_pat(&self, pat: &Pat) -> String {
+        let Pat::Ctor(ctor, fields) = pat else {
+            return "_".to_string();
+        };
+        match ctor {
+            Ctor::Bool(value) => value.to_string(),
+            Ctor::None => "none".to_string(),
+            Ctor::Constant(text) => text.clone(),
+            Ctor::Tuple(_) => {
+                let elements = fields.iter().map(|field| self.format_pat(field));
+                match fields.len() {
+                    1 => format!("({},)", self.format_pat(&fields[0])),
+                    _ => format!("({})", elements.collect::<Vec<_>>().join(", ")),
+                }
+            }
+            Ctor::Struct(def, _) => {
+                let name = &self.resolution.definition(*def).name;
+                let mut parts = (self.struct_fields[def].iter().zip(fields))
+                    .filter(|(_, field)| **field != Pat::Wild)
+                    .map(|(name, field)| format!("{name}: {}", self.format_pat(field)))
+                    .collect::<Vec<_>>();
+                if parts.len() < fields.len() {
+                    parts.push("..".to_string());
+                }
+                if parts.is_empty() {
+                    format!("{name} {{}}")
+                } else {
+                    format!("{name} {{ {} }}", parts.join(", "))
+                }
+            }
+        }
+    }
+
+    /// Returns the type of the field of a struct, which is the type of the parameter of its
+    /// constructor with the arguments of the struct.
+    fn field_type(&self, ty: &Ty, field: &str) -> Option<Ty> {
+        let Ty::Struct { def, args, .. } = self.table.shallow_resolve(ty) else {
+            return None;
+        };
+        let scheme = self.schemes.get(&def)?;
+        let Ty::Function { params, .. } = &scheme.ty else {
+            return None;
+        };
+        let index = (self.struct_fields[&def].iter()).position(|name| name == field)?;
+        let args = scheme.generics.iter().copied().zip(args).collect();
+        Some(params[index].substitute_params(&args))
+    }
+
+    fn check_expr(&mut self, expr: &Expr) -> Ty {
+        let ty = self.infer_expr(expr);
+        self.expr_types.insert(expr.span(), ty.clone());
+        ty
+    }
+
+    fn infer_expr(&mut self, expr: &Expr) -> Ty {
+        match expr {
+            Expr::Literal(literal) => self.literal_type(literal),
+            Expr::InterpolatedString(string) => {
+                for part in &string.parts {
+                    if let StringPart::Expr(expr) = part {
+                        self.check_expr(expr);
+                    }
+                }
+                Ty::String
+            }
+            Expr::Path(path) => self.path_type(&path.segments),
+            Expr::Paren(expr) => self.check_expr(&expr.expr),
+            Expr::Tuple(tuple) => Ty::Tuple(
+                (tuple.elements.iter())
+                    .map(|element| self.check_expr(element))
+                    .collect(),
+            ),
+            Expr::List(list) => {
+                let element_ty = self.table.new_var();
+                for element in &list.elements {
+                    let ty = self.check_expr(element);
+                    self.expect(&element_ty, &ty, element.span());
+                }
+                Ty::List(Box::new(element_ty))
+            }
+            Expr::Map(map) => {
+                let key_ty = self.table.new_var();
+                let value_ty = self.table.new_var();
+                for entry in &map.entries {
+                    let ty = self.check_expr(&entry.key);
+                    self.expect(&key_ty, &ty, entry.key.span());
+                    let ty = self.check_expr(&entry.value);
+                    self.expect(&value_ty, &ty, entry.value.span());
+                }
+                Ty::Map(Box::new(key_ty), Box::new(value_ty))
+            }
+            Expr::Struct(expr) => self.check_struct_expr(expr),
+            Expr::Block(block) => self.check_stmts(&block.stmts),
+            Expr::Call(call) => self.check_call(call),
+            Expr::Field(field) => {
+                let ty = self.check_expr(&field.expr);
+                self.field_access_type(&ty, &field.field)
+            }
+            Expr::Index(index) => {
+                let container_ty = self.check_expr(&index.expr);
+                let index_ty = self.check_expr(&index.index);
+                let index_span = index.index.span();
+                match self.table.shallow_resolve(&container_ty) {
+                    Ty::List(element_ty) => {
+                        self.expect(&Ty::Int, &index_ty, index_span);
+                        *element_ty
+                    }
+                    Ty::String => {
+                        self.expect(&Ty::Int, &index_ty, index_span);
+                        Ty::Char
+                    }
+                    Ty::Map(key_ty, value_ty) => {
+                        self.expect(&key_ty, &index_ty, index_span);
+                        *value_ty
+                    }
+                    _ => Ty::Error,
+                }
+            }
+            Expr::If(expr) => {
+                let condition_ty = self.check_expr(&expr.condition);
+                self.expect(&Ty::Bool, &condition_ty, expr.condition.span());
+                let then_ty = self.check_stmts(&expr.then_branch.stmts);
+                self.expr_types
+                    .insert(expr.then_branch.span, then_ty.clone());
+                match &expr.else_branch {
+                    Some(else_branch) => {
+                        let else_ty = self.check_expr(else_branch);
+                        self.expect(&then_ty, &else_ty, value_span(else_branch));
+                        then_ty
+                    }
+                    None => Ty::unit(),
+                }
+            }
+            Expr::For(expr) => {
+                let iterable_ty = self.check_expr(&expr.iterable);
+                let element_ty = self.element_type(&iterable_ty, expr.iterable.span());
+                self.check_pattern(&expr.pattern, &element_ty);
+                self.check_block(&expr.body);
+                Ty::unit()
+            }
+            Expr::While(expr) => {
+                let condition_ty = self.check_expr(&expr.condition);
+                self.expect(&Ty::Bool, &condition_ty, expr.condition.span());
+                self.check_block(&expr.body);
+                Ty::unit()
+            }
+            Expr::Match(expr) => {
+                let scrutinee_ty = self.check_expr(&expr.scrutinee);
+                let ty = self.table.new_var();
+                let mut has_pattern_errors = false;
+                for arm in &expr.arms {
+                    let error_count = self.diagnostics.len();
+                    self.check_pattern(&arm.pattern, &scrutinee_ty);
+                    has_pattern_errors |= self.diagnostics.len() > error_count;
+                    let arm_ty = self.check_expr(&arm.body);
+                    self.expect(&ty, &arm_ty, value_span(&arm.body));
+                }
+                // Patterns of the wrong type would be reported again as missing or unreachable
+                if !has_pattern_errors {
+                    self.check_match_arms(expr);
+                }
+                ty
+            }
+            Expr::Closure(closure) => self.check_closure(closure),
+            Expr::Break(expr) => {
+                if let Some(value) = &expr.value {
+                    self.check_expr(value);
+                }
+                self.table.new_var()
+            }
+            Expr::Continue(_) => self.table.new_var(),
+            Expr::Return(expr) => {
+                let (ty, span) = match &expr.value {
+                    Some(value) => (self.check_expr(value), value_span(value)),
+                    None => (Ty::unit(), expr.span),
+                };
+                // A `return` outside of functions is reported by the interpreter
+                if let Some((return_ty, annotation)) = self.return_types.last().cloned() {
+                    self.expect_annotated(&return_ty, &ty, span, annotation);
+                }
+                self.table.new_var()
+            }
+            Expr::Unary(expr) => {
+                let ty = self.check_expr(&expr.expr);
+                match expr.op {
+                    UnaryOp::Not => {
+                        self.expect(&Ty::Bool, &ty, expr.expr.span());
+                        Ty::Bool
+                    }
+                    UnaryOp::Negate => {
+                        let resolved = self.table.shallow_resolve(&ty);
+                        if !matches!(resolved, Ty::Int | Ty::Float | Ty::Var(_) | Ty::Error) {
+                            self.report_operator(expr.op.symbol(), &resolved, expr.op_span);
+                        }
+                        ty
+                    }
+                }
+            }
+            Expr::Binary(expr) => self.check_binary(expr),
+            Expr::Assign(expr) => {
+                let target_ty = self.check_expr(&expr.target);
+                let value_ty = self.check_expr(&expr.value);
+                let value_span = expr.value.span();
+                let value_ty = match expr.op {
+                    Some(op) => self.binary_type(
+                        op,
+                        expr.op_span,
+                        (&target_ty, expr.target.span()),
+                        (&value_ty, value_span),
+                    ),
+                    None => value_ty,
+                };
+                self.expect(&target_ty, &value_ty, value_span);
+                Ty::unit()
+            }
+            Expr::Missing(_) => Ty::Error,
+        }
+    }
+
+    fn check_block(&mut self, block: &BlockExpr) -> Ty {
+        let ty = self.check_stmts(&block.stmts);
+        self.expr_types.insert(block.span, ty.clone());
+        ty
+    }
+
+    fn literal_type(&mut self, literal: &LiteralExpr) -> Ty {
+        match literal.kind {
+            LiteralKind::Integer => Ty::Int,
+            LiteralKind::Float => Ty::Float,
+            LiteralKind::String => Ty::String,
+            LiteralKind::Char => Ty::Char,
+            LiteralKind::True | LiteralKind::False => Ty::Bool,
+            LiteralKind::None => Ty::Option(Box::new(self.table.new_var())),
+        }
+    }
+
+    fn path_type(&mut self, segments: &[Ident]) -> Ty {
+        let Some(reference) = self.resolution.reference(segments[0].span) else {
+            return Ty::Error;
+        };
+        let definition = self.resolution.definition(reference.def);
+        if let [_, method_name] = segments {
+            let key = match definition.kind {
+                DefinitionKind::Struct => Some(TypeKey::Struct(reference.def)),
+                DefinitionKind::BuiltinType => TypeKey::builtin(&definition.name),
+                _ => None,
+            };
+            if let Some(key) = key {
+                return match self.methods.method(key, &method_name.name) {
+                    Some(method) => {
+                        self.method_calls.insert(method_name.span, method.def);
+                        self.instantiate(method.def)
+                    }
+                    None if key.is_builtin() => Ty::Error,
+                    None => {
+                        let type_name = key.name(self.resolution).to_string();
+                        self.report_unknown_method(method_name, Some(key), &type_name, false);
+                        Ty::Error
+                    }
+                };
+            }
+        }
+        // Paths into modules, such as `math::max`, aren't checked
+        if segments.len() > 1 {
+            return Ty::Error;
+        }
+        match definition.kind {
+            DefinitionKind::BuiltinFunction => self.builtin_function_type(&definition.name),
+            DefinitionKind::Import | DefinitionKind::GenericParam | DefinitionKind::BuiltinType => {
+                Ty::Error
+            }
+            _ => self.instantiate(reference.def),
+        }
+    }
+
+    /// Checks the fields of a struct expression, such as `Point { x: 1, y }`, against the
+    /// declaration of the struct. Every field must be given once, with a value of the type of
+    /// its annotation.
+    fn check_struct_expr(&mut self, expr: &StructExpr) -> Ty {
+        let path_span = expr.path[0].span.to(expr.path[expr.path.len() - 1].span);
+        let Some(struct_ty) = self.struct_type(&expr.path) else {
+            for field in &expr.fields {
+                self.check_field_init(field);
+            }
+            // Unresolved names and paths into modules are left to name resolution
+            if let Some(reference) =
+                (self.resolution.reference(path_span)).filter(|_| expr.path.len() == 1)
+            {
+                let definition = self.resolution.definition(reference.def);
+                self.diagnostics.push(
+                    Diagnostic::error(format!("`{}` is not a struct", definition.name), path_span)
+                        .with_code(ErrorCode(311))
+                        .with_primary_message("not a struct"),
+                );
+            }
+            return Ty::Error;
+        };
+        let Ty::Struct { def, name, .. } = &struct_ty else {
+            unreachable!("struct paths have struct types")
+        };
+        let declared = self.struct_fields[def].clone();
+        let annotations = self.param_annotations[def].clone();
+
+        let given = (expr.fields.iter())
+            .map(|field| field.name.name.as_str())
+            .collect::<HashSet<_>>();
+        let mut initialized = HashMap::new();
+        for field in &expr.fields {
+            let ty = self.check_field_init(field);
+            let field_name = &field.name;
+            let Some(index) = declared.iter().position(|name| *name == field_name.name) else {
+                let available = (declared.iter())
+                    .map(String::as_str)
+                    .filter(|name| !given.contains(name));
+                let diagnostic = Diagnostic::error(
+                    format!("struct `{name}` has no field named `{}`", field_name.name),
+                    field_name.span,
+                )
+                .with_code(ErrorCode(308))
+                .with_primary_message("unknown field");
+                self.diagnostics
+                    .push(with_similar_field(diagnostic, field_name, available));
+                continue;
+            };
+            if let Some(&first_span) = initialized.get(field_name.name.as_str()) {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("field `{}` specified more than once", field_name.name),
+                        field_name.span,
+                    )
+                    .with_code(ErrorCode(309))
+                    .with_primary_message("used more than once")
+                    .with_label(first_span, format!("first use of `{}`", field_name.name)),
+                );
+                continue;
+            }
+            initialized.insert(field_name.name.as_str(), field_name.span);
+            let field_ty = (self.field_type(&struct_ty, &field_name.name))
+                .expect("declared fields have a type");
+            let span = field.value.as_ref().map_or(field_name.span, value_span);
+            self.expect_annotated(&field_ty, &ty, span, annotations[index]);
+        }
+
+        let missing = (declared.iter())
+            .filter(|name| !initialized.contains_key(name.as_str()))
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let list = join_names(&missing);
+            self.diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "missing {} {list} in initializer of `{name}`",
+                        if missing.len() == 1 {
+                            "field"
+                        } else {
+                            "fields"
+                        }
+                    ),
+                    path_span,
+                )
+                .with_code(ErrorCode(307))
+                .with_primary_message(format!("missing {list}")),
+            );
+        }
+        struct_ty
+    }
+
+    /// Checks the value of a field of a struct expression, which is the variable of the same
+    /// name for a field without value, such as `y` in `Point { x: 1, y }`.
+    fn check_field_init(&mut self, field: &FieldInit) -> Ty {
+        match &field.value {
+            Some(value) => self.check_expr(value),
+            None => {
+                let ty = self.path_type(std::slice::from_ref(&field.name));
+                self.expr_types.insert(field.name.span, ty.clone());
+                ty
+            }
+        }
+    }
+
+    /// Returns the type of the field of a value, such as `point.x`, or reports that values of
+    /// its type don't have the field. Values whose type isn't known yet aren't reported.
+    fn field_access_type(&mut self, receiver_ty: &Ty, field: &Ident) -> Ty {
+        if let Some(ty) = self.field_type(receiver_ty, &field.name) {
+            return ty;
+        }
+        let receiver_ty = self.table.resolve(receiver_ty);
+        if matches!(receiver_ty, Ty::Var(_) | Ty::Error) {
+            return Ty::Error;
+        }
+        let mut diagnostic = Diagnostic::error(
+            format!("no field `{}` on type `{receiver_ty}`", field.name),
+            field.span,
+        )
+        .with_code(ErrorCode(310))
+        .with_primary_message("unknown field");
+        if let Ty::Struct { def, .. } = &receiver_ty {
+            let fields = self.struct_fields[def].iter().map(String::as_str);
+            diagnostic = with_similar_field(diagnostic, field, fields);
+        }
+        self.diagnostics.push(diagnostic);
+        Ty::Error
+    }
+
+    fn builtin_function_type(&mut self, name: &str) -> Ty {
+        let (params, ret) = match name {
+            "assert" => (vec![Ty::Bool], Ty::unit()),
+            "len" => (vec![self.table.new_var()], Ty::Int),
+            "print" => (vec![self.table.new_var()], Ty::unit()),
+            "range" => (vec![Ty::Int, Ty::Int], Ty::List(Box::new(Ty::Int))),
+            _ => return Ty::Error,
+        };
+        Ty::Function {
+            params,
+            ret: Box::new(ret),
+        }
+    }
+
+    /// Returns the type of the elements of a value used in a `for` loop.
+    fn element_type(&mut self, iterable_ty: &Ty, span: Span) -> Ty {
+        match self.table.shallow_resolve(iterable_ty) {
+            Ty::List(element_ty) => *element_ty,
+            Ty::Map(key_ty, value_ty) => Ty::Tuple(vec![*key_ty, *value_ty]),
+            Ty::String => Ty::Char,
+            Ty::Var(_) => {
+                let element_ty = self.table.new_var();
+                self.expect(&Ty::List(Box::new(element_ty.clone())), iterable_ty, span);
+                element_ty
+            }
+            Ty::Error => Ty::Error,
+            ty => {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("`{ty}` is not iterable"), span)
+                        .with_code(ErrorCode(304))
+                        .with_primary_message("cannot be used in a `for` loop")
+                        .with_help("`for` loops accept lists, maps and strings"),
+                );
+                Ty::Error
+            }
+        }
+    }
+
+    fn check_call(&mut self, call: &CallExpr) -> Ty {
+        let callee_ty = match &*call.callee {
+            Expr::Field(field) => {
+                let receiver_ty = self.check_expr(&field.expr);
+                // A call of a field that isn't a function field, such as `xs.len()`, is a method
+                // call
+                let Some(ty) = self.field_type(&receiver_ty, &field.field.name) else {
+                    return self.check_method_call(call, field, &receiver_ty);
+                };
+                self.expr_types.insert(field.span, ty.clone());
+                ty
+            }
+            callee => self.check_expr(callee),
+        };
+        match self.table.shallow_resolve(&callee_ty) {
+            Ty::Function { params, ret } => {
+                let annotations = self.param_annotations(&call.callee);
+                self.check_args(call, "function", &params, &annotations);
+                *ret
+            }
+            Ty::Var(_) => {
+                let params = call.args.iter().map(|arg| self.check_expr(arg)).collect();
+                let ret = self.table.new_var();
+                let function_ty = Ty::Function {
+                    params,
+                    ret: Box::new(ret.clone()),
+                };
+                self.expect(&function_ty, &callee_ty, call.callee.span());
+                ret
+            }
+            ty => {
+                for arg in &call.args {
+                    self.check_expr(arg);
+                }
+                if ty != Ty::Error {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!("expected function, found `{}`", self.table.resolve(&ty)),
+                            call.callee.span(),
+                        )
+                        .with_code(ErrorCode(302))
+                        .with_primary_message("call expression requires a function"),
+                    );
+                }
+                Ty::Error
+            }
+        }
+    }
+
+    /// Checks the arguments of a call against the parameters of the function or method, or
+    /// reports that their number doesn't match.
+    fn check_args(
+        &mut self,
+        call: &CallExpr,
+        callee_kind: &str,
+        params: &[Ty],
+        annotations: &[Option<Span>],
+    ) {
+        if params.len() != call.args.len() {
+            for arg in &call.args {
+                self.check_expr(arg);
+            }
+            self.diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "this {callee_kind} takes {} but {} {} supplied",
+                        plural(params.len(), "argument"),
+                        plural(call.args.len(), "argument"),
+                        if call.args.len() == 1 { "was" } else { "were" }
+                    ),
+                    call.span,
+                )
+                .with_code(ErrorCode(301))
+                .with_primary_message(format!("expected {}", plural(params.len(), "argument"))),
+            );
+            return;
+        }
+        for (index, (arg, param_ty)) in call.args.iter().zip(params).enumerate() {
+            let arg_ty = self.check_expr(arg);
+            let annotation = annotations.get(index).copied().flatten();
+            self.expect_annotated(param_ty, &arg_ty, arg.span(), annotation);
+        }
+    }
+
+    /// Checks a call of a method on a value, such as `point.show()`, which calls the method of
+    /// that name of the type of the value with the value as `self`.
+    fn check_method_call(&mut self, call: &CallExpr, field: &FieldExpr, receiver_ty: &Ty) -> Ty {
+        let receiver_ty = self.table.resolve(receiver_ty);
+        let name = &field.field;
+        // The type of the value isn't known, or it has already been reported
+        let Some(key) = TypeKey::of(&receiver_ty) else {
+            for arg in &call.args {
+                self.check_expr(arg);
+            }
+            if !matches!(receiver_ty, Ty::Var(_) | Ty::Error) {
+                self.report_unknown_method(name, None, &receiver_ty.to_string(), true);
+            }
+            return Ty::Error;
+        };
+        let method = self.methods.method(key, &name.name);
+        let Some(method) = method.filter(|method| method.has_self) else {
+            for arg in &call.args {
+                self.check_expr(arg);
+            }
+            if method.is_some() || !key.is_builtin() {
+                self.report_unknown_method(name, Some(key), &receiver_ty.to_string(), true);
+            }
+            return Ty::Error;
+        };
+
+        self.method_calls.insert(name.span, method.def);
+        let method_ty = self.instantiate(method.def);
+        self.expr_types.insert(field.span, method_ty.clone());
+        let Ty::Function { params, ret } = method_ty else {
+            unreachable!("methods are declared with a function type")
+        };
+        self.expect(&params[0], &receiver_ty, field.expr.span());
+        let annotations = (self.param_annotations.get(&method.def))
+            .map(|annotations| annotations[1..].to_vec())
+            .unwrap_or_default();
+        self.check_args(call, "method", &params[1..], &annotations);
+        *ret
+    }
+
+    /// Reports a method call or a path, such as `Point::origin`, whose type has no method of
+    /// that name, suggesting the methods with a similar name.
+    fn report_unknown_method(
+        &mut self,
+        name: &Ident,
+        key: Option<TypeKey>,
+        type_name: &str,
+        is_method_call: bool,
+    ) {
+        let mut diagnostic = Diagnostic::error(
+            format!("no method named `{}` on type `{type_name}`", name.name),
+            name.span,
+        )
+        .with_code(ErrorCode(312))
+        .with_primary_message("method not found");
+        let Some(key) = key else {
+            self.diagnostics.push(diagnostic);
+            return;
+        };
+        if is_method_call && self.methods.method(key, &name.name).is_some() {
+            diagnostic = diagnostic.with_help(format!(
+                "`{}` has no `self` parameter, so it's called on the type, as in `{}::{}()`",
+                name.name,
+                key.name(self.resolution),
+                name.name
+            ));
+        }
+        let mut similar = similar_names(&name.name, self.methods.method_names(key));
+        match similar.as_slice() {
+            [] => {}
+            [similar_name] => {
+                diagnostic = diagnostic.with_suggestion(Suggestion::new(
+                    format!("there is a method with a similar name: `{similar_name}`"),
+                    name.span,
+                    *similar_name,
+                    Applicability::MaybeIncorrect,
+                ));
+            }
+            _ => {
+                let names = (similar.drain(..))
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>();
+                diagnostic = diagnostic.with_help(format!(
+                    "there are methods with similar names: {}",
+                    join_names(&names)
+                ));
+            }
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Returns the spans of the annotations of the parameters of the function that the callee
+    /// refers to, if it refers to a function, a struct or a method of a type.
+    fn param_annotations(&self, callee: &Expr) -> Vec<Option<Span>> {
+        let Expr::Path(path) = callee else {
+            return Vec::new();
+        };
+        let def = match path.segments.as_slice() {
+            [name] => self
+                .resolution
+                .reference(name.span)
+                .map(|reference| reference.def),
+            [_, method_name] => self.method_calls.get(&method_name.span).copied(),
+            _ => None,
+        };
+        (def.and_then(|def| self.param_annotations.get(&def)))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn check_closure(&mut self, closure: &ClosureExpr) -> Ty {
+        let params = (closure.params.iter())
+            .map(|param| {
+                let ty = self.annotation_type(param.ty.as_ref());
+                self.define_local(&param.name, ty.clone());
+                ty
+            })
+            .collect();
+        let ret = self.annotation_type(closure.return_type.as_ref());
+        let annotation = closure.return_type.as_ref().map(Type::span);
+        self.return_types.push((ret.clone(), annotation));
+        let body_ty = self.check_expr(&closure.body);
+        self.expect_annotated(&ret, &body_ty, value_span(&closure.body), annotation);
+        self.return_types.pop();
+        Ty::Function {
+            params,
+            ret: Box::new(ret),
+        }
+    }
+
+    fn check_binary(&mut self, expr: &BinaryExpr) -> Ty {
+        let lhs_ty = self.check_expr(&expr.lhs);
+        let rhs_ty = self.check_expr(&expr.rhs);
+        self.binary_type(
+            expr.op,
+            expr.op_span,
+            (&lhs_ty, expr.lhs.span()),
+            (&rhs_ty, expr.rhs.span()),
+        )
+    }
+
+    /// Checks the types of the operands of a binary operator, and returns the type of the
+    /// result.
+    fn binary_type(
+        &mut self,
+        op: BinaryOp,
+        op_span: Span,
+        (lhs_ty, lhs_span): (&Ty, Span),
+        (rhs_ty, rhs_span): (&Ty, Span),
+    ) -> Ty {
+        match op {
+            BinaryOp::Add
+            | BinaryOp::Subtract
+            | BinaryOp::Multiply
+            | BinaryOp::Divide
+            | BinaryOp::Modulo
+            | BinaryOp::Power => {
+                let supports_op = |ty: &Ty| match ty {
+                    Ty::Int | Ty::Float | Ty::Var(_) | Ty::Error => true,
+                    // Strings and lists are concatenated
+                    Ty::String | Ty::List(_) => op == BinaryOp::Add,
+                    _ => false,
+                };
+                if !self.check_operand(op, op_span, lhs_ty, supports_op) {
+                    return Ty::Error;
+                }
+                self.expect(lhs_ty, rhs_ty, rhs_span);
+                self.check_operand(op, op_span, lhs_ty, supports_op);
+                lhs_ty.clone()
+            }
+            BinaryOp::Equal | BinaryOp::NotEqual => {
+                self.expect(lhs_ty, rhs_ty, rhs_span);
+                Ty::Bool
+            }
+            BinaryOp::LessThan
+            | BinaryOp::GreaterThan
+            | BinaryOp::LessThanEqual
+            | BinaryOp::GreaterThanEqual => {
+                let supports_op = |ty: &Ty| {
+                    matches!(
+                        ty,
+                        Ty::Int | Ty::Float | Ty::String | Ty::Char | Ty::Var(_) | Ty::Error
+                    )
+                };
+                if self.check_operand(op, op_span, lhs_ty, supports_op) {
+                    self.expect(lhs_ty, rhs_ty, rhs_span);
+                    self.check_operand(op, op_span, lhs_ty, supports_op);
+                }
+                Ty::Bool
+            }
+            BinaryOp::And | BinaryOp::Or => {
+                self.expect(&Ty::Bool, lhs_ty, lhs_span);
+                self.expect(&Ty::Bool, rhs_ty, rhs_span);
+                Ty::Bool
+            }
+            BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight => {
+                self.expect(&Ty::Int, lhs_ty, lhs_span);
+                self.expect(&Ty::Int, rhs_ty, rhs_span);
+                Ty::Int
+            }
+            BinaryOp::Range | BinaryOp::RangeInclusive => {
+                self.expect(&Ty::Int, lhs_ty, lhs_span);
+                self.expect(&Ty::Int, rhs_ty, rhs_span);
+                Ty::List(Box::new(Ty::Int))
+            }
+        }
+    }
+
+    /// Reports the operator if the function returns `false` for the type of its left operand.
+    /// The operand is checked before and after the right operand is unified with it, so that
+    /// `true + 1` is reported once, for the operator.
+    fn check_operand(
+        &mut self,
+        op: BinaryOp,
+        op_span: Span,
+        lhs_ty: &Ty,
+        supports_op: impl Fn(&Ty) -> bool,
+    ) -> bool {
+        let ty = self.table.shallow_resolve(lhs_ty);
+        if supports_op(&ty) {
+            return true;
+        }
+        self.report_operator(op.symbol(), &ty, op_span);
+        false
+    }
+
+    /// Makes the found type the same as the expected type, or reports a mismatch at the span.
+    fn expect(&mut self, expected: &Ty, found: &Ty, span: Span) {
+        self.expect_annotated(expected, found, span, None);
+    }
+
+    /// Like [`Checker::expect`], but a mismatch also points to the type annotation that gives
+    /// the expected type.
+    fn expect_annotated(
+        &mut self,
+        expected: &Ty,
+        found: &Ty,
+        span: Span,
+        annotation: Option<Span>,
+    ) {
+        let full_expected = self.table.resolve(expected);
+        let full_found = self.table.resolve(found);
+        let Err(mismatch) = self.table.unify(expected, found) else {
+            return;
+        };
+        let mut diagnostic = Diagnostic::error("mismatched types", span)
+            .with_code(ErrorCode(300))
+            .with_primary_message(format!(
+                "expected `{}`, found `{}`",
+                mismatch.expected, mismatch.found
+            ));
+        // Show the whole types when the mismatch is inside them, such as in `List[Int]`
+        if (&mismatch.expected, &mismatch.found) != (&full_expected, &full_found) {
+            diagnostic = diagnostic
+                .with_note(format!("expected type `{full_expected}`"))
+                .with_note(format!("found type `{full_found}`"));
+        }
+        if let Some(annotation) = annotation {
+            diagnostic = diagnostic.with_label(annotation, "expected due to this");
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn report_operator(&mut self, symbol: &str, ty: &Ty, span: Span) {
+        let ty = self.table.resolve(ty);
+        self.diagnostics.push(
+            Diagnostic::error(
+                format!("cannot apply operator `{symbol}` to type `{ty}`"),
+                span,
+            )
+            .with_code(ErrorCode(303)),
+        );
+    }
+}
+
+/// Returns `true` if the expression leaves its block, such as `return a`.
+fn diverges(expr: &Expr) -> bool {
+    matches!(expr, Expr::Return(_) | Expr::Break(_) | Expr::Continue(_))
+}
+
+/// Returns the span of the expression that gives its value to the expression, such as the last
+/// expression of a block, to report a mismatch where the value is.
+fn value_span(expr: &Expr) -> Span {
+    match expr {
+        Expr::Block(block) => block_value_span(block),
+        _ => expr.span(),
+    }
+}
+
+fn block_value_span(block: &BlockExpr) -> Span {
+    match block.stmts.last() {
+        Some(Stmt::Expr(stmt)) if !stmt.has_semicolon => value_span(&stmt.expr),
+        _ => block.span,
+    }
+}
+
+/// Adds a suggestion to replace the unknown field with a field of a similar name, if any, or
+/// else lists the fields that could be meant.
+fn with_similar_field<'a>(
+    diagnostic: Diagnostic,
+    field: &Ident,
+    candidates: impl Iterator<Item = &'a str>,
+) -> Diagnostic {
+    let candidates = candidates.collect::<Vec<_>>();
+    if let Some(similar_name) = similar_names(&field.name, candidates.iter().copied()).first() {
+        diagnostic.with_suggestion(Suggestion::new(
+            format!("a field with a similar name exists: `{similar_name}`"),
+            field.span,
+            *similar_name,
+            Applicability::MaybeIncorrect,
+        ))
+    } else if candidates.is_empty() {
+        diagnostic
+    } else {
+        let candidates = (candidates.iter())
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>();
+        diagnostic.with_help(format!("available fields are: {}", candidates.join(", ")))
+    }
+}
+
+/// Joins the names into a list, such as `` `a`, `b` and `c` ``.
+fn join_names(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [names @ .., last] => format!("{} and {last}", names.join(", ")),
+    }
+}
+
+/// Formats the count with the word, pluralized if needed, such as `2 arguments`.
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{count} {word}")
+    } else {
+        format!("{count} {word}s")
+    }
+}
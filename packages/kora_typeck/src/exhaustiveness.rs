@@ -0,0 +1,184 @@
+use std::iter;
+
+use kora_resolve::DefId;
+
+/// A pattern reduced to what matters to know which values it matches: a constructor of values
+/// applied to patterns of its fields, or a wildcard, which variables are too.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+}
+
+/// A kind of values that a pattern can match, such as `true` or the tuples of two elements.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Ctor {
+    Bool(bool),
+    /// The literal `none`. The other values of an option can only be matched by a wildcard.
+    None,
+    /// A literal of a type with too many values to list them, such as `1` or `"a"`, or a path,
+    /// such as `Shape::Circle`. Constants are the same if their text is the same.
+    Constant(String),
+    /// The tuples with that number of elements.
+    Tuple(usize),
+    /// A struct, with the number of its fields, whose patterns are in the order of the
+    /// declaration of the struct.
+    Struct(DefId, usize),
+}
+
+impl Ctor {
+    fn arity(&self) -> usize {
+        match self {
+            Self::Tuple(arity) | Self::Struct(_, arity) => *arity,
+            Self::Bool(_) | Self::None | Self::Constant(_) => 0,
+        }
+    }
+}
+
+/// Returns patterns of the values that none of the patterns of the arms match, which is empty
+/// if the arms are exhaustive.
+pub(crate) fn missing_patterns(arms: &[Pat]) -> Vec<Pat> {
+    let rows = arms.iter().map(|arm| vec![arm.clone()]).collect::<Vec<_>>();
+    (witnesses(&rows, &[Pat::Wild]).into_iter())
+        .map(|mut witness| witness.remove(0))
+        .collect()
+}
+
+/// Returns the indices of the arms whose pattern only matches values that the arms before it
+/// match, so that the arm is never taken.
+pub(crate) fn unreachable_arms(arms: &[Pat]) -> Vec<usize> {
+    let mut rows = Vec::new();
+    let mut unreachable = Vec::new();
+    for (index, arm) in arms.iter().enumerate() {
+        let row = vec![arm.clone()];
+        if witnesses(&rows, &row).is_empty() {
+            unreachable.push(index);
+        }
+        rows.push(row);
+    }
+    unreachable
+}
+
+/// The usefulness algorithm of "Warnings for pattern matching" (Maranget, 2007): returns the
+/// values that `v` matches but no row matches, as vectors of patterns that are as long as `v`
+/// and the rows. `v` is useful after the rows if there is any such value.
+fn witnesses(rows: &[Vec<Pat>], v: &[Pat]) -> Vec<Vec<Pat>> {
+    let Some((head, rest)) = v.split_first() else {
+        // The empty vector matches the one value, which a row matches too if there is a row
+        return if rows.is_empty() {
+            vec![Vec::new()]
+        } else {
+            Vec::new()
+        };
+    };
+
+    match head {
+        Pat::Ctor(ctor, fields) => {
+            let v = fields.iter().chain(rest).cloned().collect::<Vec<_>>();
+            (witnesses(&specialize(rows, ctor), &v).into_iter())
+                .map(|witness| rebuild(ctor, witness))
+                .collect()
+        }
+        Pat::Wild => {
+            let heads = head_ctors(rows);
+            match complete_signature(&heads) {
+                // Every constructor is matched by some row, so the values that the rows miss
+                // are among the values of each constructor
+                Some(ctors) => {
+                    let mut found = Vec::new();
+                    for ctor in &ctors {
+                        let v = iter::repeat_n(Pat::Wild, ctor.arity())
+                            .chain(rest.iter().cloned())
+                            .collect::<Vec<_>>();
+                        let specialized = specialize(rows, ctor);
+                        found.extend(
+                            (witnesses(&specialized, &v).into_iter())
+                                .map(|witness| rebuild(ctor, witness)),
+                        );
+                    }
+                    found
+                }
+                // A constructor that no row matches is only matched by the rows that start with
+                // a wildcard
+                None => {
+                    let default = (rows.iter())
+                        .filter(|row| row[0] == Pat::Wild)
+                        .map(|row| row[1..].to_vec())
+                        .collect::<Vec<_>>();
+                    let missing = missing_heads(&heads);
+                    (witnesses(&default, rest).into_iter())
+                        .flat_map(|witness| {
+                            (missing.iter()).map(move |head| {
+                                iter::once(head.clone()).chain(witness.clone()).collect()
+                            })
+                        })
+                        .collect()
+                }
+            }
+        }
+    }
+}
+
+/// Returns the rows that match values of the constructor, with the patterns of its fields in
+/// place of their first pattern.
+fn specialize(rows: &[Vec<Pat>], ctor: &Ctor) -> Vec<Vec<Pat>> {
+    (rows.iter())
+        .filter_map(|row| {
+            let fields = match &row[0] {
+                Pat::Wild => vec![Pat::Wild; ctor.arity()],
+                Pat::Ctor(row_ctor, fields) if row_ctor == ctor => fields.clone(),
+                Pat::Ctor(..) => return None,
+            };
+            Some(fields.into_iter().chain(row[1..].iter().cloned()).collect())
+        })
+        .collect()
+}
+
+/// Turns a witness of a specialized matrix, which starts with the fields of the constructor,
+/// back into a witness that starts with the constructor.
+fn rebuild(ctor: &Ctor, mut witness: Vec<Pat>) -> Vec<Pat> {
+    let rest = witness.split_off(ctor.arity());
+    iter::once(Pat::Ctor(ctor.clone(), witness))
+        .chain(rest)
+        .collect()
+}
+
+/// Returns the constructors of the first patterns of the rows, without duplicates.
+fn head_ctors(rows: &[Vec<Pat>]) -> Vec<Ctor> {
+    let mut ctors = Vec::new();
+    for row in rows {
+        if let Pat::Ctor(ctor, _) = &row[0] {
+            if !ctors.contains(ctor) {
+                ctors.push(ctor.clone());
+            }
+        }
+    }
+    ctors
+}
+
+/// Returns every constructor of the type of the constructors if they are all there, such as
+/// `true` and `false`, or a tuple or a struct, which are the only constructor of their type.
+fn complete_signature(heads: &[Ctor]) -> Option<Vec<Ctor>> {
+    match heads.first()? {
+        ctor @ (Ctor::Tuple(_) | Ctor::Struct(..)) => Some(vec![ctor.clone()]),
+        Ctor::Bool(_) => {
+            let all = vec![Ctor::Bool(true), Ctor::Bool(false)];
+            all.iter().all(|ctor| heads.contains(ctor)).then_some(all)
+        }
+        Ctor::None | Ctor::Constant(_) => None,
+    }
+}
+
+/// Returns patterns for the constructors that are missing from an incomplete signature, or a
+/// wildcard if they can't be written as patterns, such as the numbers other than the constants.
+fn missing_heads(heads: &[Ctor]) -> Vec<Pat> {
+    if let Some(Ctor::Bool(_)) = heads.first() {
+        [true, false]
+            .into_iter()
+            .filter(|value| !heads.contains(&Ctor::Bool(*value)))
+            .map(|value| Pat::Ctor(Ctor::Bool(value), Vec::new()))
+            .collect()
+    } else {
+        vec![Pat::Wild]
+    }
+}
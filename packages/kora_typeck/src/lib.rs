@@ -0,0 +1,13 @@
+//! Type checking, which infers the type of every expression of a resolved AST and reports the
+//! expressions whose types don't fit together.
+
+mod checker;
+mod exhaustiveness;
+mod methods;
+mod table;
+mod ty;
+mod typing;
+
+pub use checker::check;
+pub use ty::{Ty, TyVar};
+pub use typing::Typing;
@@ -0,0 +1,145 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use kora_lexer::Span;
+use kora_resolve::{DefId, Resolution};
+
+use crate::ty::Ty;
+
+/// A type without its type arguments, to which `extend` blocks add methods, such as `List` for
+/// `List[Int]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum TypeKey {
+    Int,
+    Float,
+    Bool,
+    Char,
+    String,
+    List,
+    Map,
+    Option,
+    Struct(DefId),
+}
+
+impl TypeKey {
+    /// Returns the key of the type, or `None` for the types that can't be extended, such as
+    /// tuples, and the types that aren't known.
+    pub(crate) fn of(ty: &Ty) -> Option<Self> {
+        Some(match ty {
+            Ty::Int => Self::Int,
+            Ty::Float => Self::Float,
+            Ty::Bool => Self::Bool,
+            Ty::Char => Self::Char,
+            Ty::String => Self::String,
+            Ty::List(_) => Self::List,
+            Ty::Map(..) => Self::Map,
+            Ty::Option(_) => Self::Option,
+            Ty::Struct { def, .. } => Self::Struct(*def),
+            Ty::Tuple(_) | Ty::Function { .. } | Ty::Param { .. } | Ty::Var(_) | Ty::Error => {
+                return None
+            }
+        })
+    }
+
+    /// Returns the key of the builtin type with the name, such as `Int`.
+    pub(crate) fn builtin(name: &str) -> Option<Self> {
+        Some(match name {
+            "Int" => Self::Int,
+            "Float" => Self::Float,
+            "Bool" => Self::Bool,
+            "Char" => Self::Char,
+            "String" => Self::String,
+            "List" => Self::List,
+            "Map" => Self::Map,
+            "Option" => Self::Option,
+            _ => return None,
+        })
+    }
+
+    /// Whether the type is builtin. Builtin types also have methods that aren't declared by
+    /// `extend` blocks, such as `len`, so a method that the table lacks may still exist.
+    pub(crate) fn is_builtin(self) -> bool {
+        !matches!(self, Self::Struct(_))
+    }
+
+    /// Returns the name of the type, such as `List` or `Point`.
+    pub(crate) fn name(self, resolution: &Resolution) -> &str {
+        match self {
+            Self::Int => "Int",
+            Self::Float => "Float",
+            Self::Bool => "Bool",
+            Self::Char => "Char",
+            Self::String => "String",
+            Self::List => "List",
+            Self::Map => "Map",
+            Self::Option => "Option",
+            Self::Struct(def) => &resolution.definition(def).name,
+        }
+    }
+}
+
+/// A method that an `extend` block adds to a type.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Method {
+    pub(crate) def: DefId,
+    /// Span of the name of the method.
+    pub(crate) span: Span,
+    /// Whether the first parameter is `self`, so that the method is called on a value, such as
+    /// `point.show()`, and not only on the type, such as `Point::origin()`.
+    pub(crate) has_self: bool,
+}
+
+/// The methods of every type, and the types after `with` that every type is extended with.
+///
+/// A type has a single method of each name, whether it comes from an `extend` block with or
+/// without `with`, so that `value.method()` always refers to one method.
+#[derive(Debug, Default)]
+pub(crate) struct MethodTable {
+    methods: HashMap<TypeKey, HashMap<String, Method>>,
+    /// The span of the type after `with` in the `extend` block of every type and `with` type.
+    implementations: HashMap<(TypeKey, DefId), Span>,
+}
+
+impl MethodTable {
+    /// Adds the method to the type, or returns the method of the same name that the type
+    /// already has.
+    pub(crate) fn add_method(
+        &mut self,
+        key: TypeKey,
+        name: &str,
+        method: Method,
+    ) -> Result<(), Method> {
+        match self.methods.entry(key).or_default().entry(name.to_string()) {
+            Entry::Occupied(entry) => Err(*entry.get()),
+            Entry::Vacant(entry) => {
+                entry.insert(method);
+                Ok(())
+            }
+        }
+    }
+
+    /// Records that the type is extended with the type `with`, whose name is at `span`, or
+    /// returns the span of the previous `extend` block of the type with it.
+    pub(crate) fn add_implementation(
+        &mut self,
+        key: TypeKey,
+        with: DefId,
+        span: Span,
+    ) -> Result<(), Span> {
+        match self.implementations.entry((key, with)) {
+            Entry::Occupied(entry) => Err(*entry.get()),
+            Entry::Vacant(entry) => {
+                entry.insert(span);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn method(&self, key: TypeKey, name: &str) -> Option<Method> {
+        self.methods.get(&key)?.get(name).copied()
+    }
+
+    /// Returns the names of the methods of the type, in no particular order.
+    pub(crate) fn method_names(&self, key: TypeKey) -> impl Iterator<Item = &str> {
+        (self.methods.get(&key).into_iter()).flat_map(|methods| methods.keys().map(String::as_str))
+    }
+}
@@ -0,0 +1,103 @@
+use crate::ty::{Ty, TyVar};
+
+/// The types bound to the type variables during inference.
+#[derive(Debug, Default)]
+pub(crate) struct InferenceTable {
+    bindings: Vec<Option<Ty>>,
+}
+
+/// Two types that can't be unified, with their variables replaced by their bindings.
+#[derive(Debug)]
+pub(crate) struct Mismatch {
+    pub(crate) expected: Ty,
+    pub(crate) found: Ty,
+}
+
+impl InferenceTable {
+    pub(crate) fn new_var(&mut self) -> Ty {
+        self.bindings.push(None);
+        Ty::Var(TyVar(self.bindings.len() as u32 - 1))
+    }
+
+    /// Follows the bindings of the type until it isn't a bound variable.
+    pub(crate) fn shallow_resolve(&self, ty: &Ty) -> Ty {
+        let mut ty = ty.clone();
+        while let Ty::Var(var) = ty {
+            match &self.bindings[var.0 as usize] {
+                Some(bound) => ty = bound.clone(),
+                None => break,
+            }
+        }
+        ty
+    }
+
+    /// Replaces the bound variables of the type by their bindings, recursively.
+    pub(crate) fn resolve(&self, ty: &Ty) -> Ty {
+        ty.map_vars(&mut |var| match &self.bindings[var.0 as usize] {
+            Some(bound) => self.resolve(bound),
+            None => Ty::Var(var),
+        })
+    }
+
+    /// Binds variables so that both types are the same. If they can't be the same, returns the
+    /// innermost types that differ, such as `Int` and `String` for `List[Int]` and
+    /// `List[String]`. The variables bound before the mismatch stay bound.
+    pub(crate) fn unify(&mut self, expected: &Ty, found: &Ty) -> Result<(), Mismatch> {
+        let expected = self.shallow_resolve(expected);
+        let found = self.shallow_resolve(found);
+        match (&expected, &found) {
+            (Ty::Error, _) | (_, Ty::Error) => Ok(()),
+            (Ty::Var(a), Ty::Var(b)) if a == b => Ok(()),
+            (Ty::Var(var), other) | (other, Ty::Var(var)) => {
+                // A type can't contain itself, such as `_ = List[_]`
+                if self.resolve(other).contains_var(*var) {
+                    return Err(self.mismatch(&expected, &found));
+                }
+                self.bindings[var.0 as usize] = Some(other.clone());
+                Ok(())
+            }
+            (Ty::Int, Ty::Int)
+            | (Ty::Float, Ty::Float)
+            | (Ty::Bool, Ty::Bool)
+            | (Ty::Char, Ty::Char)
+            | (Ty::String, Ty::String) => Ok(()),
+            (Ty::List(a), Ty::List(b)) | (Ty::Option(a), Ty::Option(b)) => self.unify(a, b),
+            (Ty::Map(a_key, a_value), Ty::Map(b_key, b_value)) => {
+                self.unify(a_key, b_key)?;
+                self.unify(a_value, b_value)
+            }
+            (Ty::Tuple(a), Ty::Tuple(b)) if a.len() == b.len() => {
+                (a.iter().zip(b)).try_for_each(|(a, b)| self.unify(a, b))
+            }
+            (
+                Ty::Function { params, ret },
+                Ty::Function {
+                    params: found_params,
+                    ret: found_ret,
+                },
+            ) if params.len() == found_params.len() => {
+                (params.iter().zip(found_params)).try_for_each(|(a, b)| self.unify(a, b))?;
+                self.unify(ret, found_ret)
+            }
+            (
+                Ty::Struct { def, args, .. },
+                Ty::Struct {
+                    def: found_def,
+                    args: found_args,
+                    ..
+                },
+            ) if def == found_def && args.len() == found_args.len() => {
+                (args.iter().zip(found_args)).try_for_each(|(a, b)| self.unify(a, b))
+            }
+            (Ty::Param { def: a, .. }, Ty::Param { def: b, .. }) if a == b => Ok(()),
+            _ => Err(self.mismatch(&expected, &found)),
+        }
+    }
+
+    fn mismatch(&self, expected: &Ty, found: &Ty) -> Mismatch {
+        Mismatch {
+            expected: self.resolve(expected),
+            found: self.resolve(found),
+        }
+    }
+}
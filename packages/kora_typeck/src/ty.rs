@@ -0,0 +1,173 @@
+use std::{collections::HashMap, fmt};
+
+use kora_resolve::DefId;
+
+/// The type of a value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ty {
+    Int,
+    Float,
+    Bool,
+    Char,
+    String,
+    List(Box<Ty>),
+    Map(Box<Ty>, Box<Ty>),
+    /// The type of `none`, or of a value of the inner type.
+    Option(Box<Ty>),
+    /// A tuple, or `()` for expressions without a value, such as loops.
+    Tuple(Vec<Ty>),
+    Function {
+        params: Vec<Ty>,
+        ret: Box<Ty>,
+    },
+    Struct {
+        def: DefId,
+        name: String,
+        /// The types given to the generic parameters of the struct.
+        args: Vec<Ty>,
+    },
+    /// A generic parameter, such as `T` in `def id[T](x: T) -> T`. Inside of its function, it
+    /// is only the same as itself. Each use of the function replaces it by a new variable.
+    Param {
+        def: DefId,
+        name: String,
+    },
+    /// A type that isn't inferred yet.
+    Var(TyVar),
+    /// The type of an expression that has an error, such as an unresolved name. It fits with any
+    /// type, so that an error is reported once.
+    Error,
+}
+
+/// Identifies a type variable of the inference, see [`Ty::Var`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TyVar(pub(crate) u32);
+
+impl Ty {
+    /// The type of expressions without a value.
+    pub fn unit() -> Self {
+        Ty::Tuple(Vec::new())
+    }
+
+    /// Returns `true` if the type or one of its inner types is the variable.
+    pub(crate) fn contains_var(&self, var: TyVar) -> bool {
+        let mut found = false;
+        self.for_each_var(&mut |inner| found |= inner == var);
+        found
+    }
+
+    /// Calls the function with every type variable of the type, in order.
+    pub(crate) fn for_each_var(&self, f: &mut impl FnMut(TyVar)) {
+        match self {
+            Ty::Int | Ty::Float | Ty::Bool | Ty::Char | Ty::String => {}
+            Ty::Param { .. } | Ty::Error => {}
+            Ty::List(inner) | Ty::Option(inner) => inner.for_each_var(f),
+            Ty::Map(key, value) => {
+                key.for_each_var(f);
+                value.for_each_var(f);
+            }
+            Ty::Tuple(elements) | Ty::Struct { args: elements, .. } => {
+                elements.iter().for_each(|ty| ty.for_each_var(f))
+            }
+            Ty::Function { params, ret } => {
+                params.iter().for_each(|ty| ty.for_each_var(f));
+                ret.for_each_var(f);
+            }
+            Ty::Var(var) => f(*var),
+        }
+    }
+
+    /// Returns the type with every variable replaced by the result of the function.
+    pub(crate) fn map_vars(&self, f: &mut impl FnMut(TyVar) -> Ty) -> Ty {
+        self.replace(&mut |ty| match ty {
+            Ty::Var(var) => Some(f(*var)),
+            _ => None,
+        })
+    }
+
+    /// Returns the type with the generic parameters replaced by the types of the map.
+    pub(crate) fn substitute_params(&self, types: &HashMap<DefId, Ty>) -> Ty {
+        self.replace(&mut |ty| match ty {
+            Ty::Param { def, .. } => types.get(def).cloned(),
+            _ => None,
+        })
+    }
+
+    /// Returns the type with the inner types for which the function returns a type replaced by
+    /// that type.
+    fn replace(&self, f: &mut impl FnMut(&Ty) -> Option<Ty>) -> Ty {
+        if let Some(ty) = f(self) {
+            return ty;
+        }
+        match self {
+            Ty::Int | Ty::Float | Ty::Bool | Ty::Char | Ty::String => self.clone(),
+            Ty::Param { .. } | Ty::Var(_) | Ty::Error => self.clone(),
+            Ty::List(inner) => Ty::List(Box::new(inner.replace(f))),
+            Ty::Option(inner) => Ty::Option(Box::new(inner.replace(f))),
+            Ty::Map(key, value) => Ty::Map(Box::new(key.replace(f)), Box::new(value.replace(f))),
+            Ty::Tuple(elements) => Ty::Tuple(elements.iter().map(|ty| ty.replace(f)).collect()),
+            Ty::Function { params, ret } => Ty::Function {
+                params: params.iter().map(|ty| ty.replace(f)).collect(),
+                ret: Box::new(ret.replace(f)),
+            },
+            Ty::Struct { def, name, args } => Ty::Struct {
+                def: *def,
+                name: name.clone(),
+                args: args.iter().map(|ty| ty.replace(f)).collect(),
+            },
+        }
+    }
+}
+
+/// Types are displayed as they are written in Kora, such as `List[Int]` or `fn(Int) -> Bool`.
+/// Type variables are displayed as `_`.
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Int => write!(f, "Int"),
+            Ty::Float => write!(f, "Float"),
+            Ty::Bool => write!(f, "Bool"),
+            Ty::Char => write!(f, "Char"),
+            Ty::String => write!(f, "String"),
+            Ty::List(inner) => write!(f, "List[{inner}]"),
+            Ty::Map(key, value) => write!(f, "Map[{key}, {value}]"),
+            Ty::Option(inner) => write!(f, "Option[{inner}]"),
+            Ty::Tuple(elements) => {
+                write!(f, "(")?;
+                write_list(f, elements)?;
+                if elements.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Ty::Function { params, ret } => {
+                write!(f, "fn(")?;
+                write_list(f, params)?;
+                write!(f, ") -> {ret}")
+            }
+            Ty::Struct { name, args, .. } => {
+                write!(f, "{name}")?;
+                if !args.is_empty() {
+                    write!(f, "[")?;
+                    write_list(f, args)?;
+                    write!(f, "]")?;
+                }
+                Ok(())
+            }
+            Ty::Param { name, .. } => write!(f, "{name}"),
+            Ty::Var(_) => write!(f, "_"),
+            Ty::Error => write!(f, "{{unknown}}"),
+        }
+    }
+}
+
+/// Writes the types separated by commas.
+fn write_list(f: &mut fmt::Formatter<'_>, types: &[Ty]) -> fmt::Result {
+    for (index, ty) in types.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{ty}")?;
+    }
+    Ok(())
+}
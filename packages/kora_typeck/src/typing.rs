@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use kora_diagnostics::Diagnostic;
+use kora_lexer::Span;
+use kora_resolve::DefId;
+
+use crate::ty::Ty;
+
+/// The result of type checking: the types of the expressions and definitions of a module.
+#[derive(Debug, Clone, Default)]
+pub struct Typing {
+    /// The types of the expressions by their span. When expressions have the same span, such
+    /// as a block and its only expression, the outermost one is kept.
+    pub(crate) expr_types: HashMap<Span, Ty>,
+    /// The types of the variables, parameters, functions, structs and constants. A struct has
+    /// the type of its constructor.
+    pub(crate) def_types: HashMap<DefId, Ty>,
+    /// The methods that the names of method calls refer to, by the span of the name.
+    pub(crate) method_calls: HashMap<Span, DefId>,
+    /// Expressions whose types don't fit together.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Typing {
+    /// Returns the type of the expression at the given span. Types that couldn't be inferred
+    /// contain [`Ty::Var`].
+    pub fn expr_type(&self, span: Span) -> Option<&Ty> {
+        self.expr_types.get(&span)
+    }
+
+    /// Returns the type of the definition, or `None` for definitions without a type, such as
+    /// imports.
+    pub fn def_type(&self, def: DefId) -> Option<&Ty> {
+        self.def_types.get(&def)
+    }
+
+    /// Returns the method that the name at the given span refers to, such as `show` in
+    /// `point.show()` or `Point::show(point)`. Methods are found through the type of their
+    /// receiver, so name resolution doesn't resolve them.
+    pub fn method(&self, span: Span) -> Option<DefId> {
+        self.method_calls.get(&span).copied()
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::Write;
+
+use kora_diagnostics::{Renderer, SourceMap};
+use kora_parser::Parser;
+use kora_resolve::resolve;
+use kora_typeck::{check, Ty};
+
+/// Formats the types of the definitions of the module in the order of the code, followed by
+/// the rendered diagnostics.
+fn format_types(path: &str, source_code: &str) -> String {
+    let mut source_map = SourceMap::new();
+    let file = source_map.add_file(path, source_code);
+    let (mut module, errors) = Parser::parse_module(source_code);
+    assert_eq!(errors, []);
+    let resolution = resolve(&mut module);
+    assert!(resolution
+        .diagnostics
+        .iter()
+        .all(|diagnostic| !diagnostic.is_error()));
+    let typing = check(&module, &resolution);
+
+    let source_file = source_map.file(file);
+    let mut definitions = resolution
+        .definitions()
+        .filter_map(|(id, definition)| {
+            Some((definition.span?, &definition.name, typing.def_type(id)?))
+        })
+        .collect::<Vec<_>>();
+    definitions.sort_by_key(|(span, ..)| span.start);
+
+    let mut output = String::new();
+    for (span, name, ty) in definitions {
+        let position = source_file.position(span.start);
+        writeln!(output, "{position} {name}: {ty}").unwrap();
+    }
+    writeln!(output).unwrap();
+    for diagnostic in &typing.diagnostics {
+        writeln!(
+            output,
+            "{}",
+            Renderer::new(false).render(diagnostic, &source_map, file)
+        )
+        .unwrap();
+    }
+    output
+}
+
+#[test]
+fn test_typeck() {
+    insta::glob!("inputs/*.kora", |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        insta::assert_snapshot!(format_types(name, &input));
+    });
+}
+
+#[test]
+fn test_expr_types() {
+    let source_code = "let a = [1]\na[0] + 2";
+    let (mut module, _) = Parser::parse_module(source_code);
+    let resolution = resolve(&mut module);
+    let typing = check(&module, &resolution);
+
+    assert_eq!(
+        typing.expr_type((8..11).into()),
+        Some(&Ty::List(Box::new(Ty::Int)))
+    );
+    assert_eq!(typing.expr_type((12..16).into()), Some(&Ty::Int));
+    assert_eq!(typing.expr_type((12..20).into()), Some(&Ty::Int));
+    assert_eq!(typing.diagnostics, []);
+}
+
+#[test]
+fn test_method_calls() {
+    let source_code = "struct P {}\nextend P { def f(self) {} }\nP {}.f()\nP::f(P {})";
+    let (mut module, _) = Parser::parse_module(source_code);
+    let resolution = resolve(&mut module);
+    let typing = check(&module, &resolution);
+
+    let method = resolution.declaration((27..28).into());
+    assert!(method.is_some());
+    assert_eq!(typing.method((45..46).into()), method);
+    assert_eq!(typing.method((52..53).into()), method);
+    assert_eq!(typing.diagnostics, []);
+}
+
+#[test]
+fn test_display_types() {
+    let ty = Ty::Function {
+        params: vec![Ty::Map(Box::new(Ty::String), Box::new(Ty::Float))],
+        ret: Box::new(Ty::Tuple(vec![Ty::Option(Box::new(Ty::Char))])),
+    };
+    assert_eq!(ty.to_string(), "fn(Map[String, Float]) -> (Option[Char],)");
+    assert_eq!(Ty::unit().to_string(), "()");
+}
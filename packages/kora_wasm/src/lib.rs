@@ -0,0 +1,137 @@
+//! WebAssembly bindings for the Kora lexer, so browser playgrounds and highlighters can use the
+//! same lexer as the compiler.
+//!
+//! Spans are returned as UTF-16 offsets, because that is how JavaScript indexes strings.
+
+use kora_lexer::{Lexer, Severity, TokenKind};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Result of [`tokenize`].
+#[derive(Debug, Serialize)]
+struct Output<'source> {
+    tokens: Vec<TokenOutput<'source>>,
+    errors: Vec<ErrorOutput>,
+}
+
+#[derive(Debug, Serialize)]
+struct TokenOutput<'source> {
+    kind: TokenKind,
+    text: &'source str,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorOutput {
+    code: i32,
+    message: String,
+    severity: Severity,
+    start: usize,
+    end: usize,
+}
+
+/// Tokenizes the source code, returning an object with the `tokens`, each with its `kind`,
+/// `text`, `start` and `end`, and the `errors`, each with its `code`, `message`, `severity`,
+/// `start` and `end`.
+#[wasm_bindgen]
+pub fn tokenize(source_code: &str) -> Result<JsValue, JsError> {
+    let output = tokenize_output(source_code);
+    Ok(serde_wasm_bindgen::to_value(&output)?)
+}
+
+fn tokenize_output(source_code: &str) -> Output<'_> {
+    let (tokens, errors) = Lexer::tokenize(source_code);
+
+    // Tokens are in order, so their offsets are converted from the end of the previous one
+    let mut offsets = Utf16Offsets::new(source_code);
+    let tokens = tokens
+        .into_iter()
+        .map(|token| TokenOutput {
+            kind: token.kind,
+            text: token.text,
+            start: offsets.convert(token.span.start as usize),
+            end: offsets.convert(token.span.end as usize),
+        })
+        .collect();
+
+    let errors = errors
+        .into_iter()
+        .map(|error| ErrorOutput {
+            code: error.code(),
+            message: error.message(),
+            severity: error.severity(),
+            start: utf16_offset(source_code, error.span().start as usize),
+            end: utf16_offset(source_code, error.span().end as usize),
+        })
+        .collect();
+
+    Output { tokens, errors }
+}
+
+/// Converts a byte offset in the source code to a UTF-16 offset.
+fn utf16_offset(source_code: &str, offset: usize) -> usize {
+    source_code[..offset].encode_utf16().count()
+}
+
+/// Converts increasing byte offsets in the source code to UTF-16 offsets, only counting the code
+/// units since the previous offset.
+struct Utf16Offsets<'source> {
+    source_code: &'source str,
+    offset: usize,
+    utf16_offset: usize,
+}
+
+impl<'source> Utf16Offsets<'source> {
+    fn new(source_code: &'source str) -> Self {
+        Self {
+            source_code,
+            offset: 0,
+            utf16_offset: 0,
+        }
+    }
+
+    fn convert(&mut self, offset: usize) -> usize {
+        if offset < self.offset {
+            return utf16_offset(self.source_code, offset);
+        }
+        self.utf16_offset += utf16_offset(&self.source_code[self.offset..], offset - self.offset);
+        self.offset = offset;
+        self.utf16_offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_output_utf16_offsets() {
+        // `😀` is two UTF-16 code units, and `é` and `€` are one
+        let output = tokenize_output("\"😀\" + é €");
+
+        let tokens = output
+            .tokens
+            .iter()
+            .map(|token| (token.kind, token.text, token.start, token.end))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            [
+                (TokenKind::StringLiteral, "\"😀\"", 0, 4),
+                (TokenKind::Trivia, " ", 4, 5),
+                (TokenKind::Plus, "+", 5, 6),
+                (TokenKind::Trivia, " ", 6, 7),
+                (TokenKind::Identifier, "é", 7, 8),
+                (TokenKind::Trivia, " ", 8, 9),
+                (TokenKind::Illegal, "€", 9, 10),
+            ]
+        );
+        let errors = output
+            .errors
+            .iter()
+            .map(|error| (error.severity, error.start, error.end))
+            .collect::<Vec<_>>();
+        assert_eq!(errors, [(Severity::Error, 9, 10)]);
+    }
+}